@@ -1,24 +1,38 @@
-use std::fmt::{self, Debug, Display};
+use std::fmt::{self, Debug, Display, LowerHex, Write};
 use std::hash::{Hash, Hasher};
-use std::path::Path;
-use std::ffi::{OsStr, CStr};
+use std::path::{Path, PathBuf};
+use std::ffi::{OsStr, OsString, CStr};
 use std::os::unix::ffi::OsStrExt;
 use std::os::unix::net;
+use std::str::{self, FromStr};
+use std::convert::TryFrom;
 use std::{mem, slice};
 use std::io::{self, ErrorKind};
 
+#[cfg(target_os="linux")]
+use std::os::linux::net::SocketAddrExt;
+#[cfg(target_os="android")]
+use std::os::android::net::SocketAddrExt;
+
 use libc::{sockaddr, sa_family_t, AF_UNIX, socklen_t, sockaddr_un, c_char};
 
+#[cfg(feature="serde")]
+use serde::{Serialize, Serializer, Deserialize, Deserializer, de};
+
 /// Offset of `.sun_path` in `sockaddr_un`.
 ///
 /// This is not always identical to `mem::size_of::<sa_family_t>()`, as there
 /// be other fields before or after `.sun_family`.
-fn path_offset() -> socklen_t {
-    unsafe {
-        let total_size = mem::size_of::<sockaddr_un>();
-        let name_size = mem::size_of_val(&mem::zeroed::<sockaddr_un>().sun_path);
-        (total_size - name_size) as socklen_t
-    }
+const fn path_offset() -> socklen_t {
+    let total_size = mem::size_of::<sockaddr_un>();
+    let name_size = zeroed_sockaddr_un().sun_path.len();
+    (total_size - name_size) as socklen_t
+}
+
+/// A `const` replacement for `mem::zeroed()`, which isn't `const` before Rust 1.75.
+const fn zeroed_sockaddr_un() -> sockaddr_un {
+    // all-zeroes is a valid sockaddr_un, as it only contains integers
+    unsafe { mem::transmute([0u8; mem::size_of::<sockaddr_un>()]) }
 }
 
 fn as_u8(slice: &[c_char]) -> &[u8] {
@@ -141,16 +155,471 @@ impl Debug for UnixSocketAddr {
     }
 }
 
+/// Write `bytes` with backslash escapes for `\\`, control characters and
+/// invalid UTF-8, so that the output can be parsed back without loss.
+fn write_escaped(fmtr: &mut fmt::Formatter,  mut bytes: &[u8]) -> fmt::Result {
+    while !bytes.is_empty() {
+        let (valid, invalid) = match str::from_utf8(bytes) {
+            Ok(all) => (all, &bytes[bytes.len()..]),
+            Err(e) => {
+                let (valid, rest) = bytes.split_at(e.valid_up_to());
+                let invalid_len = e.error_len().unwrap_or(rest.len());
+                (str::from_utf8(valid).unwrap(), &rest[..invalid_len])
+            }
+        };
+        for c in valid.chars() {
+            match c {
+                '\\' => fmtr.write_str("\\\\")?,
+                '\0' => fmtr.write_str("\\0")?,
+                '\t' => fmtr.write_str("\\t")?,
+                '\n' => fmtr.write_str("\\n")?,
+                '\r' => fmtr.write_str("\\r")?,
+                c if c.is_control() => {
+                    for &b in c.encode_utf8(&mut[0; 4]).as_bytes() {
+                        write!(fmtr, "\\x{:02x}", b)?;
+                    }
+                }
+                c => fmtr.write_char(c)?,
+            }
+        }
+        for &b in invalid {
+            write!(fmtr, "\\x{:02x}", b)?;
+        }
+        bytes = &bytes[valid.len()+invalid.len()..];
+    }
+    Ok(())
+}
+
+/// Reverse `write_escaped()`.
+fn unescape(escaped: &str) -> Result<Vec<u8>, io::Error> {
+    fn hex_digit(digit: Option<u8>) -> Result<u8, io::Error> {
+        match digit.and_then(|digit| (digit as char).to_digit(16) ) {
+            Some(value) => Ok(value as u8),
+            None => Err(io::Error::new(ErrorKind::InvalidInput, "invalid \\x escape")),
+        }
+    }
+    let mut unescaped = Vec::with_capacity(escaped.len());
+    let mut bytes = escaped.bytes();
+    while let Some(b) = bytes.next() {
+        if b != b'\\' {
+            unescaped.push(b);
+            continue;
+        }
+        unescaped.push(match bytes.next() {
+            Some(b'\\') => b'\\',
+            Some(b'@') => b'@',
+            Some(b'0') => b'\0',
+            Some(b't') => b'\t',
+            Some(b'n') => b'\n',
+            Some(b'r') => b'\r',
+            Some(b'x') => {
+                let high = hex_digit(bytes.next())?;
+                let low = hex_digit(bytes.next())?;
+                high << 4 | low
+            }
+            _ => return Err(io::Error::new(ErrorKind::InvalidInput, "invalid escape sequence")),
+        });
+    }
+    Ok(unescaped)
+}
+
+/// Check whether `s` is an even number of hexadecimal digits.
+fn is_hex(s: &str) -> bool {
+    s.len() % 2 == 0  &&  s.bytes().all(|b| b.is_ascii_hexdigit() )
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>, io::Error> {
+    if hex.len() % 2 != 0 {
+        return Err(io::Error::new(ErrorKind::InvalidInput, "odd number of hex digits"));
+    }
+    hex.as_bytes().chunks(2).map(|pair| {
+        match str::from_utf8(pair).ok().and_then(|pair| u8::from_str_radix(pair, 16).ok() ) {
+            Some(b) if pair[0] != b'+' => Ok(b),
+            _ => Err(io::Error::new(ErrorKind::InvalidInput, "invalid hex digit")),
+        }
+    }).collect()
+}
+
+/// Decode `%XX` escapes in URLs.
+fn percent_decode(encoded: &str) -> Result<Vec<u8>, io::Error> {
+    let mut decoded = Vec::with_capacity(encoded.len());
+    let mut bytes = encoded.bytes();
+    while let Some(b) = bytes.next() {
+        if b != b'%' {
+            decoded.push(b);
+            continue;
+        }
+        let high = bytes.next().and_then(|digit| (digit as char).to_digit(16) );
+        let low = bytes.next().and_then(|digit| (digit as char).to_digit(16) );
+        match (high, low) {
+            (Some(high), Some(low)) => decoded.push((high << 4 | low) as u8),
+            _ => return Err(io::Error::new(ErrorKind::InvalidInput, "invalid percent-encoding")),
+        }
+    }
+    Ok(decoded)
+}
+
+/// Abstract names longer than this are truncated by the alternate `Display`.
+const REDACTED_ABSTRACT_LEN: usize = 12;
+
+/// Get the part of a path after the home directory it's inside, if any.
+fn strip_home_dir(path: &[u8]) -> Option<&[u8]> {
+    if let Some(home) = std::env::var_os("HOME") {
+        let home = home.as_bytes();
+        let home = home.strip_suffix(b"/").unwrap_or(home);
+        if !home.is_empty() && path.starts_with(home) && path.get(home.len()) == Some(&b'/') {
+            return Some(&path[home.len()+1..]);
+        }
+    }
+    // other users' home directories
+    for &parent in &[&b"/home/"[..], b"/Users/", b"/usr/home/"] {
+        if let Some(rest) = path.strip_prefix(parent) {
+            if let Some(slash) = rest.iter().position(|&b| b == b'/') {
+                return Some(&rest[slash+1..]);
+            }
+        }
+    }
+    None
+}
+
+/// FNV-1a, which unlike std's hashers is stable across Rust versions.
+fn stable_hash(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c9dc5, |hash, &b| (hash ^ b as u32).wrapping_mul(0x01000193))
+}
+
+fn write_redacted(fmtr: &mut fmt::Formatter,  addr: UnixSocketAddrRef) -> fmt::Result {
+    match addr {
+        UnixSocketAddrRef::Unnamed => fmtr.write_str("unnamed"),
+        UnixSocketAddrRef::Path(path) => {
+            let path = path.as_os_str().as_bytes();
+            match strip_home_dir(path) {
+                Some(rest) => {
+                    fmtr.write_str("~/")?;
+                    write_escaped(fmtr, rest)
+                }
+                None => write_escaped(fmtr, path),
+            }
+        }
+        UnixSocketAddrRef::Abstract(name) if name.len() > REDACTED_ABSTRACT_LEN => {
+            fmtr.write_str("@")?;
+            write_escaped(fmtr, &name[..REDACTED_ABSTRACT_LEN/2])?;
+            write!(fmtr, "...#{:08x}", stable_hash(name))
+        }
+        UnixSocketAddrRef::Abstract(name) => {
+            fmtr.write_str("@")?;
+            write_escaped(fmtr, name)
+        }
+    }
+}
+
+/// Formats the address in a way that can be parsed back with
+/// [`str::parse()`](#impl-FromStr).
+///
+/// * The unnamed address is written as `unnamed`.
+/// * Path addresses are written as-is, except that a leading `@` is escaped
+///   as `\@`, and the path `unnamed` has its first character escaped.
+/// * Abstract addresses are written as `@` followed by the name,
+///   with a leading `x` escaped as `\x78` to not be confused with the
+///   hexadecimal format.
+///
+/// In paths and abstract names, `\` is written as `\\`,
+/// NUL, tab, newline and carriage return as `\0`, `\t`, `\n` and `\r`,
+/// and other control characters and bytes that are not valid UTF-8
+/// as `\xHH`.
+///
+/// The alternate form (`{:#}`) is meant for logging, and cannot be parsed:
+/// Paths inside home directories have the home directory replaced with `~`,
+/// and abstract names longer than 12 bytes are truncated to their first
+/// six bytes followed by `...#` and a hash of the full name.
+///
+/// # Examples
+///
+#[cfg_attr(any(target_os="linux", target_os="android"), doc="```")]
+#[cfg_attr(not(any(target_os="linux", target_os="android")), doc="```no_run")]
+/// # use uds::UnixSocketAddr;
+/// let addr = UnixSocketAddr::from_abstract(b"bin\xffary\0name").unwrap();
+/// assert_eq!(addr.to_string(), "@bin\\xffary\\0name");
+/// assert_eq!(addr.to_string().parse::<UnixSocketAddr>().unwrap(), addr);
+///
+/// let addr = UnixSocketAddr::from_path("@not abstract").unwrap();
+/// assert_eq!(addr.to_string(), "\\@not abstract");
+/// assert_eq!(addr.to_string().parse::<UnixSocketAddr>().unwrap(), addr);
+/// ```
+///
+/// Redacted for logging:
+///
+#[cfg_attr(any(target_os="linux", target_os="android"), doc="```")]
+#[cfg_attr(not(any(target_os="linux", target_os="android")), doc="```no_run")]
+/// # use uds::UnixSocketAddr;
+/// let addr = UnixSocketAddr::from_abstract("session-7f3a9c2e41d8b6").unwrap();
+/// assert_eq!(format!("{:#}", addr), "@sessio...#e98aae99");
+///
+/// let addr = UnixSocketAddr::from_path("/home/alice/.app/socket").unwrap();
+/// assert_eq!(format!("{:#}", addr), "~/.app/socket");
+/// ```
 impl Display for UnixSocketAddr {
     fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        if fmtr.alternate() {
+            return write_redacted(fmtr, self.into());
+        }
         match self.into() {
             UnixSocketAddrRef::Unnamed => fmtr.write_str("unnamed"),
-            UnixSocketAddrRef::Path(path) => write!(fmtr, "{}", path.display()), // TODO check that display() doesn't print \n as-is
-            UnixSocketAddrRef::Abstract(name) => write!(fmtr, "@{}", OsStr::from_bytes(name).to_string_lossy()), // FIXME escape to sane characters
+            UnixSocketAddrRef::Path(path) => {
+                let path = path.as_os_str().as_bytes();
+                if path.first() == Some(&b'@') {
+                    fmtr.write_str("\\@")?;
+                    write_escaped(fmtr, &path[1..])
+                } else if path == b"unnamed" {
+                    fmtr.write_str("\\x75")?;
+                    write_escaped(fmtr, &path[1..])
+                } else {
+                    write_escaped(fmtr, path)
+                }
+            },
+            UnixSocketAddrRef::Abstract(name) => {
+                if name.first() == Some(&b'x') {
+                    fmtr.write_str("@\\x78")?;
+                    write_escaped(fmtr, &name[1..])
+                } else {
+                    fmtr.write_str("@")?;
+                    write_escaped(fmtr, name)
+                }
+            },
+        }
+    }
+}
+
+/// Formats abstract names as `@x` followed by the name in hexadecimal,
+/// which is useful for names that are binary data (such as hashes).
+///
+/// Path and unnamed addresses are formatted the same way as with `Display`,
+/// and the output can be parsed back with [`str::parse()`](#impl-FromStr).
+///
+/// # Examples
+///
+#[cfg_attr(any(target_os="linux", target_os="android"), doc="```")]
+#[cfg_attr(not(any(target_os="linux", target_os="android")), doc="```no_run")]
+/// # use uds::UnixSocketAddr;
+/// let addr = UnixSocketAddr::from_abstract(b"\x00\xc0\xff\xee").unwrap();
+/// assert_eq!(format!("{:x}", addr), "@x00c0ffee");
+/// assert_eq!("@x00c0ffee".parse::<UnixSocketAddr>().unwrap(), addr);
+///
+/// let path = UnixSocketAddr::from_path("/run/app.sock").unwrap();
+/// assert_eq!(format!("{:x}", path), "/run/app.sock");
+/// ```
+impl LowerHex for UnixSocketAddr {
+    fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match self.as_abstract_name() {
+            Some(name) => {
+                fmtr.write_str("@x")?;
+                for &b in name {
+                    write!(fmtr, "{:02x}", b)?;
+                }
+                Ok(())
+            }
+            None => Display::fmt(self, fmtr),
         }
     }
 }
 
+/// Parses the format produced by `Display`.
+///
+/// This differs from [`UnixSocketAddr::new()`](struct.UnixSocketAddr.html#method.new)
+/// in that backslash escapes are interpreted, `unnamed` is taken as the
+/// unnamed address, and `@x` followed by an even number of hexadecimal
+/// digits is a hexadecimal abstract name as produced by
+/// [`LowerHex`](#impl-LowerHex).
+/// Other strings starting with `@x`, such as `@xorg`, are abstract names
+/// that start with an `x`.
+///
+/// # Errors
+///
+/// * The string contains an invalid escape sequence.
+/// * Any of the errors that [`new()`](struct.UnixSocketAddr.html#method.new)
+///   can return.
+impl FromStr for UnixSocketAddr {
+    type Err = io::Error;
+    fn from_str(escaped: &str) -> Result<Self, io::Error> {
+        if escaped.is_empty() || escaped == "unnamed" {
+            Ok(UnixSocketAddr::new_unspecified())
+        } else if let Some(hex) = escaped.strip_prefix("@x").filter(|hex| is_hex(hex) ) {
+            UnixSocketAddr::from_abstract(&decode_hex(hex)?)
+        } else if let Some(name) = escaped.strip_prefix('@') {
+            UnixSocketAddr::from_abstract(&unescape(name)?)
+        } else {
+            match unescape(escaped)? {
+                ref name if name[0] == b'\0' => UnixSocketAddr::from_abstract(&name[1..]),
+                path => UnixSocketAddr::from_path(OsStr::from_bytes(&path)),
+            }
+        }
+    }
+}
+
+/// Same as [`UnixSocketAddr::new()`](struct.UnixSocketAddr.html#method.new),
+/// so a leading `@` or `'\0'` creates an abstract address and backslashes
+/// are not interpreted. (use `str::parse()` for that)
+///
+/// # Examples
+///
+/// ```
+/// # use uds::UnixSocketAddr;
+/// # use std::convert::TryFrom;
+/// fn listen_on<A>(addr: A) -> Result<UnixSocketAddr, std::io::Error>
+/// where UnixSocketAddr: TryFrom<A, Error=std::io::Error> {
+///     let addr = UnixSocketAddr::try_from(addr)?;
+///     // bind here
+///     Ok(addr)
+/// }
+/// assert!(listen_on("server.sock").unwrap().is_relative_path());
+/// assert!(listen_on(std::path::Path::new("/tmp/server.sock")).unwrap().is_absolute_path());
+/// ```
+impl<'a> TryFrom<&'a str> for UnixSocketAddr {
+    type Error = io::Error;
+    fn try_from(addr: &'a str) -> Result<Self, io::Error> {
+        UnixSocketAddr::new(addr)
+    }
+}
+
+/// Same as [`UnixSocketAddr::new()`](struct.UnixSocketAddr.html#method.new).
+impl<'a> TryFrom<&'a [u8]> for UnixSocketAddr {
+    type Error = io::Error;
+    fn try_from(addr: &'a [u8]) -> Result<Self, io::Error> {
+        UnixSocketAddr::new(addr)
+    }
+}
+
+/// Same as [`UnixSocketAddr::new()`](struct.UnixSocketAddr.html#method.new).
+impl<'a> TryFrom<&'a OsStr> for UnixSocketAddr {
+    type Error = io::Error;
+    fn try_from(addr: &'a OsStr) -> Result<Self, io::Error> {
+        UnixSocketAddr::new(addr.as_bytes())
+    }
+}
+
+/// Same as [`UnixSocketAddr::from_path()`](struct.UnixSocketAddr.html#method.from_path),
+/// so a leading `@` is part of the path.
+impl<'a> TryFrom<&'a Path> for UnixSocketAddr {
+    type Error = io::Error;
+    fn try_from(path: &'a Path) -> Result<Self, io::Error> {
+        UnixSocketAddr::from_path(path)
+    }
+}
+
+/// Same as [`UnixSocketAddr::from_c_str()`](struct.UnixSocketAddr.html#method.from_c_str).
+impl<'a> TryFrom<&'a CStr> for UnixSocketAddr {
+    type Error = io::Error;
+    fn try_from(path: &'a CStr) -> Result<Self, io::Error> {
+        UnixSocketAddr::from_c_str(path)
+    }
+}
+
+/// Lossless conversion from `std`'s address type.
+impl From<net::SocketAddr> for UnixSocketAddr {
+    fn from(addr: net::SocketAddr) -> Self {
+        if let Some(path) = addr.as_pathname() {
+            return Self::from_path(path).expect("pathname addr cannot be converted");
+        }
+        #[cfg(any(target_os="linux", target_os="android"))] {
+            if let Some(name) = addr.as_abstract_name() {
+                return Self::from_abstract(name).expect("abstract addr cannot be converted");
+            }
+        }
+        // macOS can apparently return an all-zeroes path for unnamed addresses,
+        // which std doesn't consider unnamed.
+        Self::new_unspecified()
+    }
+}
+
+/// Same as [`UnixSocketAddr::to_std()`](struct.UnixSocketAddr.html#method.to_std).
+impl TryFrom<UnixSocketAddr> for net::SocketAddr {
+    type Error = io::Error;
+    fn try_from(addr: UnixSocketAddr) -> Result<Self, io::Error> {
+        addr.to_std()
+    }
+}
+
+/// Same as [`UnixSocketAddr::to_std()`](struct.UnixSocketAddr.html#method.to_std).
+impl<'a> TryFrom<&'a UnixSocketAddr> for net::SocketAddr {
+    type Error = io::Error;
+    fn try_from(addr: &'a UnixSocketAddr) -> Result<Self, io::Error> {
+        addr.to_std()
+    }
+}
+
+/// Copies the address into socket2's address type.
+#[cfg(feature="socket2")]
+impl From<UnixSocketAddr> for socket2::SockAddr {
+    fn from(addr: UnixSocketAddr) -> Self {
+        unsafe {
+            let mut storage: libc::sockaddr_storage = mem::zeroed();
+            let dst = &mut storage as *mut libc::sockaddr_storage as *mut sockaddr_un;
+            *dst = addr.addr;
+            socket2::SockAddr::new(storage, addr.len)
+        }
+    }
+}
+
+/// Fails if the address is not an unix socket address.
+#[cfg(feature="socket2")]
+impl<'a> TryFrom<&'a socket2::SockAddr> for UnixSocketAddr {
+    type Error = io::Error;
+    fn try_from(addr: &'a socket2::SockAddr) -> Result<Self, io::Error> {
+        unsafe { UnixSocketAddr::from_raw(addr.as_ptr(), addr.len()) }
+    }
+}
+
+/// Fails if the address is not an unix socket address.
+#[cfg(feature="socket2")]
+impl TryFrom<socket2::SockAddr> for UnixSocketAddr {
+    type Error = io::Error;
+    fn try_from(addr: socket2::SockAddr) -> Result<Self, io::Error> {
+        UnixSocketAddr::try_from(&addr)
+    }
+}
+
+/// Serializes the address as the string produced by `Display`.
+///
+/// This means that paths are serialized as strings, abstract addresses as
+/// strings prefixed with `@`, and the unnamed address as `"unnamed"`.
+/// Bytes that are not valid UTF-8 are escaped.
+///
+/// # Examples
+///
+#[cfg_attr(all(feature="serde", any(target_os="linux", target_os="android")), doc="```")]
+#[cfg_attr(all(feature="serde", not(any(target_os="linux", target_os="android"))), doc="```no_run")]
+#[cfg_attr(not(feature="serde"), doc="```no_compile")]
+/// # use uds::UnixSocketAddr;
+/// let addr = UnixSocketAddr::new("@listen here").unwrap();
+/// let json = serde_json::to_string(&addr).unwrap();
+/// assert_eq!(json, r#""@listen here""#);
+/// assert_eq!(serde_json::from_str::<UnixSocketAddr>(&json).unwrap(), addr);
+/// ```
+#[cfg(feature="serde")]
+impl Serialize for UnixSocketAddr {
+    fn serialize<S: Serializer>(&self,  serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Deserializes the format produced by `Display`, as described for
+/// [`FromStr`](#impl-FromStr).
+#[cfg(feature="serde")]
+impl<'de> Deserialize<'de> for UnixSocketAddr {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct AddrVisitor;
+        impl<'de> de::Visitor<'de> for AddrVisitor {
+            type Value = UnixSocketAddr;
+            fn expecting(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+                fmtr.write_str("an unix socket address string")
+            }
+            fn visit_str<E: de::Error>(self,  escaped: &str) -> Result<UnixSocketAddr, E> {
+                escaped.parse().map_err(E::custom)
+            }
+        }
+        deserializer.deserialize_str(AddrVisitor)
+    }
+}
+
 impl UnixSocketAddr {
     /// Allows creating abstract, path or unspecified address based on an
     /// user-supplied string.
@@ -228,8 +697,8 @@ impl UnixSocketAddr {
     /// socket.bind_to_unix_addr(&addr).unwrap();
     /// assert!(socket.local_unix_addr().unwrap().is_abstract());
     /// ```
-    pub fn new_unspecified() -> Self {
-        let mut addr: sockaddr_un = unsafe { mem::zeroed() };
+    pub const fn new_unspecified() -> Self {
+        let mut addr = zeroed_sockaddr_un();
         addr.sun_family = AF_UNIX as sa_family_t;
         UnixSocketAddr {
             len: mem::size_of::<sa_family_t>() as socklen_t,
@@ -237,12 +706,74 @@ impl UnixSocketAddr {
         }
     }
 
+    /// Creates a random address that is not currently in use.
+    ///
+    /// On Linux and Android this is an abstract address with a random
+    /// hexadecimal name, similar to what auto-bind produces but longer.
+    /// On other operating systems it is a path in
+    /// [`std::env::temp_dir()`](https://doc.rust-lang.org/std/env/fn.temp_dir.html)
+    /// with a random file name, which the caller is responsible for removing
+    /// after binding to it.
+    ///
+    /// Path addresses are checked to not exist, but nothing prevents another
+    /// process from creating the same path or abstract name before the
+    /// address is bound to.
+    ///
+    /// # Errors
+    ///
+    /// * The temporary directory path is too long for an unix socket address.
+    /// * Checking whether a path exists fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use uds::{UnixSocketAddr, UnixDatagramExt};
+    /// # use std::os::unix::net::UnixDatagram;
+    /// let addr = UnixSocketAddr::new_unique().unwrap();
+    /// assert_ne!(addr, UnixSocketAddr::new_unique().unwrap());
+    /// let socket = UnixDatagram::unbound().unwrap();
+    /// socket.bind_to_unix_addr(&addr).unwrap();
+    /// assert_eq!(socket.local_unix_addr().unwrap(), addr);
+    /// # if let Some(path) = addr.as_pathname() {
+    /// #     let _ = std::fs::remove_file(path);
+    /// # }
+    /// ```
+    pub fn new_unique() -> Result<Self, io::Error> {
+        use std::collections::hash_map::RandomState;
+        use std::hash::BuildHasher;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        // RandomState is seeded randomly (but only once per thread),
+        // so mix in a counter and the time to get different values every call.
+        let random = || {
+            let mut hasher = RandomState::new().build_hasher();
+            COUNTER.fetch_add(1, Ordering::Relaxed).hash(&mut hasher);
+            std::time::SystemTime::now().hash(&mut hasher);
+            std::process::id().hash(&mut hasher);
+            hasher.finish()
+        };
+
+        if Self::has_abstract_addresses() {
+            return Self::from_abstract(format!("{:016x}", random()).as_bytes());
+        }
+        let dir = std::env::temp_dir();
+        loop {
+            let path = dir.join(format!("uds-{:016x}.socket", random()));
+            match path.symlink_metadata() {
+                Err(ref e) if e.kind() == ErrorKind::NotFound => return Self::from_path(&path),
+                Err(e) => return Err(e),
+                Ok(_) => continue,
+            }
+        }
+    }
+
     /// The maximum size of pathname addesses supported by `UnixSocketAddr`.
     ///
     /// Returns the size of the underlying `sun_path` field,
     /// minus 1 if the OS is known to require a trailing NUL (`'\0'`) byte.
-    pub fn max_path_len() -> usize {
-        mem::size_of_val(&Self::new_unspecified().addr.sun_path)
+    pub const fn max_path_len() -> usize {
+        Self::new_unspecified().addr.sun_path.len()
     }
 
     /// Create a pathname unix socket address.
@@ -260,7 +791,7 @@ impl UnixSocketAddr {
             } else if path.len() > capacity {
                 let message = "path is too long for an unix socket address";
                 Err(io::Error::new(ErrorKind::InvalidInput, message))
-            } else if path.iter().any(|&b| b == b'\0' ) {
+            } else if path.contains(&b'\0') {
                 Err(io::Error::new(ErrorKind::InvalidInput, "path cannot contain nul bytes"))
             } else {
                 for (dst, src) in addr.addr.sun_path.iter_mut().zip(path) {
@@ -276,6 +807,60 @@ impl UnixSocketAddr {
         from_path_inner(path.as_ref().as_os_str().as_bytes())
     }
 
+    /// Create a pathname unix socket address in a `const` context.
+    ///
+    /// This makes it possible to define well-known addresses as
+    /// `static`s or `const`s.
+    ///
+    /// # Panics
+    ///
+    /// Under the same conditions as [`from_path()`](#method.from_path)
+    /// returns an error, which becomes a compilation error when evaluated
+    /// at compile time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use uds::UnixSocketAddr;
+    /// static LISTEN_ADDR: UnixSocketAddr = UnixSocketAddr::from_path_const("/run/daemon.sock");
+    /// assert_eq!(LISTEN_ADDR, UnixSocketAddr::from_path("/run/daemon.sock").unwrap());
+    /// ```
+    ///
+    /// Too long paths are rejected at compile time:
+    ///
+    /// ```compile_fail
+    /// # use uds::UnixSocketAddr;
+    /// const ADDR: UnixSocketAddr = UnixSocketAddr::from_path_const(
+    ///     "/it/is/silly/to/assume/that/anybody/wants/to/type/out/such/a/long/path/name\
+    ///     /but/unix/socket/addresses/do/not/have/room/for/that/many/characters\
+    ///     /so/this/will/not/compile/anywhere",
+    /// );
+    /// # let _ = ADDR;
+    /// ```
+    pub const fn from_path_const(path: &str) -> Self {
+        let path = path.as_bytes();
+        let mut addr = Self::new_unspecified();
+        let capacity = addr.addr.sun_path.len();
+        if path.is_empty() {
+            panic!("path is empty");
+        } else if path.len() > capacity {
+            panic!("path is too long for an unix socket address");
+        }
+        let mut i = 0;
+        while i < path.len() {
+            if path[i] == b'\0' {
+                panic!("path cannot contain nul bytes");
+            }
+            addr.addr.sun_path[i] = path[i] as c_char;
+            i += 1;
+        }
+        addr.len = path_offset() + path.len() as socklen_t;
+        if path.len() < capacity {
+            addr.len += 1; // for increased portability
+        }
+        addr
+    }
+
     /// The maximum size of abstract addesses supported by `UnixSocketAddr`.
     ///
     /// Returns the size of the underlying `sun_path` field minus 1 for the
@@ -283,8 +868,8 @@ impl UnixSocketAddr {
     ///
     /// This value is also returned on operating systems that doesn't support
     /// abstract addresses.
-    pub fn max_abstract_len() -> usize {
-        mem::size_of_val(&Self::new_unspecified().addr.sun_path) - 1
+    pub const fn max_abstract_len() -> usize {
+        Self::new_unspecified().addr.sun_path.len() - 1
     }
 
     /// Whether the operating system is known to support abstract unix domain
@@ -335,19 +920,90 @@ impl UnixSocketAddr {
         from_abstract_inner(name.as_ref())
     }
 
-    /// Try to convert a `std::os::unix::net::SocketAddr` into an `UnixSocketAddr`.
+    /// Create an abstract unix domain socket address in a `const` context.
     ///
-    /// This can fail (produce `None`) on Linux and Android
-    /// if the `std` `SocketAddr` represents an abstract address,
-    /// as it provides no method for viewing abstract addresses.
-    /// (other than parsing its `Debug` output, anyway.)
+    /// This makes it possible to define well-known addresses as
+    /// `static`s or `const`s.
+    ///
+    /// # Panics
+    ///
+    /// Under the same conditions as [`from_abstract()`](#method.from_abstract)
+    /// returns an error, which becomes a compilation error when evaluated
+    /// at compile time.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(any(target_os="linux", target_os="android"), doc="```")]
+    #[cfg_attr(not(any(target_os="linux", target_os="android")), doc="```compile_fail")]
+    /// # use uds::UnixSocketAddr;
+    /// const DAEMON_ADDR: UnixSocketAddr = UnixSocketAddr::from_abstract_const(b"daemon");
+    /// assert_eq!(DAEMON_ADDR, UnixSocketAddr::from_abstract("daemon").unwrap());
+    /// ```
+    pub const fn from_abstract_const(name: &[u8]) -> Self {
+        let mut addr = Self::new_unspecified();
+        if !Self::has_abstract_addresses() {
+            panic!("abstract unix domain socket addresses are not available on this OS");
+        } else if name.len() > Self::max_abstract_len() {
+            panic!("abstract name is too long");
+        }
+        let mut i = 0;
+        while i < name.len() {
+            addr.addr.sun_path[1+i] = name[i] as c_char;
+            i += 1;
+        }
+        addr.len = path_offset() + 1 + name.len() as socklen_t;
+        addr
+    }
+
+    /// Convert a `std::os::unix::net::SocketAddr` into an `UnixSocketAddr`.
+    ///
+    /// This used to fail (produce `None`) for abstract addresses,
+    /// but now that std provides `SocketAddrExt::as_abstract_name()`
+    /// it always succeeds.
+    #[deprecated(note="use the From impl instead, which cannot fail")]
     pub fn from_std(addr: net::SocketAddr) -> Option<Self> {
-        if let Some(path) = addr.as_pathname() {
-            Some(Self::from_path(path).expect("pathname addr cannot be converted"))
-        } else if addr.is_unnamed() {
-            Some(Self::new_unspecified())
-        } else {
-            None
+        Some(Self::from(addr))
+    }
+
+    /// Convert the address into a `std::os::unix::net::SocketAddr`.
+    ///
+    /// Abstract addresses are converted with std's `SocketAddrExt`,
+    /// and the unnamed address is obtained from an unbound datagram socket,
+    /// as std has no constructor for it.
+    ///
+    /// # Errors
+    ///
+    /// Converting the unnamed address creates and closes a socket,
+    /// so it fails if the process or system is out of file descriptors
+    /// (`EMFILE` or `ENFILE`), or if creating sockets is otherwise denied.
+    ///
+    /// Also fails if std rejects the address, or with an error of kind
+    /// `AddrNotAvailable` for abstract addresses on OSes where std doesn't
+    /// support them.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(any(target_os="linux", target_os="android"), doc="```")]
+    #[cfg_attr(not(any(target_os="linux", target_os="android")), doc="```no_run")]
+    /// # use uds::UnixSocketAddr;
+    /// use std::os::unix::net::UnixDatagram;
+    ///
+    /// let addr = UnixSocketAddr::new("@to_std example").unwrap();
+    /// let socket = UnixDatagram::bind_addr(&addr.to_std().unwrap()).unwrap();
+    /// let local = UnixSocketAddr::from(socket.local_addr().unwrap());
+    /// assert_eq!(local, addr);
+    /// ```
+    pub fn to_std(&self) -> Result<net::SocketAddr, io::Error> {
+        match self.as_ref() {
+            UnixSocketAddrRef::Path(path) => net::SocketAddr::from_pathname(path),
+            UnixSocketAddrRef::Unnamed => net::UnixDatagram::unbound()?.local_addr(),
+            #[cfg(any(target_os="linux", target_os="android"))]
+            UnixSocketAddrRef::Abstract(name) => net::SocketAddr::from_abstract_name(name),
+            #[cfg(not(any(target_os="linux", target_os="android")))]
+            UnixSocketAddrRef::Abstract(_) => Err(io::Error::new(
+                    ErrorKind::AddrNotAvailable,
+                    "abstract addresses are not supported by std on this OS"
+            )),
         }
     }
 
@@ -379,6 +1035,68 @@ impl UnixSocketAddr {
         }
     }
 
+    /// Parse an unix socket URL as used by many proxies and service
+    /// configuration files.
+    ///
+    /// Supported forms:
+    ///
+    /// * `unix:///absolute/path` and `unix:/absolute/path`: absolute path addresses.
+    /// * `unix:relative/path`: relative path addresses.
+    /// * `unix-abstract://name`, `unix-abstract:name` and `unix:@name`:
+    ///   abstract addresses.
+    ///
+    /// `%XX` escapes are decoded in both paths and names.
+    ///
+    /// # Errors
+    ///
+    /// * The scheme is not `unix:` or `unix-abstract:`.
+    /// * A `unix:` URL has a host, as in `unix://host/path`.
+    /// * The path or name is empty or contains invalid `%` escapes.
+    /// * Any of the reasons [`from_path()`](#method.from_path) or
+    ///   [`from_abstract()`](#method.from_abstract) can fail for.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(any(target_os="linux", target_os="android"), doc="```")]
+    #[cfg_attr(not(any(target_os="linux", target_os="android")), doc="```no_run")]
+    /// # use uds::UnixSocketAddr;
+    /// let path = UnixSocketAddr::from_url("unix:///run/app.sock").unwrap();
+    /// assert_eq!(path, UnixSocketAddr::from_path("/run/app.sock").unwrap());
+    /// let name = UnixSocketAddr::from_url("unix-abstract://app%00control").unwrap();
+    /// assert_eq!(name, UnixSocketAddr::from_abstract(b"app\0control").unwrap());
+    /// assert_eq!(UnixSocketAddr::from_url("unix:@app").unwrap().as_abstract_name(), Some(&b"app"[..]));
+    /// assert!(UnixSocketAddr::from_url("http://localhost/").is_err());
+    /// ```
+    pub fn from_url(url: &str) -> Result<Self, io::Error> {
+        let (is_abstract, rest) = if let Some(rest) = url.strip_prefix("unix-abstract:") {
+            (true, rest.strip_prefix("//").unwrap_or(rest))
+        } else if let Some(rest) = url.strip_prefix("unix:") {
+            match (rest.strip_prefix('@'), rest.strip_prefix("//")) {
+                (Some(name), _) => (true, name),
+                // the authority must be empty, as "unix://host/path" doesn't
+                // refer to the relative path "host/path"
+                (None, Some(after)) if !after.is_empty() && !after.starts_with('/') => {
+                    let message = "unix: URLs cannot have a host";
+                    return Err(io::Error::new(ErrorKind::InvalidInput, message));
+                }
+                (None, Some(after)) => (false, after),
+                (None, None) => (false, rest),
+            }
+        } else {
+            let message = "unsupported URL scheme, expected unix: or unix-abstract:";
+            return Err(io::Error::new(ErrorKind::InvalidInput, message));
+        };
+        if rest.is_empty() {
+            return Err(io::Error::new(ErrorKind::InvalidInput, "URL has no path or name"));
+        }
+        let decoded = percent_decode(rest)?;
+        if is_abstract {
+            Self::from_abstract(&decoded)
+        } else {
+            Self::from_path(OsStr::from_bytes(&decoded))
+        }
+    }
+
     pub fn is_unnamed(&self) -> bool {
         if Self::has_abstract_addresses() {
             self.len <= path_offset()
@@ -395,6 +1113,33 @@ impl UnixSocketAddr {
             false
         }
     }
+    /// Whether the address looks like one assigned by Linux' auto-bind.
+    ///
+    /// The kernel auto-binds sockets that are bound to the unnamed address,
+    /// or that send or connect without being bound when `SO_PASSCRED` is set,
+    /// to an abstract name consisting of exactly five lowercase hexadecimal
+    /// digits.  
+    /// There is nothing preventing a socket from explicitly binding to such
+    /// a name, so this is only a heuristic.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(any(target_os="linux", target_os="android"), doc="```")]
+    #[cfg_attr(not(any(target_os="linux", target_os="android")), doc="```no_run")]
+    /// # use uds::{UnixSocketAddr, UnixDatagramExt};
+    /// # use std::os::unix::net::UnixDatagram;
+    /// let socket = UnixDatagram::unbound().unwrap();
+    /// socket.bind_to_unix_addr(&UnixSocketAddr::new_unspecified()).unwrap();
+    /// assert!(socket.local_unix_addr().unwrap().is_autobound());
+    /// assert!(!UnixSocketAddr::new("@named").unwrap().is_autobound());
+    /// ```
+    pub fn is_autobound(&self) -> bool {
+        match self.as_abstract_name() {
+            Some(name) => name.len() == 5
+                && name.iter().all(|&b| matches!(b, b'0'..=b'9' | b'a'..=b'f')),
+            None => false,
+        }
+    }
     pub fn is_absolute_path(&self) -> bool {
         self.len > path_offset()  &&  self.addr.sun_path[0] as u8 == b'/'
     }
@@ -409,10 +1154,174 @@ impl UnixSocketAddr {
 
     /// Get a view that can be pattern matched to the differnt types of
     /// addresses.
-    pub fn as_ref(&self) -> UnixSocketAddrRef {
+    pub fn as_ref(&self) -> UnixSocketAddrRef<'_> {
         UnixSocketAddrRef::from(self)
     }
 
+    /// Get the path of a path address.
+    ///
+    /// Returns `None` for unnamed and abstract addresses,
+    /// like `std`'s `SocketAddr::as_pathname()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use uds::UnixSocketAddr;
+    /// # use std::path::Path;
+    /// let addr = UnixSocketAddr::from_path("/var/run/socket.sock").unwrap();
+    /// assert_eq!(addr.as_pathname(), Some(Path::new("/var/run/socket.sock")));
+    /// assert_eq!(UnixSocketAddr::new_unspecified().as_pathname(), None);
+    /// ```
+    pub fn as_pathname(&self) -> Option<&Path> {
+        match self.as_ref() {
+            UnixSocketAddrRef::Path(path) => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Make a relative path address absolute by prepending the current
+    /// working directory.
+    ///
+    /// Relative path addresses are resolved by the OS when binding or
+    /// connecting, so they refer to a different socket once the process
+    /// changes directory.
+    /// Absolute path, abstract and unnamed addresses are returned unchanged,
+    /// and so is a relative path that would become too long.  
+    /// Symbolic links and `..` components are not resolved, so the socket
+    /// file doesn't need to exist.
+    ///
+    /// # Errors
+    ///
+    /// If the current directory cannot be determined.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use uds::UnixSocketAddr;
+    /// let addr = UnixSocketAddr::from_path("relative.sock").unwrap();
+    /// let absolute = addr.canonicalize().unwrap();
+    /// assert!(absolute.is_absolute_path());
+    /// assert_eq!(
+    ///     absolute.as_pathname().unwrap(),
+    ///     std::env::current_dir().unwrap().join("relative.sock")
+    /// );
+    /// ```
+    pub fn canonicalize(&self) -> Result<Self, io::Error> {
+        let relative = match self.as_pathname() {
+            Some(path) if path.is_relative() => path,
+            _ => return Ok(*self),
+        };
+        let path = std::env::current_dir()?.join(relative);
+        if path.as_os_str().len() > Self::max_path_len() {
+            return Ok(*self);
+        }
+        Self::from_path(&path)
+    }
+
+    /// Get the name of an abstract address, without the leading `'\0'`.
+    ///
+    /// Returns `None` for unnamed and path addresses.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(any(target_os="linux", target_os="android"), doc="```")]
+    #[cfg_attr(not(any(target_os="linux", target_os="android")), doc="```no_run")]
+    /// # use uds::UnixSocketAddr;
+    /// let addr = UnixSocketAddr::new("@abstract").unwrap();
+    /// assert_eq!(addr.as_abstract_name(), Some(&b"abstract"[..]));
+    /// assert_eq!(UnixSocketAddr::new("path").unwrap().as_abstract_name(), None);
+    /// ```
+    pub fn as_abstract_name(&self) -> Option<&[u8]> {
+        match self.as_ref() {
+            UnixSocketAddrRef::Abstract(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Get the name portion of the address as bytes.
+    ///
+    /// This is the path without any trailing NUL byte for path addresses,
+    /// the name prefixed with `'\0'` for abstract addresses,
+    /// and empty for the unnamed address.  
+    /// It is the same format that [`new()`](#method.new) accepts and that
+    /// `UnixSocketAddr` can be compared with (`PartialEq<[u8]>`),
+    /// and is stable across operating systems and crate versions.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(any(target_os="linux", target_os="android"), doc="```")]
+    #[cfg_attr(not(any(target_os="linux", target_os="android")), doc="```no_run")]
+    /// # use uds::UnixSocketAddr;
+    /// let path = UnixSocketAddr::from_path("/run/app.sock").unwrap();
+    /// assert_eq!(path.as_bytes(), b"/run/app.sock");
+    /// let name = UnixSocketAddr::from_abstract("app").unwrap();
+    /// assert_eq!(name.as_bytes(), b"\0app");
+    /// assert_eq!(UnixSocketAddr::new(name.as_bytes()).unwrap(), name);
+    /// assert_eq!(UnixSocketAddr::new_unspecified().as_bytes(), b"");
+    /// ```
+    pub fn as_bytes(&self) -> &[u8] {
+        match self.as_ref() {
+            UnixSocketAddrRef::Unnamed => &[],
+            UnixSocketAddrRef::Path(path) => path.as_os_str().as_bytes(),
+            UnixSocketAddrRef::Abstract(name) => as_u8(&self.addr.sun_path[..1+name.len()]),
+        }
+    }
+
+    /// Get the length in bytes of the raw address, including the fields
+    /// before `.sun_path` and any trailing NUL byte of paths.
+    ///
+    /// This is the length passed to the OS, as returned by
+    /// [`as_raw()`](#method.as_raw).
+    pub fn len_bytes(&self) -> usize {
+        self.len as usize
+    }
+
+    /// Whether this is an abstract address whose name starts with `prefix`.
+    ///
+    /// The prefix doesn't include the leading `'\0'`, and the empty prefix
+    /// matches all abstract addresses.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(any(target_os="linux", target_os="android"), doc="```")]
+    #[cfg_attr(not(any(target_os="linux", target_os="android")), doc="```no_run")]
+    /// # use uds::UnixSocketAddr;
+    /// let addr = UnixSocketAddr::new("@myapp/worker/1").unwrap();
+    /// assert!(addr.abstract_starts_with(b"myapp/"));
+    /// assert!(!addr.abstract_starts_with(b"otherapp/"));
+    /// assert!(!UnixSocketAddr::new("myapp/path").unwrap().abstract_starts_with(b"myapp/"));
+    /// ```
+    pub fn abstract_starts_with(&self, prefix: &[u8]) -> bool {
+        self.strip_abstract_prefix(prefix).is_some()
+    }
+
+    /// Get the rest of the name of an abstract address that starts with
+    /// `prefix`.
+    ///
+    /// Returns `None` for unnamed and path addresses, and for abstract
+    /// addresses that don't start with `prefix`.
+    ///
+    /// # Examples
+    ///
+    /// Routing peers based on their address:
+    ///
+    #[cfg_attr(any(target_os="linux", target_os="android"), doc="```")]
+    #[cfg_attr(not(any(target_os="linux", target_os="android")), doc="```no_run")]
+    /// # use uds::UnixSocketAddr;
+    /// let peer = UnixSocketAddr::new("@myapp/worker/1").unwrap();
+    /// match peer.strip_abstract_prefix(b"myapp/") {
+    ///     Some(b"control") => println!("control client"),
+    ///     Some(worker) => assert_eq!(worker, b"worker/1"),
+    ///     None => panic!("unexpected peer {}", peer),
+    /// }
+    /// ```
+    pub fn strip_abstract_prefix(&self, prefix: &[u8]) -> Option<&[u8]> {
+        match self.as_abstract_name() {
+            Some(name) if name.starts_with(prefix) => Some(&name[prefix.len()..]),
+            _ => None,
+        }
+    }
+
     /// Prepare a `struct sockaddr*` and `socklen_t*` for passing to FFI
     /// (such as `getsockname()`, `getpeername()`, or `accept()`),
     /// and validate and normalize the produced address afterwards.
@@ -439,6 +1348,7 @@ impl UnixSocketAddr {
                     "file descriptor did not correspond to a Unix socket" // identical to std's
                 ));
             }
+            addr.normalize_sun_len();
             if addr.is_abstract() {
                 if addr.len > capacity {
                     return Err(io::Error::new(
@@ -465,6 +1375,48 @@ impl UnixSocketAddr {
         }
     }
 
+    /// Set the `.sun_len` field that macOS and the BSDs have to the length
+    /// of the address.
+    ///
+    /// The OS ignores the field and uses the separately passed length,
+    /// but some libraries inspect it.
+    /// This method does nothing on operating systems without the field.
+    ///
+    /// Addresses produced by this crate have `.sun_len` set to zero,
+    /// and it is cleared again by functions that receive an address from the OS.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use uds::UnixSocketAddr;
+    /// let mut addr = UnixSocketAddr::from_path("/tmp/legacy.sock").unwrap();
+    /// let copy = addr;
+    /// addr.set_sun_len();
+    /// assert_eq!(addr, copy);
+    /// #[cfg(any(target_vendor="apple", target_os="freebsd", target_os="netbsd"))]
+    /// assert_eq!(addr.as_raw().0.sun_len as libc::socklen_t, addr.as_raw().1);
+    /// ```
+    pub fn set_sun_len(&mut self) {
+        #[cfg(any(target_vendor="apple", target_os="freebsd", target_os="dragonfly",
+                  target_os="netbsd", target_os="openbsd"))] {
+            self.addr.sun_len = self.len as u8;
+        }
+    }
+
+    /// Use `.sun_len` (on OSes that have it) if it is set and smaller than
+    /// `len`, and then clear it so that addresses compare identically
+    /// regardless of whether the OS or another library filled it in.
+    pub(crate) fn normalize_sun_len(&mut self) {
+        #[cfg(any(target_vendor="apple", target_os="freebsd", target_os="dragonfly",
+                  target_os="netbsd", target_os="openbsd"))] {
+            let sun_len = self.addr.sun_len as socklen_t;
+            if sun_len >= path_offset() && sun_len < self.len {
+                self.len = sun_len;
+            }
+            self.addr.sun_len = 0;
+        }
+    }
+
     /// Create an `UnixSocketAddr` from a pointer to a generic `sockaddr` and
     /// a length.
     ///
@@ -487,9 +1439,15 @@ impl UnixSocketAddr {
         } else {
             let addr = addr as *const sockaddr_un;
             let sun_path_ptr = (&*addr).sun_path.as_ptr();
-            let sun_path = slice::from_raw_parts(sun_path_ptr, len as usize);
-            copy.addr.sun_path.copy_from_slice(sun_path);
+            let path_len = (len - path_offset()) as usize;
+            let sun_path = slice::from_raw_parts(sun_path_ptr, path_len);
+            copy.addr.sun_path[..path_len].copy_from_slice(sun_path);
             copy.len = len;
+            #[cfg(any(target_vendor="apple", target_os="freebsd", target_os="dragonfly",
+                      target_os="netbsd", target_os="openbsd"))] {
+                copy.addr.sun_len = (&*addr).sun_len;
+            }
+            copy.normalize_sun_len();
             Ok(copy)
         }
     }
@@ -547,6 +1505,94 @@ impl UnixSocketAddr {
     }
 }
 
+/// Types that can be converted into an [`UnixSocketAddr`](struct.UnixSocketAddr.html),
+/// accepted by the functions in this crate that bind or connect sockets.
+///
+/// It is analogous to `std::net::ToSocketAddrs`, but always produces one address.
+///
+/// * Strings are parsed with [`UnixSocketAddr::new()`](struct.UnixSocketAddr.html#method.new),
+///   so a leading `@` makes them abstract addresses and the empty string is
+///   the unnamed address.
+/// * `Path`s, `OsStr`s and their owned variants are always path addresses.
+///
+/// # Examples
+///
+#[cfg_attr(not(target_vendor="apple"), doc="```")]
+#[cfg_attr(target_vendor="apple", doc="```no_run")]
+/// use uds::{UnixSeqpacketListener, UnixSeqpacketConn, UnixSocketAddr};
+/// use std::path::Path;
+///
+/// # let _ = std::fs::remove_file("to_unix_addr.socket");
+/// let listener = UnixSeqpacketListener::bind(Path::new("to_unix_addr.socket")).unwrap();
+/// let _conn = UnixSeqpacketConn::connect("to_unix_addr.socket").unwrap();
+/// let addr = UnixSocketAddr::from_path("to_unix_addr.socket").unwrap();
+/// let _conn = UnixSeqpacketConn::connect_unix_addr(&addr).unwrap();
+/// let _conn = UnixSeqpacketConn::connect(addr.as_ref()).unwrap();
+/// # std::fs::remove_file("to_unix_addr.socket").unwrap();
+/// ```
+pub trait ToUnixSocketAddr {
+    /// Create the address.
+    fn to_unix_addr(&self) -> Result<UnixSocketAddr, io::Error>;
+}
+
+impl ToUnixSocketAddr for UnixSocketAddr {
+    fn to_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+        Ok(*self)
+    }
+}
+
+impl<'a> ToUnixSocketAddr for UnixSocketAddrRef<'a> {
+    fn to_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+        match *self {
+            UnixSocketAddrRef::Unnamed => Ok(UnixSocketAddr::new_unspecified()),
+            UnixSocketAddrRef::Path(path) => UnixSocketAddr::from_path(path),
+            UnixSocketAddrRef::Abstract(name) => UnixSocketAddr::from_abstract(name),
+        }
+    }
+}
+
+impl ToUnixSocketAddr for str {
+    fn to_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+        UnixSocketAddr::new(self)
+    }
+}
+
+impl ToUnixSocketAddr for String {
+    fn to_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+        UnixSocketAddr::new(self)
+    }
+}
+
+impl ToUnixSocketAddr for Path {
+    fn to_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+        UnixSocketAddr::from_path(self)
+    }
+}
+
+impl ToUnixSocketAddr for PathBuf {
+    fn to_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+        UnixSocketAddr::from_path(self)
+    }
+}
+
+impl ToUnixSocketAddr for OsStr {
+    fn to_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+        UnixSocketAddr::from_path(self)
+    }
+}
+
+impl ToUnixSocketAddr for OsString {
+    fn to_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+        UnixSocketAddr::from_path(self)
+    }
+}
+
+impl<T: ToUnixSocketAddr+?Sized> ToUnixSocketAddr for &T {
+    fn to_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+        (**self).to_unix_addr()
+    }
+}
+
 impl Default for UnixSocketAddr {
     fn default() -> Self {
         Self::new_unspecified()
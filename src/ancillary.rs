@@ -1,25 +1,34 @@
 use std::ops::{Deref, DerefMut};
 use std::borrow::{Borrow, BorrowMut};
-use std::os::unix::io::RawFd;
+use std::os::unix::io::{RawFd, AsRawFd, AsFd, OwnedFd, FromRawFd};
 use std::io::{self, ErrorKind, IoSlice, IoSliceMut};
 use std::alloc::{self, Layout};
 use std::convert::TryInto;
-use std::{mem, ptr, slice};
+use std::{fmt, mem, ptr, slice};
 use std::marker::PhantomData;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use libc::{c_int, c_uint, c_void};
 use libc::{socklen_t, msghdr, iovec, sockaddr_un, cmsghdr};
 use libc::{sendmsg, recvmsg, close};
 //#[cfg(not(any(target_os="illumos", target_os="solaris")))]
-use libc::{MSG_TRUNC, MSG_CTRUNC};
+use libc::{MSG_TRUNC, MSG_CTRUNC, MSG_EOR};
 #[cfg(not(any(target_os="illumos", target_os="solaris")))]
 use libc::{CMSG_SPACE, CMSG_LEN, CMSG_DATA, CMSG_FIRSTHDR, CMSG_NXTHDR};
 //#[cfg(not(any(target_os="illumos", target_os="solaris")))]
 use libc::{SOL_SOCKET, SCM_RIGHTS};
 #[cfg(any(target_os="linux", target_os="android"))]
 use libc::SCM_CREDENTIALS;
+#[cfg(any(target_os="freebsd", target_os="netbsd"))]
+use libc::{SCM_CREDS, sockcred, gid_t};
+#[cfg(not(any(target_os="illumos", target_os="solaris")))]
+use libc::{SCM_TIMESTAMP, timeval};
+#[cfg(any(target_os="linux", target_os="android"))]
+use libc::{SCM_TIMESTAMPNS, timespec};
 #[cfg(not(any(target_vendor="apple", target_os="illumos", target_os="solaris")))]
 use libc::MSG_CMSG_CLOEXEC;
+#[cfg(any(target_os="linux", target_os="android"))]
+use libc::{mmsghdr, sendmmsg, recvmmsg, MSG_WAITFORONE};
 
 use crate::helpers::*;
 use crate::UnixSocketAddr;
@@ -32,6 +41,108 @@ type ControlLen = usize;
 #[cfg(not(all(target_os="linux", target_env="gnu")))]
 type ControlLen = libc::socklen_t;
 
+/// Like `send_ancillary()` without credentials, but with file descriptors
+/// from any type that implements `AsFd`.
+pub fn send_as_fds<I>(
+    socket: RawFd,  to: Option<&UnixSocketAddr>,  flags: c_int,
+    bytes: &[IoSlice],  fds: I,
+) -> Result<usize, io::Error>
+where I: IntoIterator, I::Item: AsFd {
+    // keep the items alive until sent, in case they are owned
+    let fds = fds.into_iter().collect::<Vec<_>>();
+    let raw_fds = fds.iter().map(|fd| fd.as_fd().as_raw_fd() ).collect::<Vec<_>>();
+    send_ancillary(socket, to, flags, bytes, &raw_fds, None)
+}
+
+/// A builder for sending a packet together with any combination of file
+/// descriptors and credentials.
+///
+/// Created by `send_ancillary()` on the seqpacket connection types,
+/// and sent with [`send()`](#method.send).
+///
+/// # Examples
+///
+#[cfg_attr(not(target_vendor="apple"), doc="```")]
+#[cfg_attr(target_vendor="apple", doc="```no_run")]
+/// use uds::{UnixSeqpacketConn, SendCredentials};
+/// use std::io::IoSlice;
+/// use std::os::unix::io::AsRawFd;
+///
+/// let (a, b) = UnixSeqpacketConn::pair().unwrap();
+/// let file = std::fs::File::open("README.md").unwrap();
+/// a.send_ancillary()
+///     .iovec(&[IoSlice::new(b"here's "), IoSlice::new(b"a file")])
+///     .fds(&[file.as_raw_fd()])
+///     .credentials(SendCredentials::Effective)
+///     .send()
+///     .expect("send packet with ancillary data");
+///
+/// let mut fds = [-1; 2];
+/// let (len, _, received_fds) = b.recv_fds(&mut[0; 20], &mut fds).unwrap();
+/// assert_eq!((len, received_fds), (13, 1));
+/// ```
+#[must_use = "nothing is sent until send() is called"]
+#[derive(Debug)]
+pub struct SendAncillary<'a> {
+    socket: RawFd,
+    flags: c_int,
+    single: [IoSlice<'a>; 1],
+    vectored: Option<&'a [IoSlice<'a>]>,
+    fds: &'a [RawFd],
+    creds: Option<SendCredentials>,
+}
+
+impl<'a> SendAncillary<'a> {
+    pub(crate) fn new(socket: RawFd,  flags: c_int) -> Self {
+        SendAncillary {
+            socket,
+            flags,
+            single: [IoSlice::new(&[])],
+            vectored: None,
+            fds: &[],
+            creds: None,
+        }
+    }
+
+    /// Set the content of the packet.
+    ///
+    /// Replaces any slices set with [`iovec()`](#method.iovec).
+    pub fn bytes(mut self,  bytes: &'a [u8]) -> Self {
+        self.single = [IoSlice::new(bytes)];
+        self.vectored = None;
+        self
+    }
+
+    /// Set the content of the packet from multiple slices.
+    ///
+    /// Replaces any content set with [`bytes()`](#method.bytes).
+    pub fn iovec(mut self,  slices: &'a [IoSlice<'a>]) -> Self {
+        self.vectored = Some(slices);
+        self
+    }
+
+    /// File descriptors to send with the packet.
+    pub fn fds(mut self,  fds: &'a [RawFd]) -> Self {
+        self.fds = fds;
+        self
+    }
+
+    /// Credentials to send with the packet.
+    ///
+    /// This is only necessary on Linux and Android, and is ignored on other
+    /// operating systems.
+    pub fn credentials(mut self,  creds: SendCredentials) -> Self {
+        self.creds = Some(creds);
+        self
+    }
+
+    /// Send the packet, and return the number of content bytes sent.
+    pub fn send(self) -> Result<usize, io::Error> {
+        let bytes = self.vectored.unwrap_or(&self.single);
+        send_ancillary(self.socket, None, self.flags, bytes, self.fds, self.creds)
+    }
+}
+
 /// Safe wrapper around `sendmsg()`.
 pub fn send_ancillary(
     socket: RawFd,  to: Option<&UnixSocketAddr>,  flags: c_int,
@@ -64,17 +175,17 @@ pub fn send_ancillary(
         #[cfg(any(target_os="linux", target_os="android"))]
         let creds = creds.map(|creds| {
             let creds = creds.into_raw();
-            needed_capacity += CMSG_LEN(mem::size_of_val(&creds) as u32);
+            needed_capacity += CMSG_SPACE(mem::size_of_val(&creds) as u32);
             creds
         });
-        if fds.len() > 0 {
+        if !fds.is_empty() {
             if fds.len() > 0xff_ff_ff {
                 // need to prevent truncation.
                 // I use a lower limit in case the macros don't handle overflow.
                 return Err(io::Error::new(ErrorKind::InvalidInput, "too many file descriptors"));
             }
             #[cfg(not(any(target_os="illumos", target_os="solaris")))] {
-                needed_capacity += CMSG_LEN(mem::size_of_val::<[RawFd]>(fds) as u32);
+                needed_capacity += CMSG_SPACE(mem::size_of_val::<[RawFd]>(fds) as u32);
             }
             #[cfg(any(target_os="illumos", target_os="solaris"))] {
                 return Err(io::Error::new(
@@ -100,18 +211,21 @@ pub fn send_ancillary(
             }
 
             #[cfg(not(any(target_os="illumos", target_os="solaris")))] {
-                let mut header = &mut*CMSG_FIRSTHDR(&mut msg);
+                let mut header = &mut*CMSG_FIRSTHDR(&msg);
                 #[cfg(any(target_os="linux", target_os="android"))] {
                     if let Some(creds) = creds {
                         header.cmsg_level = SOL_SOCKET;
                         header.cmsg_type = SCM_CREDENTIALS;
                         header.cmsg_len = CMSG_LEN(mem::size_of_val(&creds) as u32) as ControlLen;
                         *(CMSG_DATA(header) as *mut _) = creds;
-                        header = &mut*CMSG_NXTHDR(&mut msg, header);
+                        // CMSG_NXTHDR() returns NULL if there is no room for another message
+                        if !fds.is_empty() {
+                            header = &mut*CMSG_NXTHDR(&msg, header);
+                        }
                     }
                 }
 
-                if fds.len() > 0 {
+                if !fds.is_empty() {
                     header.cmsg_level = SOL_SOCKET;
                     header.cmsg_type = SCM_RIGHTS;
                     header.cmsg_len = CMSG_LEN(mem::size_of_val(fds) as u32) as ControlLen;
@@ -128,7 +242,7 @@ pub fn send_ancillary(
             alloc::dealloc(msg.msg_control as *mut u8, layout);
         }
 
-        result.map(|sent| sent as usize )
+        result.map(|sent| sent as usize ).map_err(explain_too_big)
     }
 }
 
@@ -137,6 +251,45 @@ pub fn send_ancillary(
 /// An ancillary data buffer that supports any capacity.
 ///
 /// For reasonable ancillary capacities it uses a stack-based array.
+///
+/// The buffer doesn't need to be cleared between receives, so creating one
+/// and reusing it avoids allocating (for big capacities) and zeroing
+/// a new buffer for every received message.
+///
+/// # Examples
+///
+/// Receiving many packets with file descriptors:
+///
+#[cfg_attr(not(target_vendor="apple"), doc="```")]
+#[cfg_attr(target_vendor="apple", doc="```no_run")]
+/// use std::fs::File;
+/// use std::io::IoSliceMut;
+/// use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd};
+/// use uds::{UnixSeqpacketConn, AncillaryBuf, AncillaryMessage};
+///
+/// let (a, b) = UnixSeqpacketConn::pair().unwrap();
+/// let file = File::open("README.md").unwrap();
+/// for _ in 0..10 {
+///     a.send_fds(b"fd", &[file.as_raw_fd()]).unwrap();
+/// }
+///
+/// let mut ancillary_buf = AncillaryBuf::with_fd_capacity(4);
+/// let mut received = Vec::new();
+/// for _ in 0..10 {
+///     let mut buf = [0; 10];
+///     let (len, ancillary) = b.recv_vectored_ancillary(
+///         &mut[IoSliceMut::new(&mut buf)],
+///         &mut ancillary_buf,
+///     ).unwrap();
+///     assert_eq!(len, 2);
+///     for message in ancillary {
+///         if let AncillaryMessage::Fds(fds) = message {
+///             received.extend(fds.iter().map(|&fd| unsafe { OwnedFd::from_raw_fd(fd) } ));
+///         }
+///     }
+/// }
+/// assert_eq!(received.len(), 10);
+/// ```
 #[repr(C)]
 pub struct AncillaryBuf {
     capacity: ControlLen,
@@ -152,14 +305,22 @@ impl Drop for AncillaryBuf {
                     self.capacity as usize,
                     mem::align_of::<cmsghdr>()
                 ).unwrap();
-                alloc::dealloc(self.ptr as *mut u8, layout);
+                alloc::dealloc(self.ptr, layout);
             }
         }
     }
 }
 impl AncillaryBuf {
+    /// Buffers up to this size are stored inline instead of on the heap.
     pub const MAX_STACK_CAPACITY: usize = 256;
-    pub const MAX_CAPACITY: usize = ControlLen::max_value() as usize;
+    /// The biggest buffer the OS supports.
+    pub const MAX_CAPACITY: usize = ControlLen::MAX as usize;
+    /// Create a buffer of `bytes` bytes.
+    ///
+    /// # Panics
+    ///
+    /// If `bytes` is greater than `MAX_CAPACITY`.
+    #[allow(clippy::match_overlapping_arm)] // first match wins
     pub fn with_capacity(bytes: usize) -> Self {
         Self {
             capacity: bytes as ControlLen,
@@ -170,7 +331,11 @@ impl AncillaryBuf {
                         bytes as usize,
                         mem::align_of::<cmsghdr>()
                     ).unwrap();
-                    alloc::alloc(layout)
+                    let ptr = alloc::alloc(layout);
+                    if ptr.is_null() {
+                        alloc::handle_alloc_error(layout);
+                    }
+                    ptr
                 },
                 _ => panic!("capacity is too high"),
             },
@@ -178,36 +343,111 @@ impl AncillaryBuf {
             on_stack: [0; Self::MAX_STACK_CAPACITY],
         }
     }
+    /// Create a buffer that is big enough for receiving `num_fds` file
+    /// descriptors in one message.
+    ///
+    /// # Panics
+    ///
+    /// If the buffer would be bigger than `MAX_CAPACITY`.
     pub fn with_fd_capacity(num_fds: usize) -> Self {
-        #[cfg(not(any(target_os="illumos", target_os="solaris")))]
-        unsafe {
-            // To prevent truncation or overflow (in CMSG macros or elsewhere)
-            // cmsghdr having bigger alignment than RawFd isn't a problem,
-            //  as that doesn't affect the maximum capacity.
-            // If the size of cmsghdr is not divisible by the size of RawFd
-            //  (which could theoretically happen if all three cmsghdr fields
-            //  are u16 or u8 somewher), then some bytes will not be usable.
-            //  But dividing by the size of RawFd silently takes care of that
-            //  as it rounds down.
-            // FIXME This should ideally be a constant, but it's not really a
-            //  problem. (libc doesn't have a const_fn feature, probably
-            //  because old compilers wouldn't be able to even parse it.
-            let max_fds =
-                (c_uint::max_value() - CMSG_SPACE(0)) as usize
-                / mem::size_of::<RawFd>();
-            if num_fds == 0 {
-                Self::with_capacity(0)
-            } else if num_fds <= max_fds {
-                let payload_bytes = num_fds * mem::size_of::<RawFd>();
-                Self::with_capacity(CMSG_SPACE(payload_bytes as u32) as usize)
-            } else {
-                panic!("too many file descriptors for ancillary buffer length")
-            }
+        #[cfg(not(any(target_os="illumos", target_os="solaris")))] {
+            Self::with_capacity(Self::space_for_fds(num_fds))
         }
         #[cfg(any(target_os="illumos", target_os="solaris"))] {
             Self::with_capacity(num_fds) // any non-zero value is not supported
         }
     }
+
+    /// Get how many bytes of a buffer one ancillary message with
+    /// `payload_bytes` bytes of data takes up. (`CMSG_SPACE()`)
+    ///
+    /// Add together the space of all messages expected to be received at once
+    /// to get the capacity the buffer needs.
+    ///
+    /// # Panics
+    ///
+    /// If the message would be bigger than `MAX_CAPACITY`.
+    #[cfg(not(any(target_os="illumos", target_os="solaris")))]
+    pub fn space_for_payload(payload_bytes: usize) -> usize {
+        unsafe {
+            // Leave room for both the header and rounding up the payload to
+            // the alignment, to prevent overflow in CMSG_SPACE().
+            let max_payload = (c_uint::MAX - 2*CMSG_SPACE(0)) as usize;
+            if payload_bytes > max_payload {
+                panic!("ancillary message payload is too long");
+            }
+            CMSG_SPACE(payload_bytes as u32) as usize
+        }
+    }
+    /// Get how many bytes of a buffer receiving `num_fds` file descriptors
+    /// in one message takes up.
+    ///
+    /// This is zero if `num_fds` is zero, as no message is sent then.
+    ///
+    /// # Panics
+    ///
+    /// If the message would be bigger than `MAX_CAPACITY`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use uds::AncillaryBuf;
+    ///
+    /// assert_eq!(AncillaryBuf::space_for_fds(0), 0);
+    /// assert!(AncillaryBuf::space_for_fds(1) > std::mem::size_of::<std::os::unix::io::RawFd>());
+    /// assert!(AncillaryBuf::space_for_fds(3) > AncillaryBuf::space_for_fds(2));
+    /// ```
+    #[cfg(not(any(target_os="illumos", target_os="solaris")))]
+    pub fn space_for_fds(num_fds: usize) -> usize {
+        // If the size of cmsghdr is not divisible by the size of RawFd
+        //  (which could theoretically happen if all three cmsghdr fields
+        //  are u16 or u8 somewhere), then some bytes will not be usable.
+        //  But checked_mul() doesn't care about that.
+        match num_fds.checked_mul(mem::size_of::<RawFd>()) {
+            Some(0) => 0,
+            Some(payload_bytes) => Self::space_for_payload(payload_bytes),
+            None => panic!("too many file descriptors for ancillary buffer length"),
+        }
+    }
+    /// Get how many bytes of a buffer the credentials attached to a message
+    /// (`SCM_CREDENTIALS`) take up.
+    ///
+    /// # Examples
+    ///
+    /// Size a buffer for receiving up to four file descriptors and credentials:
+    ///
+    /// ```
+    /// use uds::AncillaryBuf;
+    ///
+    /// let capacity = AncillaryBuf::space_for_fds(4) + AncillaryBuf::space_for_credentials();
+    /// let ancillary_buf = AncillaryBuf::with_capacity(capacity);
+    /// ```
+    #[cfg(any(target_os="linux", target_os="android"))]
+    pub fn space_for_credentials() -> usize {
+        Self::space_for_payload(mem::size_of::<RawReceivedCredentials>())
+    }
+    /// Get how many bytes of a buffer the timestamp enabled by
+    /// `set_receive_timestamps()` takes up.
+    #[cfg(not(any(target_os="illumos", target_os="solaris")))]
+    pub fn space_for_timestamp() -> usize {
+        #[cfg(any(target_os="linux", target_os="android"))] {
+            Self::space_for_payload(mem::size_of::<timespec>())
+        }
+        #[cfg(not(any(target_os="linux", target_os="android")))] {
+            Self::space_for_payload(mem::size_of::<timeval>())
+        }
+    }
+}
+// The heap allocation is owned and only accessed through &self or &mut self.
+unsafe impl Send for AncillaryBuf {}
+unsafe impl Sync for AncillaryBuf {}
+impl fmt::Debug for AncillaryBuf {
+    fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmtr.debug_struct("AncillaryBuf")
+            .field("capacity", &self.capacity)
+            .field("on_stack", &(self.capacity as usize <= Self::MAX_STACK_CAPACITY))
+            .finish()
+    }
 }
 impl Default for AncillaryBuf {
     fn default() -> Self {
@@ -241,45 +481,72 @@ impl DerefMut for AncillaryBuf {
 }
 impl Borrow<[u8]> for AncillaryBuf {
     fn borrow(&self) -> &[u8] {
-        &*self
+        self
     }
 }
 impl BorrowMut<[u8]> for AncillaryBuf {
     fn borrow_mut(&mut self) -> &mut[u8] {
-        &mut*self
+        self
     }
 }
 impl AsRef<[u8]> for AncillaryBuf {
     fn as_ref(&self) -> &[u8] {
-        &*self
+        self
     }
 }
 impl AsMut<[u8]> for AncillaryBuf {
     fn as_mut(&mut self) -> &mut[u8] {
-        &mut*self
+        self
     }
 }
 
 
 
-/// One ancillary message produced by [`Ancillary`](#struct.Ancillary)
-pub enum AncillaryItem<'a> {
+/// Linux' `SCM_SECURITY`, which libc doesn't expose.
+#[cfg(any(target_os="linux", target_os="android"))]
+const SCM_SECURITY: c_int = 0x03;
+
+/// One ancillary message produced by [`Ancillary`](struct.Ancillary.html)
+#[derive(Clone,Copy, PartialEq,Eq, Debug)]
+pub enum AncillaryMessage<'a> {
     /// One or more file descriptors sent by the peer.
     ///
     /// Consumer of the iterator is responsible for closing them.
     Fds(&'a[RawFd]),
     /// Credentials of the sending process.
-    #[allow(unused)]
     Credentials(ReceivedCredentials),
-    //Timestamp(),
-    //SecurityContext(&'a[u8]),
+    /// The security label (such as SELinux context) of the sending socket.
+    ///
+    /// Only received on Linux & Android when `SO_PASSSEC` is enabled.
+    SecurityLabel(&'a[u8]),
+    /// When the message was received by the kernel.
+    ///
+    /// Only received when enabled with `set_receive_timestamps()`.
+    Timestamp(SystemTime),
     /// An unknown or unsupported ancillary message type was received.
     ///
     /// It's up to you whether to ignore or treat as an error.
-    Unsupported
+    ///
+    /// This makes it possible to use control message types that this crate
+    /// doesn't parse yet (such as ones added by newer kernels), by matching
+    /// on `level` and `ty` and interpreting `data` yourself.
+    /// `data` is not necessarily aligned for the type it contains, so read
+    /// from it with `ptr::read_unaligned()` or by copying.
+    /// Any file descriptors contained in it are not closed automatically.
+    Unknown {
+        /// `cmsg_level`
+        level: c_int,
+        /// `cmsg_type`
+        ty: c_int,
+        /// The payload of the message
+        data: &'a[u8],
+    },
 }
 
 /// An iterator over ancillary messages received with `recv_ancillary()`.
+///
+/// File descriptors that are not iterated over are closed when this
+/// is dropped.
 pub struct Ancillary<'a> {
     // addr and bytes are not used here:
     // * addr is usually placed on the stack by the calling wrapper method,
@@ -288,14 +555,18 @@ pub struct Ancillary<'a> {
     msg: msghdr,
 
     _ancillary_buf: PhantomData<&'a[u8]>,
+    /// Whether received file descriptors should be made close-on-exec
+    /// if the OS doesn't support MSG_CMSG_CLOEXEC.
+    #[cfg(any(target_vendor="apple", target_os="freebsd"))]
+    cloexec: bool,
     /// The next message, initialized with CMSG_FIRSTHDR()
     #[cfg(not(any(target_os="illumos", target_os="solaris")))]
     next_message: *mut cmsghdr,
 }
 impl<'a> Iterator for Ancillary<'a> {
-    type Item = AncillaryItem<'a>;
+    type Item = AncillaryMessage<'a>;
     #[cfg(not(any(target_os="illumos", target_os="solaris")))]
-    fn next(&mut self) -> Option<AncillaryItem<'a>> {
+    fn next(&mut self) -> Option<AncillaryMessage<'a>> {
         unsafe {
             if self.next_message.is_null() {
                 return None;
@@ -308,7 +579,8 @@ impl<'a> Iterator for Ancillary<'a> {
                     // pointer is aligned due to the cmsg header
                     let first_fd = CMSG_DATA(self.next_message) as *const RawFd;
                     let fds = slice::from_raw_parts(first_fd, num_fds);
-                    #[cfg(any(target_vendor="apple", target_os="freebsd"))] {
+                    #[cfg(any(target_vendor="apple", target_os="freebsd"))]
+                    if self.cloexec {
                         // set cloexec
                         // This is necessary on FreeBSD as MSG_CMSG_CLOEXEC
                         // appears to have no effect.
@@ -321,17 +593,55 @@ impl<'a> Iterator for Ancillary<'a> {
                             let _ = set_cloexec(fd, true);
                         }
                     }
-                    AncillaryItem::Fds(fds)
+                    AncillaryMessage::Fds(fds)
                 }
                 #[cfg(any(target_os="linux", target_os="android"))]
                 (SOL_SOCKET, SCM_CREDENTIALS) => {
                     // FIXME check payload size?
                     let creds_ptr = CMSG_DATA(self.next_message) as *const RawReceivedCredentials;
-                    AncillaryItem::Credentials(ReceivedCredentials::from_raw(*creds_ptr))
+                    AncillaryMessage::Credentials(ReceivedCredentials::from_raw(*creds_ptr))
+                }
+                #[cfg(any(target_os="freebsd", target_os="netbsd"))]
+                (SOL_SOCKET, SCM_CREDS) if payload_bytes >= mem::size_of::<sockcred>() => {
+                    // struct sockcred ends with a variable-length array of groups
+                    let data = CMSG_DATA(self.next_message);
+                    let creds = ptr::read_unaligned(data as *const sockcred);
+                    let groups_offset = mem::size_of::<sockcred>() - mem::size_of::<gid_t>();
+                    let available = (payload_bytes - groups_offset) / mem::size_of::<gid_t>();
+                    let mut groups = [0; 5];
+                    let number_of_groups = (creds.sc_ngroups.max(0) as usize)
+                        .min(available)
+                        .min(groups.len());
+                    for (i, group) in groups[..number_of_groups].iter_mut().enumerate() {
+                        let ptr = data.add(groups_offset + i * mem::size_of::<gid_t>());
+                        *group = ptr::read_unaligned(ptr as *const gid_t);
+                    }
+                    let received = ReceivedCredentials::from_sockcred(&creds, groups, number_of_groups);
+                    AncillaryMessage::Credentials(received)
+                }
+                #[cfg(any(target_os="linux", target_os="android"))]
+                (SOL_SOCKET, SCM_SECURITY) => {
+                    let label = CMSG_DATA(self.next_message) as *const u8;
+                    AncillaryMessage::SecurityLabel(slice::from_raw_parts(label, payload_bytes))
+                }
+                (SOL_SOCKET, SCM_TIMESTAMP) if payload_bytes >= mem::size_of::<timeval>() => {
+                    let tv = ptr::read_unaligned(CMSG_DATA(self.next_message) as *const timeval);
+                    let since_epoch = Duration::new(tv.tv_sec as u64, tv.tv_usec as u32 * 1000);
+                    AncillaryMessage::Timestamp(UNIX_EPOCH + since_epoch)
+                }
+                #[cfg(any(target_os="linux", target_os="android"))]
+                (SOL_SOCKET, SCM_TIMESTAMPNS) if payload_bytes >= mem::size_of::<timespec>() => {
+                    let ts = ptr::read_unaligned(CMSG_DATA(self.next_message) as *const timespec);
+                    let since_epoch = Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32);
+                    AncillaryMessage::Timestamp(UNIX_EPOCH + since_epoch)
+                }
+                (level, ty) => {
+                    let data = CMSG_DATA(self.next_message) as *const u8;
+                    let data = slice::from_raw_parts(data, payload_bytes);
+                    AncillaryMessage::Unknown { level, ty, data }
                 }
-                _ => AncillaryItem::Unsupported,
             };
-            self.next_message = CMSG_NXTHDR(&mut self.msg, self.next_message);
+            self.next_message = CMSG_NXTHDR(&self.msg, self.next_message);
             Some(item)
         }
     }
@@ -344,7 +654,7 @@ impl<'a> Drop for Ancillary<'a> {
     fn drop(&mut self) {
         // close all remaining file descriptors
         for ancillary in self {
-            if let AncillaryItem::Fds(fds) = ancillary {
+            if let AncillaryMessage::Fds(fds) = ancillary {
                 for &fd in fds {
                     unsafe { close(fd) };
                 }
@@ -363,16 +673,359 @@ impl<'a> Ancillary<'a> {
         self.msg.msg_flags & MSG_TRUNC != 0
     }
     /// Returns `true` if ancillary messages were dropped due to a too short ancillary buffer.
-    #[allow(unused)] // type is not yet exposed
+    ///
+    /// The OS doesn't say how big the buffer would have needed to be, and
+    /// any file descriptors that didn't fit have been closed.
+    /// Use `peek_ancillary_capacity()` on seqpacket connections before
+    /// receiving to find out.
     pub fn ancillary_truncated(&self) -> bool {
         self.msg.msg_flags & MSG_CTRUNC != 0
     }
+    /// Get how many bytes of the ancillary buffer were filled.
+    pub fn ancillary_len(&self) -> usize {
+        self.msg.msg_controllen as usize
+    }
+    /// Returns `true` if the OS marked the message as the end of a record.
+    ///
+    /// Linux never sets this for unix domain sockets.
+    pub fn end_of_record(&self) -> bool {
+        self.msg.msg_flags & MSG_EOR != 0
+    }
+    /// Returns `true` if any ancillary messages were received.
+    #[cfg(any(target_os="linux", target_os="android"))]
+    pub fn has_control_messages(&self) -> bool {
+        self.msg.msg_controllen != 0
+    }
+}
+
+/// Information about a received packet, from the flags set by `recvmsg()`.
+#[derive(Clone,Copy, PartialEq,Eq, Debug)]
+pub struct RecvInfo {
+    len: usize,
+    flags: c_int,
+}
+impl RecvInfo {
+    pub(crate) fn new(len: usize,  ancillary: &Ancillary) -> Self {
+        RecvInfo { len, flags: ancillary.msg.msg_flags }
+    }
+    /// The number of bytes stored in the buffer(s).
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Returns `true` if no bytes were stored in the buffer(s).
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Returns `true` if the packet didn't fit in the buffer(s),
+    /// and the rest of it was discarded.
+    pub fn is_truncated(&self) -> bool {
+        self.flags & MSG_TRUNC != 0
+    }
+    /// Returns `true` if ancillary data such as file descriptors was
+    /// discarded because there was no room for it.
+    pub fn is_control_truncated(&self) -> bool {
+        self.flags & MSG_CTRUNC != 0
+    }
+    /// Returns `true` if the OS marked the packet as the end of a record.
+    ///
+    /// Linux never sets this for unix domain sockets.
+    pub fn is_end_of_record(&self) -> bool {
+        self.flags & MSG_EOR != 0
+    }
+}
+
+/// Everything received with a datagram or packet by `recv_full()`.
+#[derive(Debug)]
+pub struct ReceivedMessage {
+    info: RecvInfo,
+    from: UnixSocketAddr,
+    fds: Vec<OwnedFd>,
+    refused_fds: usize,
+    credentials: Option<ReceivedCredentials>,
+    timestamp: Option<SystemTime>,
+}
+impl ReceivedMessage {
+    /// The number of bytes received and whether anything was truncated.
+    pub fn info(&self) -> RecvInfo {
+        self.info
+    }
+    /// The number of bytes stored in the buffer.
+    pub fn len(&self) -> usize {
+        self.info.len()
+    }
+    /// Returns `true` if no bytes were stored in the buffer.
+    pub fn is_empty(&self) -> bool {
+        self.info.is_empty()
+    }
+    /// The address of the socket that sent the message.
+    ///
+    /// This is unnamed if the sender isn't bound to an address.
+    pub fn from(&self) -> UnixSocketAddr {
+        self.from
+    }
+    /// The file descriptors sent with the message.
+    pub fn fds(&self) -> &[OwnedFd] {
+        &self.fds
+    }
+    /// Take ownership of the file descriptors sent with the message.
+    pub fn into_fds(self) -> Vec<OwnedFd> {
+        self.fds
+    }
+    /// The number of received file descriptors that were closed because the
+    /// [`FdPolicy`](enum.FdPolicy.html) didn't accept them.
+    ///
+    /// File descriptors the OS had no room for are not counted, but then
+    /// [`info().is_control_truncated()`](struct.RecvInfo.html#method.is_control_truncated)
+    /// returns `true`.
+    pub fn refused_fds(&self) -> usize {
+        self.refused_fds
+    }
+    /// The credentials of the sending process, if receiving them is enabled.
+    pub fn credentials(&self) -> Option<ReceivedCredentials> {
+        self.credentials
+    }
+    /// When the kernel received the message, if receiving timestamps is enabled.
+    pub fn timestamp(&self) -> Option<SystemTime> {
+        self.timestamp
+    }
+}
+
+/// The most file descriptors Linux accepts in one message (`SCM_MAX_FD`).
+const FD_CAPACITY: usize = 253;
+
+/// What to do with file descriptors received by `recv_with_fd_policy()`,
+/// or by a socket wrapped in [`FdPolicySocket`](struct.FdPolicySocket.html).
+///
+/// File descriptors that are not accepted are closed immediately,
+/// and counted by [`ReceivedMessage::refused_fds()`](struct.ReceivedMessage.html#method.refused_fds).
+#[derive(Clone,Copy, PartialEq,Eq, Debug)]
+pub enum FdPolicy {
+    /// Close all received file descriptors.
+    RefuseFds,
+    /// Accept up to this many file descriptors per message,
+    /// and close the rest.
+    AcceptUpTo(usize),
+    /// Accept all file descriptors received with a message.
+    AcceptAll,
+}
+
+/// A socket with an [`FdPolicy`](enum.FdPolicy.html) for the file
+/// descriptors it receives.
+///
+/// Messages received with [`recv_message()`](#method.recv_message)
+/// have the file descriptors the policy doesn't accept closed immediately.
+/// The wrapper derefs to the socket, so that other methods can be called
+/// on it directly, but file descriptors received by them are not subject
+/// to the policy.
+///
+/// # Examples
+///
+/// Never accept any file descriptors:
+///
+#[cfg_attr(not(any(target_os="illumos", target_os="solaris")), doc="```")]
+#[cfg_attr(any(target_os="illumos", target_os="solaris"), doc="```no_run")]
+/// use std::os::unix::net::UnixDatagram;
+/// use uds::{UnixDatagramExt, FdPolicy, FdPolicySocket};
+///
+/// let (a, b) = UnixDatagram::pair().unwrap();
+/// let b = FdPolicySocket::new(b, FdPolicy::RefuseFds);
+/// a.send_fds(b"unwanted", &[0, 1, 2]).unwrap();
+/// let received = b.recv_message(&mut[0; 10]).unwrap();
+/// assert_eq!(received.len(), 8);
+/// assert_eq!(received.fds().len(), 0);
+/// assert_eq!(received.refused_fds(), 3);
+/// ```
+#[derive(Debug)]
+pub struct FdPolicySocket<S> {
+    socket: S,
+    policy: FdPolicy,
+}
+
+impl<S: AsRawFd> FdPolicySocket<S> {
+    /// Apply `policy` to file descriptors received by `socket`.
+    pub fn new(socket: S,  policy: FdPolicy) -> Self {
+        FdPolicySocket { socket, policy }
+    }
+    /// The current policy.
+    pub fn policy(&self) -> FdPolicy {
+        self.policy
+    }
+    /// Change the policy for messages received after this.
+    pub fn set_policy(&mut self,  policy: FdPolicy) {
+        self.policy = policy;
+    }
+    /// Receive a message, and close the file descriptors sent with it that
+    /// the policy doesn't accept.
+    ///
+    /// Any credentials or timestamp sent with the message are also returned.
+    pub fn recv_message(&self,  buffer: &mut[u8]) -> Result<ReceivedMessage, io::Error> {
+        recv_with_fd_policy(self.socket.as_raw_fd(), buffer, self.policy)
+    }
+    /// Get the socket back.
+    pub fn into_inner(self) -> S {
+        self.socket
+    }
+}
+
+impl<S> Deref for FdPolicySocket<S> {
+    type Target = S;
+    fn deref(&self) -> &S {
+        &self.socket
+    }
+}
+
+impl<S: AsRawFd> AsRawFd for FdPolicySocket<S> {
+    fn as_raw_fd(&self) -> RawFd {
+        self.socket.as_raw_fd()
+    }
+}
+
+/// Receive a message with up to `max_fds` file descriptors,
+/// and any credentials or timestamp attached to it.
+pub fn recv_full(socket: RawFd,  buffer: &mut[u8],  max_fds: usize)
+-> Result<ReceivedMessage, io::Error> {
+    recv_message(socket, buffer, max_fds, max_fds)
+}
+
+/// Receive a message and close the file descriptors `policy` doesn't accept.
+pub fn recv_with_fd_policy(socket: RawFd,  buffer: &mut[u8],  policy: FdPolicy)
+-> Result<ReceivedMessage, io::Error> {
+    // make room for refused file descriptors so that they can be counted
+    let (capacity, accept) = match policy {
+        FdPolicy::RefuseFds => (FD_CAPACITY, 0),
+        FdPolicy::AcceptUpTo(max_fds) => (max_fds.max(FD_CAPACITY), max_fds),
+        FdPolicy::AcceptAll => (FD_CAPACITY, usize::MAX),
+    };
+    recv_message(socket, buffer, capacity, accept)
+}
+
+/// Receive a message with room for `capacity_fds` file descriptors,
+/// and close all but the first `accept_fds` of them.
+fn recv_message(socket: RawFd,  buffer: &mut[u8],  capacity_fds: usize,  accept_fds: usize)
+-> Result<ReceivedMessage, io::Error> {
+    #[cfg(not(any(target_os="illumos", target_os="solaris")))]
+    #[allow(unused_mut)]
+    let mut capacity = AncillaryBuf::space_for_fds(capacity_fds) + AncillaryBuf::space_for_timestamp();
+    #[cfg(any(target_os="linux", target_os="android"))] {
+        capacity += AncillaryBuf::space_for_credentials();
+    }
+    #[cfg(any(target_os="illumos", target_os="solaris"))]
+    let capacity = capacity_fds;
+    let mut ancillary_buf = AncillaryBuf::with_capacity(capacity);
+    let mut from = UnixSocketAddr::default();
+    let mut buffers = [IoSliceMut::new(buffer)];
+    let (len, ancillary) = recv_ancillary(socket, Some(&mut from), 0, &mut buffers, &mut ancillary_buf)?;
+    let mut message = ReceivedMessage {
+        info: RecvInfo::new(len, &ancillary),
+        from,
+        fds: Vec::new(),
+        refused_fds: 0,
+        credentials: None,
+        timestamp: None,
+    };
+    let mut refused = Vec::new();
+    for item in ancillary {
+        match item {
+            AncillaryMessage::Fds(fds) => {
+                for &fd in fds {
+                    if message.fds.len() < accept_fds {
+                        message.fds.push(unsafe { OwnedFd::from_raw_fd(fd) });
+                    } else {
+                        refused.push(fd);
+                    }
+                }
+            }
+            AncillaryMessage::Credentials(credentials) => message.credentials = Some(credentials),
+            AncillaryMessage::Timestamp(timestamp) => message.timestamp = Some(timestamp),
+            _ => {}
+        }
+    }
+    message.refused_fds = refused.len();
+    close_fds(&mut refused);
+    Ok(message)
+}
+
+/// Send multiple datagrams to the same address with one `sendmmsg()` call.
+///
+/// Returns the number of datagrams sent, which can be fewer than given.
+#[cfg(any(target_os="linux", target_os="android"))]
+pub fn send_multiple_to(socket: RawFd,  to: &UnixSocketAddr,  datagrams: &[&[u8]])
+-> Result<usize, io::Error> {
+    let (addr, addr_len) = to.as_raw();
+    let mut iovecs = datagrams.iter().map(|datagram| iovec {
+        iov_base: datagram.as_ptr() as *mut c_void,
+        iov_len: datagram.len(),
+    }).collect::<Vec<iovec>>();
+    let mut messages = iovecs.iter_mut().map(|iovec| unsafe {
+        let mut msg: msghdr = mem::zeroed();
+        msg.msg_name = addr as *const sockaddr_un as *const c_void as *mut c_void;
+        msg.msg_namelen = addr_len;
+        msg.msg_iov = iovec;
+        msg.msg_iovlen = 1;
+        mmsghdr { msg_hdr: msg, msg_len: 0 }
+    }).collect::<Vec<mmsghdr>>();
+    let count: c_uint = match messages.len().try_into() {
+        Ok(count) => count,
+        Err(_) => return Err(io::Error::new(ErrorKind::InvalidInput, "too many datagrams")),
+    };
+    let sent = cvt_r!(unsafe {
+        sendmmsg(socket, messages.as_mut_ptr(), count, MSG_NOSIGNAL as _)
+    })?;
+    Ok(sent as usize)
+}
+
+/// Receive multiple datagrams with one `recvmmsg()` call,
+/// waiting for only the first one.
+///
+/// Returns the length, whether it was truncated and the sender for each
+/// received datagram.
+#[cfg(any(target_os="linux", target_os="android"))]
+pub fn recv_multiple_from(socket: RawFd,  buffers: &mut[&mut[u8]])
+-> Result<Vec<(usize, bool, UnixSocketAddr)>, io::Error> {
+    let mut addrs = vec![UnixSocketAddr::default(); buffers.len()];
+    let mut iovecs = buffers.iter_mut().map(|buffer| iovec {
+        iov_base: buffer.as_mut_ptr() as *mut c_void,
+        iov_len: buffer.len(),
+    }).collect::<Vec<iovec>>();
+    let mut messages = iovecs.iter_mut().zip(&mut addrs).map(|(iovec, addr)| unsafe {
+        let (addr, _) = addr.as_raw_mut();
+        let mut msg: msghdr = mem::zeroed();
+        msg.msg_name = addr as *mut sockaddr_un as *mut c_void;
+        msg.msg_namelen = mem::size_of::<sockaddr_un>() as socklen_t;
+        msg.msg_iov = iovec;
+        msg.msg_iovlen = 1;
+        mmsghdr { msg_hdr: msg, msg_len: 0 }
+    }).collect::<Vec<mmsghdr>>();
+    let count: c_uint = match messages.len().try_into() {
+        Ok(count) => count,
+        Err(_) => return Err(io::Error::new(ErrorKind::InvalidInput, "too many buffers")),
+    };
+    let flags = MSG_NOSIGNAL | MSG_WAITFORONE;
+    let received = cvt_r!(unsafe {
+        recvmmsg(socket, messages.as_mut_ptr(), count, flags as _, ptr::null_mut())
+    })? as usize;
+    let results = messages[..received].iter().zip(&mut addrs).map(|(message, addr)| {
+        unsafe { *addr.as_raw_mut().1 = message.msg_hdr.msg_namelen };
+        addr.normalize_sun_len();
+        let truncated = message.msg_hdr.msg_flags & MSG_TRUNC != 0;
+        (message.msg_len as usize, truncated, *addr)
+    }).collect();
+    Ok(results)
 }
 
 /// A safe (but incomplete) wrapper around `recvmsg()`.
 pub fn recv_ancillary<'ancillary_buf>(
-    socket: RawFd,  from: Option<&mut UnixSocketAddr>,  mut flags: c_int,
+    socket: RawFd,  from: Option<&mut UnixSocketAddr>,  flags: c_int,
+    bufs: &mut[IoSliceMut],  ancillary_buf: &'ancillary_buf mut[u8],
+) -> Result<(usize, Ancillary<'ancillary_buf>), io::Error> {
+    recv_ancillary_with_cloexec(socket, from, flags, bufs, ancillary_buf, true)
+}
+
+/// `recv_ancillary()` that can leave received file descriptors inheritable.
+pub fn recv_ancillary_with_cloexec<'ancillary_buf>(
+    socket: RawFd,  mut from: Option<&mut UnixSocketAddr>,  mut flags: c_int,
     bufs: &mut[IoSliceMut],  ancillary_buf: &'ancillary_buf mut[u8],
+    cloexec: bool,
 ) -> Result<(usize, Ancillary<'ancillary_buf>), io::Error> {
     unsafe {
         let mut msg: msghdr = mem::zeroed();
@@ -389,13 +1042,13 @@ pub fn recv_ancillary<'ancillary_buf>(
         msg.msg_control = ptr::null_mut();
         msg.msg_controllen = 0;
 
-        if let Some(addr) = from {
+        if let Some(addr) = from.as_deref_mut() {
             let (addr, _) = addr.as_raw_mut();
             msg.msg_name = addr as *mut sockaddr_un as *mut c_void;
             msg.msg_namelen = mem::size_of::<sockaddr_un>() as socklen_t;
         }
 
-        if ancillary_buf.len() > 0 {
+        if !ancillary_buf.is_empty() {
             #[cfg(any(target_os="illumos", target_os="solaris"))] {
                 return Err(io::Error::new(
                     ErrorKind::Other,
@@ -406,7 +1059,7 @@ pub fn recv_ancillary<'ancillary_buf>(
                 let msg = "ancillary buffer is not properly aligned";
                 return Err(io::Error::new(ErrorKind::InvalidInput, msg));
             }
-            if ancillary_buf.len() > ControlLen::max_value() as usize {
+            if ancillary_buf.len() > ControlLen::MAX as usize {
                 let msg = "ancillary buffer is too big";
                 return Err(io::Error::new(ErrorKind::InvalidInput, msg));
             }
@@ -414,13 +1067,21 @@ pub fn recv_ancillary<'ancillary_buf>(
             msg.msg_controllen = ancillary_buf.len() as ControlLen;
         }
         flags |= MSG_NOSIGNAL;
-        #[cfg(not(any(target_vendor="apple", target_os="illumos", target_os="solaris")))] {
+        #[cfg(not(any(target_vendor="apple", target_os="illumos", target_os="solaris")))]
+        if cloexec {
             flags |= MSG_CMSG_CLOEXEC;
         }
         let received = cvt_r!(recvmsg(socket, &mut msg, flags))? as usize;
+        if let Some(addr) = from {
+            let (_, len) = addr.as_raw_mut();
+            *len = msg.msg_namelen;
+            addr.normalize_sun_len();
+        }
         let ancillary_iterator = Ancillary {
             msg,
             _ancillary_buf: PhantomData,
+            #[cfg(any(target_vendor="apple", target_os="freebsd"))]
+            cloexec,
             #[cfg(not(any(target_os="illumos", target_os="solaris")))]
             next_message: CMSG_FIRSTHDR(&msg),
         };
@@ -428,15 +1089,124 @@ pub fn recv_ancillary<'ancillary_buf>(
     }
 }
 
+/// Receive a message and the credentials attached to it,
+/// closing any received file descriptors.
+#[cfg(any(target_os="linux", target_os="android", target_os="freebsd", target_os="netbsd"))]
+pub fn recv_with_credentials(
+        socket: RawFd,  from: Option<&mut UnixSocketAddr>,  buffer: &mut[u8],
+) -> Result<(usize, bool, Option<ReceivedCredentials>), io::Error> {
+    let mut ancillary_buf = AncillaryBuf::default();
+    let mut buffers = [IoSliceMut::new(buffer)];
+    let (len, ancillary) = recv_ancillary(socket, from, 0, &mut buffers, &mut ancillary_buf)?;
+    let truncated = ancillary.message_truncated();
+    let mut credentials = None;
+    for item in ancillary {
+        match item {
+            AncillaryMessage::Credentials(received) => credentials = Some(received),
+            AncillaryMessage::Fds(fds) => {
+                for &fd in fds {
+                    unsafe { close(fd) };
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok((len, truncated, credentials))
+}
+
+/// Receive a message and the timestamp attached to it,
+/// closing any received file descriptors.
+#[cfg(not(any(target_os="illumos", target_os="solaris")))]
+pub fn recv_with_timestamp(
+        socket: RawFd,  from: Option<&mut UnixSocketAddr>,  buffer: &mut[u8],
+) -> Result<(usize, bool, Option<SystemTime>), io::Error> {
+    let mut ancillary_buf = AncillaryBuf::default();
+    let mut buffers = [IoSliceMut::new(buffer)];
+    let (len, ancillary) = recv_ancillary(socket, from, 0, &mut buffers, &mut ancillary_buf)?;
+    let truncated = ancillary.message_truncated();
+    let mut timestamp = None;
+    for item in ancillary {
+        match item {
+            AncillaryMessage::Timestamp(received) => timestamp = Some(received),
+            AncillaryMessage::Fds(fds) => {
+                for &fd in fds {
+                    unsafe { close(fd) };
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok((len, truncated, timestamp))
+}
+
+/// Like `recv_fds()`, but with file descriptors that are closed when dropped,
+/// and which are only close-on-exec if `cloexec` is `true`.
+pub fn recv_owned_fds(
+        fd: RawFd,  from: Option<&mut UnixSocketAddr>,
+        bufs: &mut[IoSliceMut],  max_fds: usize,  cloexec: bool,
+) -> Result<(usize, bool, Vec<OwnedFd>), io::Error> {
+    let mut fd_buf = vec![-1; max_fds];
+    let (num_bytes, truncated, num_fds) = recv_fds_with_cloexec(fd, from, bufs, &mut fd_buf, cloexec)?;
+    let fds = fd_buf[..num_fds].iter()
+        .map(|&fd| unsafe { OwnedFd::from_raw_fd(fd) } )
+        .collect();
+    Ok((num_bytes, truncated, fds))
+}
+
+/// Like `recv_owned_fds()`, but fails instead of silently discarding
+/// file descriptors that didn't fit.
+///
+/// All received file descriptors are closed when this returns an error,
+/// and the bytes of the message are lost.
+pub fn recv_fds_limited(
+        fd: RawFd,  from: Option<&mut UnixSocketAddr>,
+        bufs: &mut[IoSliceMut],  max_fds: usize,
+) -> Result<(usize, bool, Vec<OwnedFd>), io::Error> {
+    // Make room for one file descriptor more than the limit to detect
+    // exceeding it, and for credentials and timestamps if they're enabled,
+    // so that they don't push out file descriptors.
+    #[cfg(not(any(target_os="illumos", target_os="solaris")))]
+    #[cfg_attr(not(any(target_os="linux", target_os="android")), allow(unused_mut))]
+    let mut capacity = AncillaryBuf::space_for_fds(max_fds + 1) + AncillaryBuf::space_for_timestamp();
+    #[cfg(any(target_os="linux", target_os="android"))] {
+        capacity += AncillaryBuf::space_for_credentials();
+    }
+    #[cfg(any(target_os="illumos", target_os="solaris"))]
+    let capacity = max_fds + 1; // any non-zero value is not supported
+    let mut ancillary_buf = AncillaryBuf::with_capacity(capacity);
+    let (num_bytes, mut ancillary) = recv_ancillary(fd, from, 0, bufs, &mut ancillary_buf)?;
+    let mut fds = Vec::new();
+    for message in &mut ancillary {
+        if let AncillaryMessage::Fds(received) = message {
+            fds.extend(received.iter().map(|&fd| unsafe { OwnedFd::from_raw_fd(fd) } ));
+        }
+    }
+    // Count instead of checking MSG_CTRUNC, which is also set if the
+    // process is out of file descriptors, or by other control messages.
+    if fds.len() > max_fds {
+        let message = "peer sent more file descriptors than the limit, all have been closed";
+        return Err(io::Error::new(ErrorKind::InvalidData, message));
+    }
+    Ok((num_bytes, ancillary.message_truncated(), fds))
+}
+
 pub fn recv_fds(
         fd: RawFd,  from: Option<&mut UnixSocketAddr>,
         bufs: &mut[IoSliceMut],  fd_buf: &mut[RawFd]
+) -> Result<(usize, bool, usize), io::Error> {
+    recv_fds_with_cloexec(fd, from, bufs, fd_buf, true)
+}
+
+fn recv_fds_with_cloexec(
+        fd: RawFd,  from: Option<&mut UnixSocketAddr>,
+        bufs: &mut[IoSliceMut],  fd_buf: &mut[RawFd],  cloexec: bool,
 ) -> Result<(usize, bool, usize), io::Error> {
     let mut ancillary_buf = AncillaryBuf::with_fd_capacity(fd_buf.len());
-    let (num_bytes, mut ancillary) = recv_ancillary(fd, from, 0, bufs, &mut*ancillary_buf)?;
+    let (num_bytes, mut ancillary)
+        = recv_ancillary_with_cloexec(fd, from, 0, bufs, &mut ancillary_buf, cloexec)?;
     let mut num_fds = 0;
     for message in &mut ancillary {
-        if let AncillaryItem::Fds(fds) = message {
+        if let AncillaryMessage::Fds(fds) = message {
             // Due to alignment of cmsg_len in glibc the minimum payload
             // capacity is on Linux (and probably Android) 8 bytes,
             // which means we might receive two file descriptors even though
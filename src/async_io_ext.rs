@@ -0,0 +1,214 @@
+use std::future;
+use std::io::{self, ErrorKind};
+use std::net::Shutdown;
+use std::os::unix::io::AsRawFd;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use async_io::Async;
+use futures_core::Stream;
+use futures_sink::Sink;
+
+use crate::seqpacket::{NonblockingUnixSeqpacketListener, NonblockingUnixSeqpacketConn};
+use crate::seqpacket::{RecvPacket, recv_packet_to_vec};
+
+/// Extension trait for async-io's `Async<NonblockingUnixSeqpacketListener>`.
+///
+/// Requires the `async-io` feature.
+pub trait AsyncSeqpacketListenerExt {
+    /// Get a stream of accepted connections, ready to be used with async-io.
+    ///
+    /// The stream never ends, but yields errors from accepting or from
+    /// registering the connections.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// use async_io::Async;
+    /// use futures_lite::StreamExt;
+    /// use uds::nonblocking::{UnixSeqpacketListener, UnixSeqpacketConn};
+    /// use uds::AsyncSeqpacketListenerExt;
+    ///
+    /// # let addr = uds::UnixSocketAddr::new_unique().unwrap();
+    /// async_io::block_on(async {
+    ///     let listener = Async::new(UnixSeqpacketListener::bind_unix_addr(addr).unwrap())
+    ///         .unwrap();
+    ///     let _client = UnixSeqpacketConn::connect_unix_addr(addr).unwrap();
+    ///     let conn = listener.incoming().next().await.unwrap().unwrap();
+    ///     assert!(conn.get_ref().peer_unix_addr().unwrap().is_unnamed());
+    /// });
+    /// # if let Some(path) = addr.as_pathname() { let _ = std::fs::remove_file(path); }
+    /// ```
+    fn incoming(&self) -> Incoming<'_>;
+}
+
+impl AsyncSeqpacketListenerExt for Async<NonblockingUnixSeqpacketListener> {
+    fn incoming(&self) -> Incoming<'_> {
+        Incoming { listener: self }
+    }
+}
+
+/// A `Stream` of connections accepted by an async-io-wrapped listener.
+///
+/// Created by [`AsyncSeqpacketListenerExt::incoming()`](trait.AsyncSeqpacketListenerExt.html#tymethod.incoming).
+#[derive(Debug)]
+pub struct Incoming<'a> {
+    listener: &'a Async<NonblockingUnixSeqpacketListener>,
+}
+
+impl<'a> Stream for Incoming<'a> {
+    type Item = Result<Async<NonblockingUnixSeqpacketConn>, io::Error>;
+    fn poll_next(self: Pin<&mut Self>,  cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        poll_accept(self.listener, cx).map(|result| Some(result.and_then(Async::new)) )
+    }
+}
+
+/// Accept a connection, or register for readiness if there is none.
+///
+/// Also used by async-std's `AsyncStdIncoming`.
+pub(crate) fn poll_accept(listener: &Async<NonblockingUnixSeqpacketListener>,  cx: &mut Context<'_>)
+-> Poll<Result<NonblockingUnixSeqpacketConn, io::Error>> {
+    loop {
+        match listener.get_ref().accept() {
+            Ok(conn) => return Poll::Ready(Ok(conn)),
+            Err(ref e) if e.kind() == ErrorKind::WouldBlock => {}
+            Err(e) => return Poll::Ready(Err(e)),
+        }
+        match listener.poll_readable(cx) {
+            Poll::Ready(Ok(())) => {}
+            Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+            Poll::Pending => return Poll::Pending,
+        }
+    }
+}
+
+/// A `Stream` and `Sink` of whole packets over an async-io-wrapped
+/// seqpacket connection.
+///
+/// Each packet sent becomes one item received by the peer, and the stream
+/// ends when the peer closes the connection or shuts down its write half.
+/// Closing the sink shuts down the write half of this side.
+///
+/// Requires the `async-io` feature.
+///
+/// # Examples
+///
+#[cfg_attr(not(target_vendor="apple"), doc="```")]
+#[cfg_attr(target_vendor="apple", doc="```no_run")]
+/// use async_io::Async;
+/// use futures_lite::{future, StreamExt};
+/// use uds::nonblocking::UnixSeqpacketConn;
+/// use uds::AsyncPackets;
+///
+/// let (a, b) = UnixSeqpacketConn::pair().unwrap();
+/// let mut a = AsyncPackets::new(Async::new(a).unwrap());
+/// let b = AsyncPackets::new(Async::new(b).unwrap());
+/// future::block_on(async {
+///     a.send_packet(b"hello".to_vec()).await.unwrap();
+///     a.send_packet(Vec::new()).await.unwrap();
+///     drop(a);
+///     let received = b.try_collect::<_, _, Vec<Vec<u8>>>().await.unwrap();
+///     assert_eq!(received, vec![b"hello".to_vec(), Vec::new()]);
+/// });
+/// ```
+#[derive(Debug)]
+pub struct AsyncPackets {
+    conn: Async<NonblockingUnixSeqpacketConn>,
+    /// A packet given to `start_send()` which couldn't be sent immediately.
+    unsent: Option<Vec<u8>>,
+}
+
+impl AsyncPackets {
+    /// Wrap a connection.
+    pub fn new(conn: Async<NonblockingUnixSeqpacketConn>) -> Self {
+        AsyncPackets { conn, unsent: None }
+    }
+    /// Get a reference to the connection.
+    pub fn get_ref(&self) -> &Async<NonblockingUnixSeqpacketConn> {
+        &self.conn
+    }
+    /// Unwrap the connection.
+    ///
+    /// A packet that has been accepted by the sink but not yet sent is lost.
+    pub fn into_inner(self) -> Async<NonblockingUnixSeqpacketConn> {
+        self.conn
+    }
+    /// Send a packet, waiting for there to be room for it.
+    ///
+    /// This doesn't require importing a `SinkExt` trait.
+    pub async fn send_packet(&mut self,  packet: Vec<u8>) -> Result<(), io::Error> {
+        future::poll_fn(|cx| Pin::new(&mut *self).poll_ready(cx) ).await?;
+        Pin::new(&mut *self).start_send(packet)?;
+        future::poll_fn(|cx| Pin::new(&mut *self).poll_flush(cx) ).await
+    }
+}
+
+impl From<Async<NonblockingUnixSeqpacketConn>> for AsyncPackets {
+    fn from(conn: Async<NonblockingUnixSeqpacketConn>) -> Self {
+        AsyncPackets::new(conn)
+    }
+}
+
+impl Stream for AsyncPackets {
+    type Item = Result<Vec<u8>, io::Error>;
+    fn poll_next(self: Pin<&mut Self>,  cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut packet = Vec::new();
+        loop {
+            match recv_packet_to_vec(self.conn.as_raw_fd(), &mut packet) {
+                Ok(RecvPacket::Packet { .. }) => return Poll::Ready(Some(Ok(packet))),
+                Ok(RecvPacket::EndOfConnection) => return Poll::Ready(None),
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => {}
+                Err(e) => return Poll::Ready(Some(Err(e))),
+            }
+            match self.conn.poll_readable(cx) {
+                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Err(e)) => return Poll::Ready(Some(Err(e))),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl Sink<Vec<u8>> for AsyncPackets {
+    type Error = io::Error;
+    fn poll_ready(self: Pin<&mut Self>,  cx: &mut Context<'_>) -> Poll<Result<(), io::Error>> {
+        self.poll_flush(cx)
+    }
+    fn start_send(mut self: Pin<&mut Self>,  packet: Vec<u8>) -> Result<(), io::Error> {
+        if self.unsent.is_some() {
+            let message = "start_send() called without poll_ready() returning Ready";
+            return Err(io::Error::new(ErrorKind::InvalidInput, message));
+        }
+        match self.conn.get_ref().send(&packet) {
+            Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
+                self.unsent = Some(packet);
+                Ok(())
+            }
+            result => result.map(|_| () ),
+        }
+    }
+    fn poll_flush(mut self: Pin<&mut Self>,  cx: &mut Context<'_>) -> Poll<Result<(), io::Error>> {
+        while let Some(packet) = self.unsent.take() {
+            match self.conn.get_ref().send(&packet) {
+                Ok(_) => {}
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
+                    self.unsent = Some(packet);
+                    match self.conn.poll_writable(cx) {
+                        Poll::Ready(Ok(())) => {}
+                        Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                        Poll::Pending => return Poll::Pending,
+                    }
+                }
+                Err(e) => return Poll::Ready(Err(e)),
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+    fn poll_close(mut self: Pin<&mut Self>,  cx: &mut Context<'_>) -> Poll<Result<(), io::Error>> {
+        match self.as_mut().poll_flush(cx) {
+            Poll::Ready(Ok(())) => Poll::Ready(self.conn.get_ref().shutdown(Shutdown::Write)),
+            other => other,
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use std::io;
+use std::net::Shutdown;
+use std::os::unix::io::{RawFd, AsRawFd, FromRawFd, IntoRawFd};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use async_io::Async;
+use async_std::stream::Stream;
+
+use crate::addr::{UnixSocketAddr, ToUnixSocketAddr};
+use crate::helpers::set_nonblocking;
+use crate::async_io_ext::poll_accept;
+use crate::seqpacket::{UnixSeqpacketListener, UnixSeqpacketConn};
+use crate::seqpacket::{NonblockingUnixSeqpacketListener, NonblockingUnixSeqpacketConn};
+
+/// An async seqpacket listener for async-std.
+///
+/// async-std runs on async-io's reactor, which this uses for readiness.
+///
+/// Requires the `async-std` feature.
+///
+/// # Examples
+///
+#[cfg_attr(not(target_vendor="apple"), doc="```")]
+#[cfg_attr(target_vendor="apple", doc="```no_run")]
+/// use uds::{AsyncStdSeqpacketListener, AsyncStdSeqpacketConn};
+///
+/// # let addr = uds::UnixSocketAddr::new_unique().unwrap();
+/// async_std::task::block_on(async {
+///     let listener = AsyncStdSeqpacketListener::bind_unix_addr(addr).unwrap();
+///     let client = AsyncStdSeqpacketConn::connect_unix_addr(addr).await.unwrap();
+///     let (server, _) = listener.accept_unix_addr().await.unwrap();
+///     client.send(b"hello").await.unwrap();
+///     assert_eq!(server.recv(&mut[0; 10]).await.unwrap(), (5, false));
+/// });
+/// # if let Some(path) = addr.as_pathname() { let _ = std::fs::remove_file(path); }
+/// ```
+#[derive(Debug)]
+pub struct AsyncStdSeqpacketListener {
+    listener: Async<NonblockingUnixSeqpacketListener>,
+}
+
+impl AsyncStdSeqpacketListener {
+    /// Create a listener bound to `addr`.
+    pub fn bind_unix_addr<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error> {
+        Self::new(NonblockingUnixSeqpacketListener::bind_unix_addr(addr)?)
+    }
+    /// Register a nonblocking listener with the reactor.
+    pub fn new(listener: NonblockingUnixSeqpacketListener) -> Result<Self, io::Error> {
+        Ok(AsyncStdSeqpacketListener { listener: Async::new(listener)? })
+    }
+    /// Put a blocking listener into nonblocking mode and register it with the reactor.
+    ///
+    /// This is for sockets created or configured synchronously,
+    /// such as ones inherited from systemd.
+    pub fn from_std(listener: UnixSeqpacketListener) -> Result<Self, io::Error> {
+        listener.set_nonblocking(true)?;
+        let fd = listener.into_raw_fd();
+        Self::new(unsafe { NonblockingUnixSeqpacketListener::from_raw_fd(fd) })
+    }
+    /// Deregister the listener and put it back into blocking mode.
+    pub fn into_std(self) -> Result<UnixSeqpacketListener, io::Error> {
+        let listener = self.into_inner()?;
+        set_nonblocking(listener.as_raw_fd(), false)?;
+        Ok(unsafe { UnixSeqpacketListener::from_raw_fd(listener.into_raw_fd()) })
+    }
+    /// Get the wrapped listener.
+    pub fn get_ref(&self) -> &NonblockingUnixSeqpacketListener {
+        self.listener.get_ref()
+    }
+    /// Deregister the listener and unwrap it.
+    pub fn into_inner(self) -> Result<NonblockingUnixSeqpacketListener, io::Error> {
+        self.listener.into_inner()
+    }
+    /// Get the address this listener is bound to.
+    pub fn local_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+        self.get_ref().local_unix_addr()
+    }
+
+    /// Wait for a connection and accept it, along with the client's address.
+    pub async fn accept_unix_addr(&self)
+    -> Result<(AsyncStdSeqpacketConn, UnixSocketAddr), io::Error> {
+        let (conn, addr) = self.listener.read_with(|listener| listener.accept_unix_addr() ).await?;
+        Ok((AsyncStdSeqpacketConn::new(conn)?, addr))
+    }
+    /// Get a stream of accepted connections.
+    ///
+    /// The stream never ends, but yields errors from accepting or from
+    /// registering the connections.
+    pub fn incoming(&self) -> AsyncStdIncoming<'_> {
+        AsyncStdIncoming { listener: self }
+    }
+}
+
+/// A `Stream` of connections accepted by an
+/// [`AsyncStdSeqpacketListener`](struct.AsyncStdSeqpacketListener.html).
+///
+/// Created by [`AsyncStdSeqpacketListener::incoming()`](struct.AsyncStdSeqpacketListener.html#method.incoming).
+#[derive(Debug)]
+pub struct AsyncStdIncoming<'a> {
+    listener: &'a AsyncStdSeqpacketListener,
+}
+
+impl<'a> Stream for AsyncStdIncoming<'a> {
+    type Item = Result<AsyncStdSeqpacketConn, io::Error>;
+    fn poll_next(self: Pin<&mut Self>,  cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        poll_accept(&self.listener.listener, cx)
+            .map(|result| Some(result.and_then(AsyncStdSeqpacketConn::new)) )
+    }
+}
+
+/// An async seqpacket connection for async-std.
+///
+/// Requires the `async-std` feature.
+///
+/// # Examples
+///
+#[cfg_attr(not(target_vendor="apple"), doc="```")]
+#[cfg_attr(target_vendor="apple", doc="```no_run")]
+/// use uds::AsyncStdSeqpacketConn;
+///
+/// async_std::task::block_on(async {
+///     let (a, b) = AsyncStdSeqpacketConn::pair().unwrap();
+///     a.send(b"hello").await.unwrap();
+///     let mut buf = [0; 10];
+///     assert_eq!(b.recv(&mut buf).await.unwrap(), (5, false));
+/// });
+/// ```
+#[derive(Debug)]
+pub struct AsyncStdSeqpacketConn {
+    conn: Async<NonblockingUnixSeqpacketConn>,
+}
+
+impl AsyncStdSeqpacketConn {
+    /// Connect to a listening seqpacket socket,
+    /// waiting for the connect to complete if it doesn't immediately.
+    pub async fn connect_unix_addr<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error> {
+        let (conn, connected) = NonblockingUnixSeqpacketConn::start_connect(addr)?;
+        let conn = Self::new(conn)?;
+        if !connected {
+            while !conn.get_ref().poll_connected()? {
+                conn.conn.writable().await?;
+            }
+        }
+        Ok(conn)
+    }
+    /// Create a pair of connected sockets registered with the reactor.
+    pub fn pair() -> Result<(Self, Self), io::Error> {
+        let (a, b) = NonblockingUnixSeqpacketConn::pair()?;
+        Ok((Self::new(a)?, Self::new(b)?))
+    }
+    /// Register a nonblocking connection with the reactor.
+    pub fn new(conn: NonblockingUnixSeqpacketConn) -> Result<Self, io::Error> {
+        Ok(AsyncStdSeqpacketConn { conn: Async::new(conn)? })
+    }
+    /// Put a blocking connection into nonblocking mode and register it with the reactor.
+    ///
+    /// This is for sockets created or configured synchronously,
+    /// such as ones inherited from systemd.
+    pub fn from_std(conn: UnixSeqpacketConn) -> Result<Self, io::Error> {
+        conn.set_nonblocking(true)?;
+        Self::new(unsafe { NonblockingUnixSeqpacketConn::from_raw_fd(conn.into_raw_fd()) })
+    }
+    /// Deregister the connection and put it back into blocking mode.
+    pub fn into_std(self) -> Result<UnixSeqpacketConn, io::Error> {
+        let conn = self.into_inner()?;
+        set_nonblocking(conn.as_raw_fd(), false)?;
+        Ok(unsafe { UnixSeqpacketConn::from_raw_fd(conn.into_raw_fd()) })
+    }
+    /// Get the wrapped connection.
+    pub fn get_ref(&self) -> &NonblockingUnixSeqpacketConn {
+        self.conn.get_ref()
+    }
+    /// Deregister the connection and unwrap it.
+    pub fn into_inner(self) -> Result<NonblockingUnixSeqpacketConn, io::Error> {
+        self.conn.into_inner()
+    }
+    /// Get the address of this side of the connection.
+    pub fn local_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+        self.get_ref().local_unix_addr()
+    }
+    /// Get the address of the other side of the connection.
+    pub fn peer_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+        self.get_ref().peer_unix_addr()
+    }
+
+    /// Send a packet, waiting for there to be room for it.
+    pub async fn send(&self,  packet: &[u8]) -> Result<usize, io::Error> {
+        self.conn.write_with(|conn| conn.send(packet) ).await
+    }
+    /// Send a packet with associated file descriptors.
+    pub async fn send_fds(&self,  bytes: &[u8],  fds: &[RawFd]) -> Result<usize, io::Error> {
+        self.conn.write_with(|conn| conn.send_fds(bytes, fds) ).await
+    }
+    /// Wait for a packet and receive it.
+    ///
+    /// The returned `bool` indicates whether the packet was truncated due to
+    /// the buffer being too small.
+    pub async fn recv(&self,  buffer: &mut[u8]) -> Result<(usize, bool), io::Error> {
+        self.conn.read_with(|conn| conn.recv(buffer) ).await
+    }
+    /// Wait for a packet and receive it along with associated file descriptors.
+    pub async fn recv_fds(&self,  byte_buffer: &mut[u8],  fd_buffer: &mut[RawFd])
+    -> Result<(usize, bool, usize), io::Error> {
+        self.conn.read_with(|conn| conn.recv_fds(byte_buffer, fd_buffer) ).await
+    }
+    /// Shut down the read, write, or both directions of the connection.
+    pub fn shutdown(&self,  how: Shutdown) -> Result<(), io::Error> {
+        self.get_ref().shutdown(how)
+    }
+}
@@ -0,0 +1,78 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::addr::{UnixSocketAddr, ToUnixSocketAddr};
+
+/// Removes the socket file of a bound socket when dropped.
+///
+/// Unlike [`RemoveOnDrop`](struct.RemoveOnDrop.html) this doesn't wrap the
+/// socket, so it works with any socket type, including std's listeners and
+/// datagram sockets, and can be kept separately from the socket.
+///
+/// Abstract and unnamed addresses have no file, and the guard then does
+/// nothing.
+///
+/// # Examples
+///
+/// ```
+/// use std::os::unix::net::UnixListener;
+/// use uds::{BoundPath, UnixListenerExt};
+///
+/// # let _ = std::fs::remove_file("/tmp/uds bound path example.sock");
+/// let (listener, guard) = BoundPath::bind(
+///     "/tmp/uds bound path example.sock",
+///     UnixListener::bind_unix_addr,
+/// ).unwrap();
+/// assert!(guard.path().unwrap().exists());
+/// drop(guard);
+/// assert!(!std::path::Path::new("/tmp/uds bound path example.sock").exists());
+/// # drop(listener);
+/// ```
+#[derive(Debug)]
+pub struct BoundPath {
+    path: Option<PathBuf>,
+}
+
+impl BoundPath {
+    /// Bind a socket with `bind`, and create a guard for the path it was
+    /// bound to.
+    ///
+    /// No guard is created if `bind` fails, so a file that already existed
+    /// is never removed.
+    pub fn bind<A, S, F>(addr: A,  bind: F) -> Result<(S, Self), io::Error>
+    where A: ToUnixSocketAddr, F: FnOnce(UnixSocketAddr) -> Result<S, io::Error> {
+        let addr = addr.to_unix_addr()?;
+        let socket = bind(addr)?;
+        Ok((socket, Self::new(&addr)))
+    }
+    /// Create a guard for an address a socket has already been bound to.
+    pub fn new(addr: &UnixSocketAddr) -> Self {
+        BoundPath { path: addr.as_pathname().map(Path::to_path_buf) }
+    }
+
+    /// Get the path that will be removed, if any.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+    /// Don't remove the file when dropped, such as after handing the
+    /// socket over to another process that will clean up after it.
+    pub fn disarm(&mut self) {
+        self.path = None;
+    }
+    /// Drop the guard without removing the file, and get the path it would
+    /// have removed.
+    pub fn forget(mut self) -> Option<PathBuf> {
+        self.path.take()
+    }
+}
+
+impl Drop for BoundPath {
+    fn drop(&mut self) {
+        if let Some(path) = self.path.take() {
+            // the file might have been removed or replaced by someone else,
+            // but there's nothing to do about that here.
+            let _ = fs::remove_file(path);
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use std::ffi::CString;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::mem;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::io::{RawFd, AsRawFd, FromRawFd, IntoRawFd};
+use std::path::Path;
+
+use libc::{c_int, c_void, gid_t, uid_t, socklen_t, setsockopt};
+use libc::{SOCK_STREAM, SOCK_DGRAM, SOCK_SEQPACKET, SOL_SOCKET, SO_SNDBUF, SO_RCVBUF};
+use libc::EINPROGRESS;
+
+use crate::addr::{UnixSocketAddr, ToUnixSocketAddr};
+use crate::helpers::*;
+
+/// Set `SO_SNDBUF` or `SO_RCVBUF`.
+fn set_buffer_size(fd: RawFd,  option: c_int,  size: usize) -> Result<(), io::Error> {
+    let size = if size > c_int::MAX as usize {c_int::MAX} else {size as c_int};
+    let len = mem::size_of::<c_int>() as socklen_t;
+    cvt!(unsafe { setsockopt(fd, SOL_SOCKET, option, &size as *const c_int as *const c_void, len) })?;
+    Ok(())
+}
+
+/// Safe wrapper around `chown()`, where `None` leaves the id unchanged.
+fn chown(path: &Path,  uid: Option<uid_t>,  gid: Option<gid_t>) -> Result<(), io::Error> {
+    let path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|_| io::Error::new(ErrorKind::InvalidInput, "path contains NUL byte") )?;
+    let uid = uid.unwrap_or(!0);
+    let gid = gid.unwrap_or(!0);
+    cvt!(unsafe { libc::chown(path.as_ptr(), uid, gid) })?;
+    Ok(())
+}
+
+/// Create any kind of unix domain socket with options applied in one go,
+/// and finish into this crate's or std's socket types.
+///
+/// Options that are not set keep the defaults of this crate's other
+/// constructors: blocking, close-on-exec, and OS-default buffer sizes.
+///
+/// The finishing methods are generic over `FromRawFd`, and the type must
+/// match the socket type and mode: for example `UnixDatagram` for
+/// [`datagram()`](#method.datagram), or
+/// [`nonblocking::UnixSeqpacketListener`](nonblocking/struct.UnixSeqpacketListener.html)
+/// for a nonblocking [`seqpacket()`](#method.seqpacket) listener.
+///
+/// # Examples
+///
+#[cfg_attr(not(target_vendor="apple"), doc="```")]
+#[cfg_attr(target_vendor="apple", doc="```no_run")]
+/// use std::os::unix::fs::PermissionsExt;
+/// use uds::{SocketBuilder, UnixSeqpacketListener, UnixSeqpacketConn};
+///
+/// # let _ = std::fs::remove_file("/tmp/builder_example.sock");
+/// let listener: UnixSeqpacketListener = SocketBuilder::seqpacket()
+///     .mode(0o600)
+///     .backlog(64)
+///     .listen("/tmp/builder_example.sock")
+///     .unwrap();
+/// let metadata = std::fs::metadata("/tmp/builder_example.sock").unwrap();
+/// assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+///
+/// let conn: UnixSeqpacketConn = SocketBuilder::seqpacket()
+///     .send_buffer_size(64*1024)
+///     .connect("/tmp/builder_example.sock")
+///     .unwrap();
+/// # drop((listener, conn));
+/// # std::fs::remove_file("/tmp/builder_example.sock").unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct SocketBuilder {
+    socket_type: c_int,
+    nonblocking: bool,
+    cloexec: bool,
+    send_buffer_size: Option<usize>,
+    recv_buffer_size: Option<usize>,
+    #[cfg(any(target_os="linux", target_os="android"))]
+    pass_credentials: bool,
+    mode: Option<u32>,
+    owner: (Option<uid_t>, Option<gid_t>),
+    backlog: Option<u32>,
+    take_over_stale: bool,
+}
+
+impl SocketBuilder {
+    fn new(socket_type: c_int) -> Self {
+        SocketBuilder {
+            socket_type,
+            nonblocking: false,
+            cloexec: true,
+            send_buffer_size: None,
+            recv_buffer_size: None,
+            #[cfg(any(target_os="linux", target_os="android"))]
+            pass_credentials: false,
+            mode: None,
+            owner: (None, None),
+            backlog: None,
+            take_over_stale: false,
+        }
+    }
+    /// Build `SOCK_STREAM` sockets.
+    pub fn stream() -> Self {
+        Self::new(SOCK_STREAM)
+    }
+    /// Build `SOCK_DGRAM` sockets.
+    pub fn datagram() -> Self {
+        Self::new(SOCK_DGRAM)
+    }
+    /// Build `SOCK_SEQPACKET` sockets.
+    pub fn seqpacket() -> Self {
+        Self::new(SOCK_SEQPACKET)
+    }
+
+    /// Create the sockets in nonblocking mode.
+    pub fn nonblocking(mut self,  nonblocking: bool) -> Self {
+        self.nonblocking = nonblocking;
+        self
+    }
+    /// Set whether the sockets are closed on exec, which is the default.
+    pub fn cloexec(mut self,  cloexec: bool) -> Self {
+        self.cloexec = cloexec;
+        self
+    }
+    /// Set the size of the send buffer (`SO_SNDBUF`),
+    /// which the OS might adjust.
+    pub fn send_buffer_size(mut self,  size: usize) -> Self {
+        self.send_buffer_size = Some(size);
+        self
+    }
+    /// Set the size of the receive buffer (`SO_RCVBUF`),
+    /// which the OS might adjust.
+    pub fn recv_buffer_size(mut self,  size: usize) -> Self {
+        self.recv_buffer_size = Some(size);
+        self
+    }
+    /// Enable `SO_PASSCRED`, which makes Linux attach the credentials of the
+    /// sender to every received message.
+    ///
+    /// Sockets accepted from a listener with this set inherit it.
+    #[cfg(any(target_os="linux", target_os="android"))]
+    pub fn pass_credentials(mut self,  pass: bool) -> Self {
+        self.pass_credentials = pass;
+        self
+    }
+    /// Set the permissions of the socket file when binding to a path.
+    ///
+    /// Connecting to a socket requires write permission to the file
+    /// on most operating systems.
+    ///
+    /// The permissions are set after binding, which creates the file.
+    /// On Linux and Android the file is created with at most these
+    /// permissions, but on other operating systems it's created with
+    /// permissions based on the umask, so other users might be able to
+    /// connect before the permissions are changed.
+    /// Bind in a directory that only the intended users can access if that
+    /// matters.
+    pub fn mode(mut self,  mode: u32) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+    /// Set the owner and or group of the socket file when binding to a path.
+    ///
+    /// `None` leaves the user or group unchanged.
+    pub fn owner(mut self,  uid: Option<u32>,  gid: Option<u32>) -> Self {
+        self.owner = (uid.map(|uid| uid as uid_t ), gid.map(|gid| gid as gid_t ));
+        self
+    }
+    /// Set the maximum number of pending connections of listeners,
+    /// which the OS might limit further.
+    pub fn backlog(mut self,  backlog: u32) -> Self {
+        self.backlog = Some(backlog);
+        self
+    }
+
+    /// Replace a socket file left behind at the path by a socket that is no
+    /// longer bound, instead of failing with `AddrInUse`.
+    ///
+    /// The path is probed by connecting to it with a socket of the same type,
+    /// and the file is only removed if that is refused.
+    /// Binding then fails with an error of kind `AddrInUse` if a socket is
+    /// still bound to the path, or `AlreadyExists` if it isn't a socket file.
+    pub fn take_over_stale(mut self,  take_over: bool) -> Self {
+        self.take_over_stale = take_over;
+        self
+    }
+
+    /// Apply the options that don't depend on an address.
+    fn configure(&self,  socket: &Socket) -> Result<(), io::Error> {
+        if !self.cloexec {
+            set_cloexec(socket.as_raw_fd(), false)?;
+        }
+        if let Some(size) = self.send_buffer_size {
+            set_buffer_size(socket.as_raw_fd(), SO_SNDBUF, size)?;
+        }
+        if let Some(size) = self.recv_buffer_size {
+            set_buffer_size(socket.as_raw_fd(), SO_RCVBUF, size)?;
+        }
+        #[cfg(any(target_os="linux", target_os="android"))]
+        if self.pass_credentials {
+            set_passcred(socket.as_raw_fd(), true)?;
+        }
+        Ok(())
+    }
+
+    fn create(&self) -> Result<Socket, io::Error> {
+        let socket = Socket::new(self.socket_type, self.nonblocking)?;
+        self.configure(&socket)?;
+        Ok(socket)
+    }
+
+    /// Bind, set the file permissions and owner, and optionally start
+    /// listening, removing the socket file if any of the later steps fail.
+    fn create_bound(&self,  addr: &UnixSocketAddr,  listen: bool) -> Result<Socket, io::Error> {
+        let socket = self.create()?;
+        // Linux creates the file with the permissions of the socket minus the
+        // umask, so that the file is never more accessible than requested.
+        #[cfg(any(target_os="linux", target_os="android"))]
+        if let (Some(mode), Some(_)) = (self.mode, addr.as_pathname()) {
+            cvt!(unsafe { libc::fchmod(socket.as_raw_fd(), mode as libc::mode_t) })?;
+        }
+        if self.take_over_stale {
+            bind_to_taking_over(socket.as_raw_fd(), addr, self.socket_type)?;
+        } else {
+            bind_to(socket.as_raw_fd(), addr)?;
+        }
+        let result = (|| {
+            if let Some(path) = addr.as_pathname() {
+                if let Some(mode) = self.mode {
+                    fs::set_permissions(path, fs::Permissions::from_mode(mode))?;
+                }
+                if self.owner != (None, None) {
+                    chown(path, self.owner.0, self.owner.1)?;
+                }
+            }
+            if listen {
+                match self.backlog {
+                    Some(backlog) => socket.start_listening_with_backlog(backlog),
+                    None => socket.start_listening(),
+                }
+            } else {
+                Ok(())
+            }
+        })();
+        if let Err(e) = result {
+            if let Some(path) = addr.as_pathname() {
+                let _ = fs::remove_file(path);
+            }
+            return Err(e);
+        }
+        Ok(socket)
+    }
+
+    /// Create a listener bound to `addr`.
+    ///
+    /// This is for stream and seqpacket sockets.
+    pub fn listen<A: ToUnixSocketAddr,  L: FromRawFd>(&self,  addr: A) -> Result<L, io::Error> {
+        let socket = self.create_bound(&addr.to_unix_addr()?, true)?;
+        Ok(unsafe { L::from_raw_fd(socket.into_raw_fd()) })
+    }
+    /// Create a socket bound to `addr` that isn't listening.
+    ///
+    /// This is mainly for datagram sockets.
+    pub fn bind<A: ToUnixSocketAddr,  S: FromRawFd>(&self,  addr: A) -> Result<S, io::Error> {
+        let socket = self.create_bound(&addr.to_unix_addr()?, false)?;
+        Ok(unsafe { S::from_raw_fd(socket.into_raw_fd()) })
+    }
+    /// Create a socket connected to `addr`.
+    ///
+    /// For nonblocking stream and seqpacket sockets the connect might not
+    /// have completed when this returns.
+    pub fn connect<A: ToUnixSocketAddr,  S: FromRawFd>(&self,  addr: A) -> Result<S, io::Error> {
+        let socket = self.create()?;
+        match connect_to(socket.as_raw_fd(), &addr.to_unix_addr()?) {
+            Err(ref e) if e.raw_os_error() == Some(EINPROGRESS) => {}
+            result => result?,
+        }
+        Ok(unsafe { S::from_raw_fd(socket.into_raw_fd()) })
+    }
+    /// Create a pair of sockets connected to each other.
+    pub fn pair<S: FromRawFd>(&self) -> Result<(S, S), io::Error> {
+        let (a, b) = Socket::pair(self.socket_type, self.nonblocking)?;
+        self.configure(&a)?;
+        self.configure(&b)?;
+        Ok(unsafe { (S::from_raw_fd(a.into_raw_fd()), S::from_raw_fd(b.into_raw_fd())) })
+    }
+    /// Like `pair()`, but fail if the builder is for another socket type.
+    pub(crate) fn pair_of_type<S: FromRawFd>(&self,  socket_type: c_int)
+    -> Result<(S, S), io::Error> {
+        if self.socket_type != socket_type {
+            return Err(io::Error::new(ErrorKind::InvalidInput, "wrong socket type for builder"));
+        }
+        self.pair()
+    }
+}
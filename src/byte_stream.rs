@@ -0,0 +1,129 @@
+use std::io::{self, ErrorKind, Read, Write};
+use std::os::unix::io::AsRawFd;
+
+use crate::seqpacket::{UnixSeqpacketConn, RecvPacket, recv_packet_to_vec};
+
+/// An adaptor that implements `Read` and `Write` over a seqpacket connection,
+/// for using stream-oriented code with a seqpacket transport.
+///
+/// Written bytes are buffered, and sent as one packet when `flush()` is
+/// called or the buffer reaches the maximum packet size.
+/// Received packets are buffered until they have been completely read,
+/// so packet boundaries are not visible to readers.
+/// Empty packets are skipped, and reading doesn't change any socket options,
+/// so file descriptors can still be received on the wrapped connection.
+/// The write buffer is flushed when the adaptor is dropped, but errors are
+/// ignored then, so call `flush()` first to handle them.
+///
+/// # Examples
+///
+#[cfg_attr(not(target_vendor="apple"), doc="```")]
+#[cfg_attr(target_vendor="apple", doc="```no_run")]
+/// use std::io::{Read, Write};
+/// use uds::{UnixSeqpacketConn, SeqpacketByteStream};
+///
+/// let (a, b) = UnixSeqpacketConn::pair().unwrap();
+/// let mut writer = SeqpacketByteStream::new(a).unwrap();
+/// write!(writer, "{} + {} = {}", 1, 2, 1 + 2).unwrap();
+/// writer.flush().unwrap();
+/// drop(writer);
+///
+/// let mut text = String::new();
+/// SeqpacketByteStream::new(b).unwrap().read_to_string(&mut text).unwrap();
+/// assert_eq!(text, "1 + 2 = 3");
+/// ```
+#[derive(Debug)]
+pub struct SeqpacketByteStream {
+    /// Only `None` after `into_inner()`.
+    conn: Option<UnixSeqpacketConn>,
+    max_packet_size: usize,
+    read_buf: Vec<u8>,
+    read_pos: usize,
+    write_buf: Vec<u8>,
+}
+
+impl SeqpacketByteStream {
+    /// Wrap a connection, and send packets of up to
+    /// [`max_send_size()`](struct.UnixSeqpacketConn.html#method.max_send_size).
+    pub fn new(conn: UnixSeqpacketConn) -> Result<Self, io::Error> {
+        let max_packet_size = conn.max_send_size()?;
+        Self::with_max_packet_size(conn, max_packet_size)
+    }
+
+    /// Wrap a connection, and send packets of up to `max_packet_size` bytes.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `max_packet_size` is zero.
+    pub fn with_max_packet_size(conn: UnixSeqpacketConn,  max_packet_size: usize)
+    -> Result<Self, io::Error> {
+        if max_packet_size == 0 {
+            return Err(io::Error::new(ErrorKind::InvalidInput, "max packet size cannot be zero"));
+        }
+        Ok(SeqpacketByteStream {
+            conn: Some(conn),
+            max_packet_size,
+            read_buf: Vec::new(),
+            read_pos: 0,
+            write_buf: Vec::new(),
+        })
+    }
+
+    /// Get the wrapped connection.
+    pub fn get_ref(&self) -> &UnixSeqpacketConn {
+        self.conn.as_ref().unwrap()
+    }
+
+    /// Flush the write buffer and unwrap the connection.
+    ///
+    /// Any received bytes that haven't been read are lost.
+    pub fn into_inner(mut self) -> Result<UnixSeqpacketConn, io::Error> {
+        self.flush()?;
+        Ok(self.conn.take().unwrap())
+    }
+}
+
+impl Read for SeqpacketByteStream {
+    fn read(&mut self,  buf: &mut[u8]) -> Result<usize, io::Error> {
+        while self.read_pos == self.read_buf.len() {
+            self.read_pos = 0;
+            let socket = self.get_ref().as_raw_fd();
+            if recv_packet_to_vec(socket, &mut self.read_buf)? == RecvPacket::EndOfConnection {
+                return Ok(0);
+            }
+            // skip empty packets, which would otherwise look like end of file
+        }
+        let unread = &self.read_buf[self.read_pos..];
+        let len = unread.len().min(buf.len());
+        buf[..len].copy_from_slice(&unread[..len]);
+        self.read_pos += len;
+        Ok(len)
+    }
+}
+
+impl Write for SeqpacketByteStream {
+    fn write(&mut self,  buf: &[u8]) -> Result<usize, io::Error> {
+        if self.write_buf.len() == self.max_packet_size {
+            self.flush()?;
+        }
+        let len = buf.len().min(self.max_packet_size - self.write_buf.len());
+        self.write_buf.extend_from_slice(&buf[..len]);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> Result<(), io::Error> {
+        if !self.write_buf.is_empty() {
+            self.get_ref().send(&self.write_buf)?;
+            self.write_buf.clear();
+        }
+        Ok(())
+    }
+}
+
+impl Drop for SeqpacketByteStream {
+    fn drop(&mut self) {
+        if self.conn.is_some() {
+            let _ = self.flush();
+        }
+    }
+}
@@ -1,4 +1,6 @@
 use std::os::unix::io::RawFd;
+#[cfg(any(target_os="linux", target_os="android"))]
+use std::os::unix::io::{OwnedFd, FromRawFd};
 use std::{io, fmt};
 use std::num::NonZeroU32;
 use std::io::ErrorKind::*;
@@ -7,6 +9,10 @@ use std::mem;
 
 #[cfg(any(target_os="linux", target_os="android", target_os="freebsd", target_vendor="apple"))]
 use libc::{getsockopt, c_void, socklen_t};
+#[cfg(any(target_os="linux", target_os="android", target_vendor="apple"))]
+use libc::c_int;
+#[cfg(any(target_os="openbsd", target_os="netbsd"))]
+use libc::{uid_t, gid_t, getpeereid};
 #[cfg(any(target_os="linux", target_os="android"))]
 use libc::{pid_t, uid_t, gid_t, getpid, getuid, geteuid, getgid, getegid};
 #[cfg(any(target_os="linux", target_os="android"))]
@@ -15,16 +21,22 @@ use libc::{ucred, SOL_SOCKET, SO_PEERCRED};
 use libc::{xucred, XUCRED_VERSION, LOCAL_PEERCRED};
 #[cfg(target_vendor="apple")]
 use libc::SOL_LOCAL; // Apple is for once the one that does the right thing!
+#[cfg(target_vendor="apple")]
+use libc::{pid_t, LOCAL_PEERPID, LOCAL_PEEREPID, LOCAL_PEEREUUID};
 
-/// Credentials to be sent with `send_ancillary()`.
+/// Credentials to be sent with [`SendAncillary`](struct.SendAncillary.html).
 ///
 /// Only on Linux (& Android) does one need to send credentials, and on other
 /// operating systems this struct is ignored.
 #[derive(Clone,Copy, PartialEq,Eq, Debug)]
-#[allow(unused)] // not used yet
 pub enum SendCredentials {
+    /// The process ID and effective user and group IDs of this process.
     Effective,
+    /// The process ID and real user and group IDs of this process.
     Real,
+    /// Other values, which requires privileges (`CAP_SYS_ADMIN` for pid,
+    /// and `CAP_SETUID` / `CAP_SETGID` for uid and gid) unless they match
+    /// the sending process.
     Custom{ pid: u32, uid: u32, gid: u32 }
 }
 #[cfg(any(target_os="linux", target_os="android"))]
@@ -39,7 +51,7 @@ impl SendCredentials {
         ucred.pid = pid;
         ucred.uid = uid;
         ucred.gid = gid;
-        return ucred;
+        ucred
     }
 }
 
@@ -63,41 +75,80 @@ impl SendCredentials {
 ///
 /// Current limitations of this crate:
 ///
-/// * OpenBSD, NetBSD, DragonFly BSD, Illumos and Solaris are not supported yet.
+/// * DragonFly BSD, Illumos and Solaris are not supported yet.
 ///   On these OSes, functions that can return this type
 ///   will return an error instead.
+/// * On OpenBSD and NetBSD only effective user and group ID is provided,
+///   through `getpeereid()`. They are returned as the `MacOsLike` variant
+///   with the effective group ID as the only group, and pid is not available.
 /// * FreeBSD also provides pid, but this crate doesn't know that yet.
+///
+/// On macOS and FreeBSD the information is retrieved with `LOCAL_PEERCRED`,
+/// and on Linux and Android with `SO_PEERCRED`.
+/// The methods return what is available on all of them,
+/// so matching on the variants is only needed for OS-specific details.
+///
+/// # Examples
+///
+/// Accepting only connections from processes running as the same user or
+/// as root, and which are a member of a group:
+///
+#[cfg_attr(any(target_os="linux", target_os="android", target_os="freebsd"), doc="```")]
+#[cfg_attr(not(any(target_os="linux", target_os="android", target_os="freebsd")), doc="```no_run")]
+/// use uds::{UnixSeqpacketConn, ConnCredentials};
+///
+/// fn is_allowed(creds: &ConnCredentials,  group: u32) -> bool {
+///     let own_uid = unsafe { libc::geteuid() };
+///     (creds.euid() == own_uid || creds.euid() == 0)
+///         && (creds.egid() == Some(group) || creds.groups().contains(&group))
+/// }
+///
+/// let (a, _b) = UnixSeqpacketConn::pair().unwrap();
+/// let creds = a.initial_peer_credentials().unwrap();
+/// assert!(is_allowed(&creds, unsafe { libc::getegid() }));
+/// ```
 #[derive(Clone,Copy, PartialEq)]
 pub enum ConnCredentials {
+    /// Credentials provided by Linux and Android.
     LinuxLike{ pid: NonZeroU32, euid: u32, egid: u32 },
+    /// Credentials from `struct xucred`, provided by macOS and FreeBSD.
+    ///
+    /// Only the first `number_of_groups` of `groups` are valid,
+    /// and the rest are set to `!0`.
+    /// On OpenBSD and NetBSD this contains the result of `getpeereid()`,
+    /// with the effective group ID as the only group.
+    ///
+    /// On macOS the process ID is available through
+    /// [`UnixStreamExt::initial_peer_pid()`](trait.UnixStreamExt.html#method.initial_peer_pid).
     MacOsLike{ euid: u32, number_of_groups: u8, groups: [u32; 16/*what libc uses for all OSes*/] },
 }
 impl ConnCredentials {
     /// Get the process ID of the initial peer of a connection.
     ///
-    /// This is currently only available on Linux & Android,
-    /// but will in the future also be available on OpenBSD and NetBSD,
-    /// and possibly also FreeBSD and Solaris.
+    /// This is currently only available on Linux and Android,
+    /// as `getpeereid()` which is used on OpenBSD and NetBSD doesn't provide it.
+    /// On macOS it can be retrieved with
+    /// [`UnixStreamExt::initial_peer_pid()`](trait.UnixStreamExt.html#method.initial_peer_pid).
     pub fn pid(&self) -> Option<NonZeroU32> {
-        match self {
-            &ConnCredentials::LinuxLike{ pid, .. } => Some(pid),
-            &ConnCredentials::MacOsLike{ .. } => None,
+        match *self {
+            ConnCredentials::LinuxLike{ pid, .. } => Some(pid),
+            ConnCredentials::MacOsLike{ .. } => None,
         }
     }
     /// Get the effective user ID of the initial peer of a connection.
     ///
     /// This is provided by any supported OS.
     pub fn euid(&self) -> u32 {
-        match self {
-            &ConnCredentials::LinuxLike{ euid, .. } => euid,
-            &ConnCredentials::MacOsLike{ euid, .. } => euid,
+        match *self {
+            ConnCredentials::LinuxLike{ euid, .. } => euid,
+            ConnCredentials::MacOsLike{ euid, .. } => euid,
         }
     }
     /// Get the effective group ID of the initial peer of a connection.
     ///
-    /// * On Linux, Android and in the future OpenBSD and NetBSD,
+    /// * On Linux and Android,
     ///   `egid` from the `LinuxLike` variant is returned.
-    /// * On FreeBSD, macOS and in the future DragonFly BSD,
+    /// * On FreeBSD, macOS, OpenBSD, NetBSD and in the future DragonFly BSD,
     ///   `groups[0]` from the `MacOsLike` variant is returned
     ///   (except in the unlikely case that `number_of_groups` is zero).
     // Sources for that the first group is egid: `<sys/ucred.h>` for
@@ -110,36 +161,39 @@ impl ConnCredentials {
     // [macOS](https://opensource.apple.com/source/Libc/Libc-1082.50.1/gen/FreeBSD/getpeereid.c.auto.html)
     // TODO remove None case before 0.2
     pub fn egid(&self) -> Option<u32> {
-        match self {
-            &ConnCredentials::LinuxLike{ egid, .. } => Some(egid),
-            &ConnCredentials::MacOsLike{ number_of_groups: 1..=255, groups, .. } => Some(groups[0]),
-            &ConnCredentials::MacOsLike{ number_of_groups: 0, .. } => None,
+        match *self {
+            ConnCredentials::LinuxLike{ egid, .. } => Some(egid),
+            ConnCredentials::MacOsLike{ number_of_groups: 1..=255, groups, .. } => Some(groups[0]),
+            ConnCredentials::MacOsLike{ number_of_groups: 0, .. } => None,
         }
     }
     /// Get the groups that the initial peer of a connection was a mamber of.
     ///
     /// This is only available on FreeBSD and macOS (and in the future
-    /// DragonFly BSD), and an empty slice is returned on other OSes.
+    /// DragonFly BSD), and an empty slice is returned on other OSes,
+    /// except OpenBSD and NetBSD where it contains only the effective group ID.
+    /// Use `initial_peer_groups()` to also get them on Linux,
+    /// or if the peer is a member of more than 16 groups.
     pub fn groups(&self) -> &[u32] {
-        match self {
-            &ConnCredentials::LinuxLike{ .. } => &[],
-            &ConnCredentials::MacOsLike{ number_of_groups: n @ 0..=15, ref groups, .. } => {
+        match *self {
+            ConnCredentials::LinuxLike{ .. } => &[],
+            ConnCredentials::MacOsLike{ number_of_groups: n @ 0..=15, ref groups, .. } => {
                 &groups[..(n as usize)]
             },
-            &ConnCredentials::MacOsLike{ number_of_groups: 16..=255, ref groups, .. } => groups,
+            ConnCredentials::MacOsLike{ number_of_groups: 16..=255, ref groups, .. } => groups,
         }
     }
 }
 impl fmt::Debug for ConnCredentials {
     fn fmt(&self,  fmtr: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         let mut repr = fmtr.debug_struct("ConnCredentials");
-        match self {
-            &ConnCredentials::LinuxLike{ ref pid, ref euid, ref egid } => {
+        match *self {
+            ConnCredentials::LinuxLike{ ref pid, ref euid, ref egid } => {
                 repr.field("pid", pid);
                 repr.field("euid", euid);
                 repr.field("egid", egid);
             }
-            &ConnCredentials::MacOsLike{ ref euid, number_of_groups, ref groups } => {
+            ConnCredentials::MacOsLike{ ref euid, number_of_groups, ref groups } => {
                 repr.field("euid", euid);
                 let number_of_groups = (number_of_groups as usize).min(groups.len());
                 repr.field("groups", &&groups[..number_of_groups]);
@@ -189,7 +243,7 @@ pub fn peer_credentials(conn: RawFd) -> Result<ConnCredentials, io::Error> {
                 Err(io::Error::new(InvalidData, "unknown version of peer credentials"))
             },
             _ => {
-                let mut groups = [u32::max_value(); 16]; // set all unused group slots to ~0
+                let mut groups = [u32::MAX; 16]; // set all unused group slots to ~0
                 let filled_groups = xucred.cr_groups.iter().take(xucred.cr_ngroups as usize);
                 for (&src, dst) in filled_groups.zip(&mut groups) {
                     *dst = src.into();
@@ -204,7 +258,61 @@ pub fn peer_credentials(conn: RawFd) -> Result<ConnCredentials, io::Error> {
     }
 }
 
-#[cfg(any(target_os="openbsd", target_os="dragonfly", target_os="netbsd"))]
+/// Get a fixed-size value from a `SOL_LOCAL` socket option.
+#[cfg(target_vendor="apple")]
+fn get_local_option<T: Copy>(conn: RawFd,  option: c_int,  mut value: T) -> Result<T, io::Error> {
+    let ptr = &mut value as *mut T as *mut c_void;
+    let mut size = mem::size_of::<T>() as socklen_t;
+    match unsafe { getsockopt(conn, SOL_LOCAL, option, ptr, &mut size) } {
+        -1 => Err(io::Error::last_os_error()),
+        _ => Ok(value),
+    }
+}
+
+/// Get the pid with `LOCAL_PEERPID`, which was added in macOS 10.8.
+#[cfg(target_vendor="apple")]
+pub fn peer_pid(conn: RawFd) -> Result<NonZeroU32, io::Error> {
+    let pid = get_local_option::<pid_t>(conn, LOCAL_PEERPID, 0)?;
+    NonZeroU32::new(pid as u32).ok_or_else(|| io::Error::new(NotConnected, "socket is not a connection") )
+}
+
+#[cfg(not(target_vendor="apple"))]
+pub fn peer_pid(conn: RawFd) -> Result<NonZeroU32, io::Error> {
+    peer_credentials(conn)?.pid().ok_or_else(|| io::Error::new(Other, "not available") )
+}
+
+/// Get the pid of the process the peer connected on behalf of,
+/// with `LOCAL_PEEREPID`.
+#[cfg(target_vendor="apple")]
+pub fn peer_effective_pid(conn: RawFd) -> Result<NonZeroU32, io::Error> {
+    let pid = get_local_option::<pid_t>(conn, LOCAL_PEEREPID, 0)?;
+    NonZeroU32::new(pid as u32).ok_or_else(|| io::Error::new(NotConnected, "socket is not a connection") )
+}
+
+/// Get the UUID of the executable of the process the peer connected on
+/// behalf of, with `LOCAL_PEEREUUID`.
+#[cfg(target_vendor="apple")]
+pub fn peer_effective_uuid(conn: RawFd) -> Result<[u8; 16], io::Error> {
+    get_local_option(conn, LOCAL_PEEREUUID, [0u8; 16])
+}
+
+#[cfg(any(target_os="openbsd", target_os="netbsd"))]
+pub fn peer_credentials(conn: RawFd) -> Result<ConnCredentials, io::Error> {
+    // initialize to values that don't signify root, to reduce severity of bugs
+    let (mut euid, mut egid): (uid_t, gid_t) = (!0, !0);
+    if unsafe { getpeereid(conn, &mut euid, &mut egid) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    let mut groups = [u32::MAX; 16];
+    groups[0] = egid as u32;
+    Ok(ConnCredentials::MacOsLike {
+        euid: euid as u32,
+        number_of_groups: 1,
+        groups,
+    })
+}
+
+#[cfg(target_os="dragonfly")]
 pub fn peer_credentials(_: RawFd) -> Result<ConnCredentials, io::Error> {
     Err(io::Error::new(Other, "Not yet supported"))
 }
@@ -219,6 +327,123 @@ pub fn peer_credentials(_: RawFd) -> Result<ConnCredentials, io::Error> {
 
 
 
+/// Not in libc yet. Value from `<asm-generic/socket.h>`, which most
+/// architectures use.
+#[cfg(all(
+    any(target_os="linux", target_os="android"),
+    not(any(target_arch="sparc", target_arch="sparc64")),
+))]
+const SO_PEERGROUPS: c_int = 59;
+#[cfg(all(
+    any(target_os="linux", target_os="android"),
+    any(target_arch="sparc", target_arch="sparc64"),
+))]
+const SO_PEERGROUPS: c_int = 0x003d;
+
+/// Get the supplementary groups of the peer when the connection was established.
+///
+/// Uses `SO_PEERGROUPS`, which was added in Linux 4.13.
+#[cfg(any(target_os="linux", target_os="android"))]
+pub fn peer_groups(conn: RawFd) -> Result<Vec<u32>, io::Error> {
+    let mut groups: Vec<gid_t> = vec![0; 16];
+    loop {
+        let ptr = groups.as_mut_ptr() as *mut c_void;
+        let mut size = (groups.len() * mem::size_of::<gid_t>()) as socklen_t;
+        if unsafe { getsockopt(conn, SOL_SOCKET, SO_PEERGROUPS, ptr, &mut size) } == 0 {
+            groups.truncate(size as usize / mem::size_of::<gid_t>());
+            return Ok(groups.into_iter().map(|gid| gid as u32 ).collect());
+        }
+        let error = io::Error::last_os_error();
+        if error.raw_os_error() != Some(libc::ERANGE) {
+            return Err(error);
+        }
+        // size has been set to what is needed
+        let needed = size as usize / mem::size_of::<gid_t>();
+        groups.resize(needed.max(groups.len()+1), 0);
+    }
+}
+
+#[cfg(any(target_os="freebsd", target_vendor="apple"))]
+pub fn peer_groups(conn: RawFd) -> Result<Vec<u32>, io::Error> {
+    peer_credentials(conn).map(|creds| creds.groups().to_vec() )
+}
+
+#[cfg(not(any(
+    target_os="linux", target_os="android", target_os="freebsd", target_vendor="apple",
+)))]
+pub fn peer_groups(_: RawFd) -> Result<Vec<u32>, io::Error> {
+    Err(io::Error::new(Other, "not available"))
+}
+
+
+/// Not in libc for Linux yet.
+#[cfg(all(
+    any(target_os="linux", target_os="android"),
+    not(any(target_arch="mips", target_arch="mips64", target_arch="sparc", target_arch="sparc64")),
+))]
+const SO_PEERSEC: c_int = 31;
+#[cfg(all(
+    any(target_os="linux", target_os="android"),
+    any(target_arch="mips", target_arch="mips64", target_arch="sparc", target_arch="sparc64"),
+))]
+const SO_PEERSEC: c_int = 30;
+
+/// Get the security label (SELinux context, AppArmor profile or Smack label)
+/// of the peer when the connection was established.
+///
+/// A trailing NUL byte is removed.
+#[cfg(any(target_os="linux", target_os="android"))]
+pub fn peer_security_label(conn: RawFd) -> Result<Vec<u8>, io::Error> {
+    let mut label = vec![0u8; 256];
+    loop {
+        let ptr = label.as_mut_ptr() as *mut c_void;
+        let mut size = label.len() as socklen_t;
+        if unsafe { getsockopt(conn, SOL_SOCKET, SO_PEERSEC, ptr, &mut size) } == 0 {
+            label.truncate(size as usize);
+            if label.last() == Some(&0) {
+                label.pop();
+            }
+            return Ok(label);
+        }
+        let error = io::Error::last_os_error();
+        if error.raw_os_error() != Some(libc::ERANGE) {
+            return Err(error);
+        }
+        // size has been set to what is needed
+        let needed = size as usize;
+        label.resize(needed.max(label.len()+1), 0);
+    }
+}
+
+
+/// Not in libc for Linux yet.
+#[cfg(all(
+    any(target_os="linux", target_os="android"),
+    not(any(target_arch="sparc", target_arch="sparc64")),
+))]
+const SO_PEERPIDFD: c_int = 77;
+#[cfg(all(
+    any(target_os="linux", target_os="android"),
+    any(target_arch="sparc", target_arch="sparc64"),
+))]
+const SO_PEERPIDFD: c_int = 0x0056;
+
+/// Get a pidfd referring to the peer process when the connection was
+/// established, using `SO_PEERPIDFD` (added in Linux 6.5).
+///
+/// The pidfd is close-on-exec.
+#[cfg(any(target_os="linux", target_os="android"))]
+pub fn peer_pidfd(conn: RawFd) -> Result<OwnedFd, io::Error> {
+    let mut pidfd: c_int = -1;
+    let ptr = &mut pidfd as *mut c_int as *mut c_void;
+    let mut size = mem::size_of::<c_int>() as socklen_t;
+    if unsafe { getsockopt(conn, SOL_SOCKET, SO_PEERPIDFD, ptr, &mut size) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(unsafe { OwnedFd::from_raw_fd(pidfd) })
+}
+
+
 #[cfg(any(target_os="linux", target_os="android"))]
 pub type RawReceivedCredentials = libc::ucred;
 
@@ -237,7 +462,7 @@ pub type RawReceivedCredentials = libc::ucred;
 /// * OpenBSD doesn't appear to support receiving credentials.
 #[derive(Clone,Copy, PartialEq,Eq,Hash, Debug)]
 pub struct ReceivedCredentials {
-    #[cfg(any(target_os="linux", target_os="android", target_os="dragonfly"))]
+    #[cfg(any(target_os="linux", target_os="android", target_os="netbsd", target_os="dragonfly"))]
     pid: u32,
     #[cfg(any(target_os="linux", target_os="android"))]
     uid: u32,
@@ -269,9 +494,13 @@ pub struct ReceivedCredentials {
         target_os="illumos", target_os="solaris", target_os="macos",
     ))]
     groups: [u32; 5],
+    #[cfg(any(
+        target_os="freebsd", target_os="netbsd", target_os="dragonfly",
+        target_os="illumos", target_os="solaris", target_os="macos",
+    ))]
+    number_of_groups: usize,
 }
 
-#[allow(unused)] // TODO
 impl ReceivedCredentials {
     #[cfg(any(target_os="linux", target_os="android"))]
     pub(crate) fn from_raw(creds: libc::ucred) -> Self {
@@ -281,18 +510,34 @@ impl ReceivedCredentials {
             gid: creds.gid as u32,
         }
     }
+    #[cfg(any(target_os="freebsd", target_os="netbsd"))]
+    pub(crate) fn from_sockcred(creds: &libc::sockcred,  groups: [u32; 5],  number_of_groups: usize)
+    -> Self {
+        ReceivedCredentials {
+            #[cfg(target_os="netbsd")]
+            pid: creds.sc_pid as u32,
+            real_uid: creds.sc_uid,
+            effective_uid: creds.sc_euid,
+            real_gid: creds.sc_gid,
+            effective_gid: creds.sc_egid,
+            groups,
+            number_of_groups,
+        }
+    }
 
     /// The pid of the peer.
     ///
-    /// This information is only available on Linux, Android and DragonFly BSD.
+    /// This information is only available on Linux, Android, NetBSD and
+    /// DragonFly BSD.
     pub fn pid(&self) -> Option<u32> {
-        #[cfg(any(target_os="linux", target_os="android", target_os="dragonfly"))] {
+        #[cfg(any(target_os="linux", target_os="android", target_os="netbsd", target_os="dragonfly"))] {
             Some(self.pid)
         }
-        #[cfg(not(any(target_os="linux", target_os="android", target_os="dragonfly")))] {
+        #[cfg(not(any(target_os="linux", target_os="android", target_os="netbsd", target_os="dragonfly")))] {
             None
         }
     }
+    /// The effective uid of the peer, or the uid it sent on Linux.
     pub fn effective_or_sent_uid(&self) -> u32 {
         #[cfg(any(target_os="linux", target_os="android"))] {
             self.uid
@@ -311,6 +556,7 @@ impl ReceivedCredentials {
             unreachable!("struct cannot be created on unsupported OSes")
         }
     }
+    /// The real uid of the peer, or the uid it sent on Linux.
     pub fn real_or_sent_uid(&self) -> u32 {
         #[cfg(any(target_os="linux", target_os="android"))] {
             self.uid
@@ -329,6 +575,9 @@ impl ReceivedCredentials {
             unreachable!("struct cannot be created on unsupported OSes")
         }
     }
+    /// The effective gid of the peer, or the gid it sent on Linux.
+    ///
+    /// This information is not available on DragonFly BSD.
     pub fn effective_or_sent_gid(&self) -> Option<u32> {
         #[cfg(any(target_os="linux", target_os="android"))] {
             Some(self.gid)
@@ -347,6 +596,7 @@ impl ReceivedCredentials {
             None
         }
     }
+    /// The real gid of the peer, or the gid it sent on Linux.
     pub fn real_or_sent_gid(&self) -> u32 {
         #[cfg(any(target_os="linux", target_os="android"))] {
             self.gid
@@ -367,14 +617,15 @@ impl ReceivedCredentials {
     }
     /// Get the peer's group memberships.
     ///
-    /// This information is only available on macOS, the BSDs and and Illumos.
+    /// This information is only available on macOS, the BSDs and and Illumos,
+    /// and only the first five groups are returned.
     /// On other operating systems an empty slice is returned.
     pub fn groups(&self) -> &[u32] {
         #[cfg(any(
             target_os="freebsd", target_os="netbsd", target_os="dragonfly",
             target_os="illumos", target_os="solaris", target_os="macos",
         ))] {
-            &self.groups[..]
+            &self.groups[..self.number_of_groups]
         }
         #[cfg(not(any(
             target_os="freebsd", target_os="netbsd", target_os="dragonfly",
@@ -0,0 +1,174 @@
+use std::io::{self, ErrorKind, IoSliceMut};
+use std::os::unix::io::{RawFd, AsRawFd};
+use std::os::unix::net::UnixDatagram;
+use std::time::{Duration, Instant};
+
+use crate::addr::{UnixSocketAddr, ToUnixSocketAddr};
+use crate::ancillary::recv_ancillary;
+use crate::bound_path::BoundPath;
+use crate::traits::UnixDatagramExt;
+
+/// A client for request/response protocols over unix datagrams,
+/// such as the control sockets of many daemons.
+///
+/// The client is bound to an address servers can reply to: an auto-bound
+/// abstract address on Linux and Android, and a random path in the
+/// temporary directory elsewhere, which is removed when the client is dropped.
+/// It's also connected to the server, so datagrams from other sockets are
+/// not received.
+///
+/// Requests are resent if no reply is received within the timeout,
+/// so they should be safe to repeat.
+/// Replies are not matched with requests: Datagrams that are waiting when a
+/// request is sent or resent are discarded, but a reply to an earlier
+/// request that arrives after that is taken as the reply.
+/// Protocols that need to tell replies apart should include an identifier
+/// in requests and replies and check it.
+///
+/// # Examples
+///
+/// ```
+/// use std::os::unix::net::UnixDatagram;
+/// use std::time::Duration;
+/// use uds::{DatagramClient, UnixDatagramExt, UnixSocketAddr};
+///
+/// let addr = UnixSocketAddr::new_unique().unwrap();
+/// let server = UnixDatagram::bind_unix_addr(addr).unwrap();
+/// let handle = std::thread::spawn(move || {
+///     let mut buf = [0; 10];
+///     let (len, client) = server.recv_from_unix_addr(&mut buf).unwrap();
+///     assert_eq!(&buf[..len], b"status");
+///     server.send_to_unix_addr(b"running", client).unwrap();
+/// });
+///
+/// let client = DatagramClient::new(addr).unwrap()
+///     .timeout(Duration::from_secs(5))
+///     .retries(0);
+/// let mut reply = [0; 10];
+/// assert_eq!(client.request(b"status", &mut reply).unwrap(), (7, false));
+/// assert_eq!(&reply[..7], b"running");
+/// handle.join().unwrap();
+/// # if let Some(path) = addr.as_pathname() { let _ = std::fs::remove_file(path); }
+/// ```
+#[derive(Debug)]
+pub struct DatagramClient {
+    socket: UnixDatagram,
+    server: UnixSocketAddr,
+    timeout: Duration,
+    retries: u32,
+    // declared after the socket so that it's closed before the file is removed
+    _reply_path: BoundPath,
+}
+
+impl DatagramClient {
+    /// Create a client for the server bound to `server`,
+    /// which waits one second for replies and retries twice.
+    pub fn new<A: ToUnixSocketAddr>(server: A) -> Result<Self, io::Error> {
+        let server = server.to_unix_addr()?;
+        #[cfg(any(target_os="linux", target_os="android"))]
+        let reply_addr = UnixSocketAddr::new_unspecified();
+        #[cfg(not(any(target_os="linux", target_os="android")))]
+        let reply_addr = UnixSocketAddr::new_unique()?;
+        let (socket, reply_path) = BoundPath::bind(reply_addr, UnixDatagram::bind_unix_addr)?;
+        socket.connect_to_unix_addr(server)?;
+        Ok(DatagramClient {
+            socket,
+            server,
+            timeout: Duration::from_secs(1),
+            retries: 2,
+            _reply_path: reply_path,
+        })
+    }
+    /// Set how long to wait for a reply before resending the request or
+    /// giving up.
+    ///
+    /// A zero duration makes requests fail with an error of kind `InvalidInput`.
+    pub fn timeout(mut self,  timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+    /// Set how many times to resend a request that got no reply.
+    pub fn retries(mut self,  retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Get the address replies are sent to.
+    pub fn local_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+        self.socket.local_unix_addr()
+    }
+    /// Get the address of the server.
+    pub fn server_addr(&self) -> UnixSocketAddr {
+        self.server
+    }
+    /// Get the underlying socket.
+    pub fn get_ref(&self) -> &UnixDatagram {
+        &self.socket
+    }
+
+    /// Send a request and wait for the reply.
+    ///
+    /// The returned `bool` indicates whether the reply was truncated due to
+    /// `reply` being too small.
+    ///
+    /// The read timeout of the socket is changed while waiting,
+    /// and restored before returning.
+    ///
+    /// # Errors
+    ///
+    /// Fails with an error of kind `TimedOut` if no reply is received after
+    /// all retries, and with the error from sending if the server isn't
+    /// running.
+    pub fn request(&self,  request: &[u8],  reply: &mut[u8]) -> Result<(usize, bool), io::Error> {
+        if self.timeout == Duration::from_secs(0) {
+            return Err(io::Error::new(ErrorKind::InvalidInput, "timeout cannot be zero"));
+        }
+        let read_timeout = self.socket.read_timeout()?;
+        let result = self.send_and_wait(request, reply);
+        let restored = self.socket.set_read_timeout(read_timeout);
+        let received = result?;
+        restored?;
+        Ok(received)
+    }
+
+    fn send_and_wait(&self,  request: &[u8],  reply: &mut[u8]) -> Result<(usize, bool), io::Error> {
+        for _ in 0..=self.retries {
+            self.discard_late_replies()?;
+            self.socket.send(request)?;
+            // wait forever if the timeout is too long to represent
+            let deadline = Instant::now().checked_add(self.timeout);
+            loop {
+                let remaining = deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()) );
+                if remaining == Some(Duration::from_secs(0)) {
+                    break;
+                }
+                self.socket.set_read_timeout(remaining)?;
+                match recv_ancillary(self.as_raw_fd(), None, 0, &mut[IoSliceMut::new(reply)], &mut[]) {
+                    Ok((len, ancillary)) => return Ok((len, ancillary.message_truncated())),
+                    Err(ref e) if e.kind() == ErrorKind::WouldBlock => {}
+                    Err(ref e) if e.kind() == ErrorKind::TimedOut => {}
+                    Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+        Err(io::Error::new(ErrorKind::TimedOut, "no reply from server"))
+    }
+
+    /// Discard replies to earlier requests or attempts that arrived late.
+    fn discard_late_replies(&self) -> Result<(), io::Error> {
+        loop {
+            match self.socket.try_recv(&mut[0; 1]) {
+                Ok(_) => {}
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => break Ok(()),
+                Err(e) => break Err(e),
+            }
+        }
+    }
+}
+
+impl AsRawFd for DatagramClient {
+    fn as_raw_fd(&self) -> RawFd {
+        self.socket.as_raw_fd()
+    }
+}
@@ -0,0 +1,122 @@
+use std::io::{self, ErrorKind, IoSlice};
+
+use crate::seqpacket::UnixSeqpacketConn;
+
+/// Header byte of every fragment except the last one of a message.
+const MORE_FRAGMENTS: u8 = 1;
+/// Header byte of the last fragment of a message.
+const LAST_FRAGMENT: u8 = 0;
+
+/// A seqpacket connection that splits messages bigger than what can be sent
+/// in one packet into multiple packets, and reassembles them when received.
+///
+/// Every packet starts with a header byte that says whether more fragments
+/// of the message follow.
+/// (`MSG_EOR` can't be used to mark the end of messages, as Linux ignores it
+/// for unix domain sockets and makes every `send()` a separate record.)
+/// Both sides of the connection must therefore use this type.
+///
+/// # Examples
+///
+#[cfg_attr(not(target_vendor="apple"), doc="```")]
+#[cfg_attr(target_vendor="apple", doc="```no_run")]
+/// use uds::{UnixSeqpacketConn, FragmentingSeqpacketConn};
+///
+/// let (a, b) = UnixSeqpacketConn::pair().unwrap();
+/// let a = FragmentingSeqpacketConn::with_fragment_size(a, 1000).unwrap();
+/// let b = FragmentingSeqpacketConn::new(b).unwrap();
+/// let message = vec![b'm'; 3500];
+/// a.send(&message).unwrap();
+/// assert_eq!(b.recv().unwrap(), Some(message));
+/// drop(a);
+/// assert_eq!(b.recv().unwrap(), None);
+/// ```
+#[derive(Debug)]
+pub struct FragmentingSeqpacketConn {
+    conn: UnixSeqpacketConn,
+    fragment_size: usize,
+}
+
+impl FragmentingSeqpacketConn {
+    /// Wrap a connection, and send packets of up to
+    /// [`max_send_size()`](struct.UnixSeqpacketConn.html#method.max_send_size).
+    pub fn new(conn: UnixSeqpacketConn) -> Result<Self, io::Error> {
+        let fragment_size = conn.max_send_size()?;
+        Self::with_fragment_size(conn, fragment_size)
+    }
+
+    /// Wrap a connection, and send packets of up to `fragment_size` bytes,
+    /// including the header byte.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `fragment_size` is less than two.
+    pub fn with_fragment_size(conn: UnixSeqpacketConn,  fragment_size: usize)
+    -> Result<Self, io::Error> {
+        if fragment_size < 2 {
+            let message = "fragment size must have room for the header and at least one byte";
+            return Err(io::Error::new(ErrorKind::InvalidInput, message));
+        }
+        Ok(FragmentingSeqpacketConn { conn, fragment_size })
+    }
+
+    /// Send a message of any length.
+    ///
+    /// If sending one of the fragments fails, the peer will have received
+    /// an incomplete message, so the connection should not be used after that.
+    pub fn send(&self,  message: &[u8]) -> Result<(), io::Error> {
+        let mut fragments = message.chunks(self.fragment_size - 1).peekable();
+        if fragments.peek().is_none() {
+            self.conn.send(&[LAST_FRAGMENT])?;
+        }
+        while let Some(fragment) = fragments.next() {
+            let header = if fragments.peek().is_some() {MORE_FRAGMENTS} else {LAST_FRAGMENT};
+            self.conn.send_vectored(&[IoSlice::new(&[header]), IoSlice::new(fragment)])?;
+        }
+        Ok(())
+    }
+
+    /// Receive a complete message.
+    ///
+    /// Returns `None` if the peer closed the connection or shut down its
+    /// write half between messages.
+    ///
+    /// # Errors
+    ///
+    /// Fails with an error of kind `UnexpectedEof` if the connection ends in
+    /// the middle of a message, and `InvalidData` if a packet doesn't start
+    /// with a valid header.
+    pub fn recv(&self) -> Result<Option<Vec<u8>>, io::Error> {
+        let mut message = Vec::new();
+        let mut fragment = Vec::new();
+        loop {
+            self.conn.recv_to_vec(&mut fragment)?;
+            match fragment.split_first() {
+                None if message.is_empty() => return Ok(None),
+                None => {
+                    let error = "connection ended in the middle of a message";
+                    return Err(io::Error::new(ErrorKind::UnexpectedEof, error));
+                }
+                Some((&MORE_FRAGMENTS, content)) => message.extend_from_slice(content),
+                Some((&LAST_FRAGMENT, content)) => {
+                    message.extend_from_slice(content);
+                    return Ok(Some(message));
+                }
+                Some(_) => {
+                    let error = "received packet is not a message fragment";
+                    return Err(io::Error::new(ErrorKind::InvalidData, error));
+                }
+            }
+        }
+    }
+
+    /// Get the wrapped connection.
+    pub fn get_ref(&self) -> &UnixSeqpacketConn {
+        &self.conn
+    }
+
+    /// Unwrap the connection.
+    pub fn into_inner(self) -> UnixSeqpacketConn {
+        self.conn
+    }
+}
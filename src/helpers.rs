@@ -1,17 +1,25 @@
 /* See each function for copyright holders */
 
-/// Functions to handle OS differences.
-/// Several adapted from std.
+//! Functions to handle OS differences.
+//! Several adapted from std.
 
 use std::os::unix::io::{RawFd, AsRawFd, IntoRawFd};
 use std::io::{self, ErrorKind};
-use std::mem;
-
-use libc::{c_int, sockaddr, socklen_t, AF_UNIX};
+use std::net::Shutdown;
+use std::{mem, ptr};
+use std::time::{Duration, Instant};
+use std::thread;
+use std::fs;
+use std::os::unix::fs::FileTypeExt;
+
+use libc::{c_int, c_short, sockaddr, socklen_t, AF_UNIX, SOCK_STREAM};
+use libc::{poll, pollfd, POLLOUT, EINPROGRESS, EPROTOTYPE};
 use libc::{bind, connect, getsockname, getpeername};
 use libc::{socket, accept, close, listen, socketpair};
-use libc::{ioctl, FIONBIO, FIOCLEX, FIONCLEX};
-use libc::{fcntl, F_DUPFD_CLOEXEC, EINVAL, dup};
+use libc::{ioctl, FIONBIO, FIOCLEX, FIONCLEX, FIONREAD};
+use libc::{fcntl, F_DUPFD_CLOEXEC, F_GETFL, O_NONBLOCK, EINVAL, dup};
+use libc::{SHUT_RD, SHUT_WR, SHUT_RDWR};
+use libc::{getsockopt, SO_ERROR, SO_SNDBUF, SO_TYPE, SO_ACCEPTCONN, EMSGSIZE};
 #[cfg(any(target_os="illumos", target_os="solaris"))]
 use libc::{F_GETFD, F_SETFD, FD_CLOEXEC};
 
@@ -20,8 +28,21 @@ use libc::{SOCK_CLOEXEC, SOCK_NONBLOCK};
 #[cfg(not(any(target_vendor="apple", target_os="netbsd", target_os="illumos", target_os="solaris")))]
 // FIXME netbsd and illumos has it, but libc doesn't expose it
 use libc::{accept4, ENOSYS};
+use libc::{SOL_SOCKET, c_void};
+#[cfg(not(any(target_os="illumos", target_os="solaris")))]
+use libc::setsockopt;
+#[cfg(any(target_os="linux", target_os="android"))]
+use libc::SO_TIMESTAMPNS;
+#[cfg(not(any(target_os="linux", target_os="android", target_os="illumos", target_os="solaris")))]
+use libc::SO_TIMESTAMP;
 #[cfg(target_vendor="apple")]
-use libc::{setsockopt, SOL_SOCKET, SO_NOSIGPIPE, c_void};
+use libc::SO_NOSIGPIPE;
+#[cfg(any(target_os="linux", target_os="android"))]
+use libc::SO_PASSCRED;
+#[cfg(target_os="linux")]
+use libc::{SYS_close_range, c_uint};
+#[cfg(any(target_os="linux", target_os="android"))]
+use libc::SPLICE_F_MOVE;
 
 use crate::addr::*;
 
@@ -63,7 +84,271 @@ pub fn set_nonblocking(fd: RawFd,  nonblocking: bool) -> Result<(), io::Error> {
     Ok(())
 }
 
+/// Check whether `O_NONBLOCK` is set.
+pub fn is_nonblocking(fd: RawFd) -> Result<bool, io::Error> {
+    let flags = cvt!(unsafe { fcntl(fd, F_GETFL) })?;
+    Ok(flags & O_NONBLOCK != 0)
+}
+
+/// Get which of `events`, `POLLHUP` and `POLLERR` are ready on `fd`,
+/// without waiting.
+pub fn poll_now(fd: RawFd,  events: c_short) -> Result<c_short, io::Error> {
+    let mut pollfd = pollfd { fd, events, revents: 0 };
+    cvt_r!(unsafe { poll(&mut pollfd, 1, 0) })?;
+    Ok(pollfd.revents)
+}
+/// Wait until `events` are ready on `fd`, or fail with `TimedOut` once
+/// `deadline` has passed.
+pub fn wait_until(fd: RawFd,  events: c_short,  deadline: Instant) -> Result<(), io::Error> {
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        // round up to not busy-loop when less than a millisecond remains
+        let ms = remaining.as_millis() + (remaining.subsec_nanos() % 1_000_000 != 0) as u128;
+        let timeout = if ms > c_int::MAX as u128 {c_int::MAX} else {ms as c_int};
+        let mut pollfd = pollfd { fd, events, revents: 0 };
+        match cvt_r!(unsafe { poll(&mut pollfd, 1, timeout) })? {
+            0 if remaining.as_nanos() == 0 => {
+                return Err(io::Error::new(ErrorKind::TimedOut, "deadline has passed"));
+            }
+            0 => {/* the deadline might not have been reached yet due to clock granularity */}
+            _ => return Ok(()),
+        }
+    }
+}
+
+/// Wait until `events` are ready on `fd`, or fail with `TimedOut` after
+/// `timeout`. Waits indefinitely if `timeout` is `None`.
+pub fn wait_for(fd: RawFd,  events: c_short,  timeout: Option<Duration>)
+-> Result<(), io::Error> {
+    match timeout.and_then(|timeout| Instant::now().checked_add(timeout) ) {
+        Some(deadline) => wait_until(fd, events, deadline),
+        None => {
+            let mut pollfd = pollfd { fd, events, revents: 0 };
+            cvt_r!(unsafe { poll(&mut pollfd, 1, -1) })?;
+            Ok(())
+        }
+    }
+}
+
+/// Connect a nonblocking socket, waiting at most `timeout` for the
+/// connection to be established.
+///
+/// Linux fails with `EAGAIN` instead of `EINPROGRESS` when the listener's
+/// backlog is full, and provides no way to wait for room, so retry then.
+pub fn connect_timeout(socket: RawFd,  addr: &UnixSocketAddr,  timeout: Duration)
+-> Result<(), io::Error> {
+    if timeout == Duration::from_secs(0) {
+        return Err(io::Error::new(ErrorKind::InvalidInput, "cannot connect with zero timeout"));
+    }
+    let deadline = Instant::now() + timeout;
+    loop {
+        match connect_to(socket, addr) {
+            Err(ref e) if e.raw_os_error() == Some(EINPROGRESS) => {
+                wait_until(socket, POLLOUT, deadline)?;
+                return match take_error(socket)? {
+                    Some(error) => Err(error),
+                    None => Ok(()),
+                };
+            }
+            Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining == Duration::from_secs(0) {
+                    return Err(io::Error::new(ErrorKind::TimedOut, "connect timed out"));
+                }
+                thread::sleep(remaining.min(Duration::from_millis(10)));
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Move up to `len` bytes between two file descriptors, where one of them
+/// must be a pipe, with `splice()`.
+#[cfg(any(target_os="linux", target_os="android"))]
+pub fn splice(from: RawFd,  to: RawFd,  len: usize) -> Result<usize, io::Error> {
+    let moved = cvt_r!(unsafe {
+        libc::splice(from, ptr::null_mut(), to, ptr::null_mut(), len, SPLICE_F_MOVE)
+    })?;
+    Ok(moved as usize)
+}
+
+/// Safe wrapper around `shutdown()`.
+pub fn shutdown(fd: RawFd,  how: Shutdown) -> Result<(), io::Error> {
+    let how = match how {
+        Shutdown::Read => SHUT_RD,
+        Shutdown::Write => SHUT_WR,
+        Shutdown::Both => SHUT_RDWR,
+    };
+    cvt!(unsafe { libc::shutdown(fd, how) })?;
+    Ok(())
+}
+/// Get the number of bytes that can be received without blocking,
+/// with `FIONREAD`.
+pub fn bytes_available(fd: RawFd) -> Result<usize, io::Error> {
+    let mut available: c_int = 0;
+    cvt!(unsafe { ioctl(fd, FIONREAD, &mut available) })?;
+    Ok(available as usize)
+}
+/// Get the size of the biggest packet or datagram that can be sent,
+/// based on `SO_SNDBUF`.
+pub fn max_send_size(fd: RawFd) -> Result<usize, io::Error> {
+    let mut size: c_int = 0;
+    let mut len = mem::size_of::<c_int>() as socklen_t;
+    cvt!(unsafe {
+        getsockopt(fd, SOL_SOCKET, SO_SNDBUF, &mut size as *mut c_int as *mut c_void, &mut len)
+    })?;
+    // Linux reserves 32 bytes of the buffer for bookkeeping.
+    #[cfg(any(target_os="linux", target_os="android"))]
+    let size = size.saturating_sub(32);
+    Ok(size as usize)
+}
+/// Replace the opaque error for `EMSGSIZE` with one that says what's wrong.
+pub fn explain_too_big(error: io::Error) -> io::Error {
+    if error.raw_os_error() == Some(EMSGSIZE) {
+        let message = "packet is bigger than the socket can send, see max_send_size()";
+        io::Error::new(ErrorKind::InvalidInput, message)
+    } else {
+        error
+    }
+}
+/// Get `SO_TYPE`.
+pub fn socket_type(fd: RawFd) -> Result<c_int, io::Error> {
+    let mut socket_type: c_int = 0;
+    let mut len = mem::size_of::<c_int>() as socklen_t;
+    let ptr = &mut socket_type as *mut c_int as *mut c_void;
+    cvt!(unsafe { getsockopt(fd, SOL_SOCKET, SO_TYPE, ptr, &mut len) })?;
+    Ok(socket_type)
+}
+/// Check `SO_ACCEPTCONN`.
+pub fn is_listening(fd: RawFd) -> Result<bool, io::Error> {
+    let mut listening: c_int = 0;
+    let mut len = mem::size_of::<c_int>() as socklen_t;
+    let ptr = &mut listening as *mut c_int as *mut c_void;
+    cvt!(unsafe { getsockopt(fd, SOL_SOCKET, SO_ACCEPTCONN, ptr, &mut len) })?;
+    Ok(listening != 0)
+}
+/// Get and clear `SO_ERROR`.
+pub fn take_error(fd: RawFd) -> Result<Option<io::Error>, io::Error> {
+    let mut error: c_int = 0;
+    let mut len = mem::size_of::<c_int>() as socklen_t;
+    cvt!(unsafe {
+        getsockopt(fd, SOL_SOCKET, SO_ERROR, &mut error as *mut c_int as *mut c_void, &mut len)
+    })?;
+    Ok(if error == 0 {None} else {Some(io::Error::from_raw_os_error(error))})
+}
+
+/// Get the value of a boolean `SOL_SOCKET` option.
+#[cfg(any(target_os="linux", target_os="android"))]
+pub fn get_socket_flag(fd: RawFd,  option: c_int) -> Result<bool, io::Error> {
+    let mut value: c_int = 0;
+    let mut len = mem::size_of::<c_int>() as socklen_t;
+    cvt!(unsafe {
+        getsockopt(fd, SOL_SOCKET, option, &mut value as *mut c_int as *mut c_void, &mut len)
+    })?;
+    Ok(value != 0)
+}
+/// Set a boolean `SOL_SOCKET` option.
+#[cfg(any(target_os="linux", target_os="android"))]
+pub fn set_socket_flag(fd: RawFd,  option: c_int,  value: bool) -> Result<(), io::Error> {
+    let value = value as c_int;
+    let size = mem::size_of::<c_int>() as socklen_t;
+    cvt!(unsafe {
+        setsockopt(fd, SOL_SOCKET, option, &value as *const c_int as *const c_void, size)
+    })?;
+    Ok(())
+}
+
+/// Enable / disable `SO_PASSCRED`, which makes Linux attach the credentials
+/// of the sender to every received message.
+#[cfg(any(target_os="linux", target_os="android"))]
+pub fn set_passcred(fd: RawFd,  pass: bool) -> Result<(), io::Error> {
+    let pass = pass as c_int;
+    let size = mem::size_of::<c_int>() as socklen_t;
+    cvt!(unsafe {
+        setsockopt(fd, SOL_SOCKET, SO_PASSCRED, &pass as *const c_int as *const c_void, size)
+    })?;
+    Ok(())
+}
 
+/// Enable / disable `LOCAL_CREDS`, which makes FreeBSD and NetBSD attach the
+/// credentials of the sender to every received datagram.
+#[cfg(any(target_os="freebsd", target_os="netbsd"))]
+pub fn set_local_creds(fd: RawFd,  pass: bool) -> Result<(), io::Error> {
+    let pass = pass as c_int;
+    let size = mem::size_of::<c_int>() as socklen_t;
+    cvt!(unsafe {
+        // level is 0, as the option is handled by the unix domain socket code
+        setsockopt(fd, 0, libc::LOCAL_CREDS, &pass as *const c_int as *const c_void, size)
+    })?;
+    Ok(())
+}
+
+/// Not in libc for Linux yet.
+#[cfg(all(
+    any(target_os="linux", target_os="android"),
+    not(any(target_arch="sparc", target_arch="sparc64")),
+))]
+const SO_PASSSEC: c_int = 34;
+#[cfg(all(
+    any(target_os="linux", target_os="android"),
+    any(target_arch="sparc", target_arch="sparc64"),
+))]
+const SO_PASSSEC: c_int = 0x001f;
+
+/// Enable / disable `SO_PASSSEC`, which makes Linux attach the security label
+/// of the sender to every received message.
+#[cfg(any(target_os="linux", target_os="android"))]
+pub fn set_passsec(fd: RawFd,  pass: bool) -> Result<(), io::Error> {
+    let pass = pass as c_int;
+    let size = mem::size_of::<c_int>() as socklen_t;
+    cvt!(unsafe {
+        setsockopt(fd, SOL_SOCKET, SO_PASSSEC, &pass as *const c_int as *const c_void, size)
+    })?;
+    Ok(())
+}
+
+/// Enable / disable receiving a timestamp with every message, with
+/// `SO_TIMESTAMPNS` on Linux and `SO_TIMESTAMP` elsewhere.
+#[cfg(not(any(target_os="illumos", target_os="solaris")))]
+pub fn set_timestamps(fd: RawFd,  enable: bool) -> Result<(), io::Error> {
+    #[cfg(any(target_os="linux", target_os="android"))]
+    let option = SO_TIMESTAMPNS;
+    #[cfg(not(any(target_os="linux", target_os="android")))]
+    let option = SO_TIMESTAMP;
+    let enable = enable as c_int;
+    let size = mem::size_of::<c_int>() as socklen_t;
+    cvt!(unsafe {
+        setsockopt(fd, SOL_SOCKET, option, &enable as *const c_int as *const c_void, size)
+    })?;
+    Ok(())
+}
+
+/// Close multiple file descriptors,
+/// with `close_range()` for consecutive ones on Linux.
+pub fn close_fds(fds: &mut[RawFd]) {
+    fds.sort_unstable();
+    let mut remaining = &fds[..];
+    while let Some(&first) = remaining.first() {
+        let consecutive = remaining.iter()
+            .enumerate()
+            .take_while(|&(i, &fd)| fd as usize == first as usize + i )
+            .count();
+        let (run, rest) = remaining.split_at(consecutive);
+        remaining = rest;
+        #[cfg(target_os="linux")]
+        if run.len() > 1 {
+            let last = run[run.len()-1];
+            // fails with ENOSYS on Linux older than 5.9
+            let closed = unsafe { libc::syscall(SYS_close_range, first as c_uint, last as c_uint, 0) };
+            if closed == 0 {
+                continue;
+            }
+        }
+        for &fd in run {
+            unsafe { close(fd) };
+        }
+    }
+}
 
 type SetSide = unsafe extern "C" fn(RawFd, *const sockaddr, socklen_t) -> c_int;
 unsafe fn set_unix_addr(socket: RawFd,  set_side: SetSide,  addr: &UnixSocketAddr)
@@ -88,6 +373,64 @@ pub fn bind_to(socket: RawFd,  addr: &UnixSocketAddr) -> Result<(), io::Error> {
 pub fn connect_to(socket: RawFd,  addr: &UnixSocketAddr) -> Result<(), io::Error> {
     unsafe { set_unix_addr(socket, connect, addr) }
 }
+/// Connect a nonblocking socket, treating `EINPROGRESS` as success.
+pub fn connect_in_progress_ok(socket: RawFd,  addr: &UnixSocketAddr) -> Result<(), io::Error> {
+    match connect_to(socket, addr) {
+        Err(ref e) if e.raw_os_error() == Some(EINPROGRESS) => Ok(()),
+        result => result,
+    }
+}
+/// Remove the socket file at `addr` if no socket is bound to it anymore,
+/// probing it with a socket of `socket_type`.
+///
+/// Fails with `AddrInUse` if a socket is still bound to it,
+/// with `AlreadyExists` if the path isn't a socket file,
+/// and with `error` for abstract addresses or if probing fails otherwise.
+pub fn remove_stale_socket(addr: &UnixSocketAddr,  error: io::Error,  socket_type: c_int)
+-> Result<(), io::Error> {
+    let path = match addr.as_pathname() {
+        Some(path) => path,
+        None => return Err(error), // abstract addresses disappear with the socket
+    };
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => {}
+        Ok(_) => {
+            let message = "the path exists and is not a socket";
+            return Err(io::Error::new(ErrorKind::AlreadyExists, message));
+        }
+        Err(_) => return Err(error),
+    }
+    // nonblocking to not wait for a server whose backlog is full
+    let probe = Socket::new(socket_type, true)?;
+    match connect_to(probe.as_raw_fd(), addr) {
+        Err(ref e) if e.kind() == ErrorKind::ConnectionRefused => {
+            match fs::remove_file(path) {
+                Err(ref e) if e.kind() == ErrorKind::NotFound => Ok(()),
+                result => result,
+            }
+        }
+        // a socket of another type is bound to the path
+        Err(ref e) if e.raw_os_error() == Some(EPROTOTYPE) => {
+            Err(io::Error::new(ErrorKind::AddrInUse, "another socket is bound to the path"))
+        }
+        Err(ref e) if e.kind() != ErrorKind::WouldBlock && e.raw_os_error() != Some(EINPROGRESS) => {
+            Err(error)
+        }
+        _ => Err(io::Error::new(ErrorKind::AddrInUse, "another server is listening on the path")),
+    }
+}
+/// Bind to `addr`, and if a stale socket file is in the way,
+/// remove it and try again.
+///
+/// See `remove_stale_socket()` for the errors.
+pub fn bind_to_taking_over(socket: RawFd,  addr: &UnixSocketAddr,  socket_type: c_int)
+-> Result<(), io::Error> {
+    match bind_to(socket, addr) {
+        Err(e) if e.kind() == ErrorKind::AddrInUse => remove_stale_socket(addr, e, socket_type)?,
+        result => return result,
+    }
+    bind_to(socket, addr)
+}
 
 type GetSide = unsafe extern "C" fn(RawFd, *mut sockaddr, *mut socklen_t) -> c_int;
 unsafe fn get_unix_addr(socket: RawFd,  get_side: GetSide)
@@ -176,40 +519,90 @@ impl Socket {
         Ok(socket)
     }
 
+    /// Create a socket bound to `addr`.
+    pub fn bound(socket_type: c_int,  nonblocking: bool,  addr: &UnixSocketAddr)
+    -> Result<Self, io::Error> {
+        let socket = Self::new(socket_type, nonblocking)?;
+        bind_to(socket.0, addr)?;
+        Ok(socket)
+    }
+
+    /// Create a stream socket connected to `addr`,
+    /// waiting at most `timeout` for the connection to be accepted.
+    pub fn connected_with_timeout(nonblocking: bool,  addr: &UnixSocketAddr,  timeout: Duration)
+    -> Result<Self, io::Error> {
+        let socket = Self::new(SOCK_STREAM, true)?;
+        connect_timeout(socket.0, addr, timeout)?;
+        if !nonblocking {
+            set_nonblocking(socket.0, false)?;
+        }
+        Ok(socket)
+    }
+
     pub fn accept_from(fd: RawFd,  nonblocking: bool)
     -> Result<(Self, UnixSocketAddr), io::Error> {
         unsafe { UnixSocketAddr::new_from_ffi(|addr_ptr, len_ptr| {
-            // Use accept4() to set close-on-exec atomically if possible.
-            // ENOSYS is handled for compatibility with Linux < 2.6.28,
-            // because Rust std still supports Linux 2.6.18.
-            // (used by RHEL 5 which doesn't reach EOL until November 2020).
-            #[cfg(any(
-                target_os="linux", target_os="android",
-                target_os="freebsd", target_os="dragonfly", target_os="openbsd"
-                // FIXME netbsd and illumos also has this, but libc doesn't expose it
-            ))] {
-                let flags = SOCK_CLOEXEC | if nonblocking {SOCK_NONBLOCK} else {0};
-                match cvt_r!(accept4(fd, addr_ptr, len_ptr, flags)) {
-                    Ok(fd) => return Ok(Socket(fd)),
-                    Err(ref e) if e.raw_os_error() == Some(ENOSYS) => {/*try normal accept()*/},
-                    Err(e) => return Err(e),
-                }
+            Self::accept_into(fd, addr_ptr, len_ptr, nonblocking, true)
+        }) }
+    }
+
+    /// Accept a connection without getting the peer address.
+    pub fn accept_without_addr(fd: RawFd,  nonblocking: bool) -> Result<Self, io::Error> {
+        unsafe { Self::accept_into(fd, ptr::null_mut(), ptr::null_mut(), nonblocking, true) }
+    }
+
+    /// Accept a connection without setting close-on-exec,
+    /// so that it's inherited by child processes.
+    pub fn accept_inheritable(fd: RawFd,  nonblocking: bool)
+    -> Result<(Self, UnixSocketAddr), io::Error> {
+        unsafe { UnixSocketAddr::new_from_ffi(|addr_ptr, len_ptr| {
+            Self::accept_into(fd, addr_ptr, len_ptr, nonblocking, false)
+        }) }
+    }
+
+    /// `addr_ptr` and `len_ptr` can both be null.
+    unsafe fn accept_into(fd: RawFd,  addr_ptr: *mut sockaddr,  len_ptr: *mut socklen_t,
+            nonblocking: bool,  cloexec: bool,
+    ) -> Result<Self, io::Error> {
+        // Use accept4() to set close-on-exec atomically if possible.
+        // ENOSYS is handled for compatibility with Linux < 2.6.28,
+        // because Rust std still supports Linux 2.6.18.
+        // (used by RHEL 5 which doesn't reach EOL until November 2020).
+        #[cfg(any(
+            target_os="linux", target_os="android",
+            target_os="freebsd", target_os="dragonfly", target_os="openbsd"
+            // FIXME netbsd and illumos also has this, but libc doesn't expose it
+        ))] {
+            let flags = if cloexec {SOCK_CLOEXEC} else {0}
+                | if nonblocking {SOCK_NONBLOCK} else {0};
+            match cvt_r!(accept4(fd, addr_ptr, len_ptr, flags)) {
+                Ok(fd) => return Ok(Socket(fd)),
+                Err(ref e) if e.raw_os_error() == Some(ENOSYS) => {/*try normal accept()*/},
+                Err(e) => return Err(e),
             }
+        }
 
-            // Portable but not as efficient:
-            let fd = cvt_r!(accept(fd, addr_ptr, len_ptr))?;
-            let socket = Socket(fd);
+        // Portable but not as efficient:
+        let fd = cvt_r!(accept(fd, addr_ptr, len_ptr))?;
+        let socket = Socket(fd);
+        if cloexec {
             set_cloexec(socket.0, true)?;
-            socket.set_nosigpipe(true)?;
-            if nonblocking {
-                set_nonblocking(socket.0, true)?;
-            }
-            Ok(socket)
-        }) }
+        }
+        socket.set_nosigpipe(true)?;
+        if nonblocking {
+            set_nonblocking(socket.0, true)?;
+        }
+        Ok(socket)
     }
 
     pub fn start_listening(&self) -> Result<(), io::Error> {
-        cvt!(unsafe { listen(self.0, LISTEN_BACKLOG) }).map(|_| () )
+        self.start_listening_with_backlog(LISTEN_BACKLOG as u32)
+    }
+
+    /// Listen with a custom backlog, which the OS might limit further.
+    pub fn start_listening_with_backlog(&self,  backlog: u32) -> Result<(), io::Error> {
+        let backlog = if backlog > c_int::MAX as u32 {c_int::MAX} else {backlog as c_int};
+        cvt!(unsafe { listen(self.0, backlog) }).map(|_| () )
     }
 
     pub fn try_clone_from(fd: RawFd) -> Result<Self, io::Error> {
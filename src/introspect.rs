@@ -0,0 +1,62 @@
+//! Get information about any unix domain socket, such as one created by
+//! another crate or received from C code.
+//!
+//! These are free functions instead of an extension trait implemented for
+//! all `AsFd` types, as such a trait would make calling the methods of the
+//! same name on [`UnixStreamExt`](../trait.UnixStreamExt.html) and the other
+//! extension traits ambiguous.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::os::unix::net::UnixStream;
+//! use uds::introspect;
+//!
+//! let (a, _b) = UnixStream::pair().unwrap();
+//! assert_eq!(introspect::socket_type(&a).unwrap(), libc::SOCK_STREAM);
+//! assert!(!introspect::is_listening(&a).unwrap());
+//! assert!(introspect::local_unix_addr(&a).unwrap().is_unnamed());
+//! ```
+
+use std::io;
+use std::os::unix::io::{AsFd, AsRawFd};
+
+use libc::c_int;
+
+use crate::addr::UnixSocketAddr;
+use crate::credentials::{self, ConnCredentials};
+use crate::helpers;
+
+/// Get the address the socket is bound to.
+///
+/// Fails if the socket is not a unix domain socket.
+pub fn local_unix_addr<S: AsFd>(socket: &S) -> Result<UnixSocketAddr, io::Error> {
+    helpers::local_addr(socket.as_fd().as_raw_fd())
+}
+
+/// Get the address of the socket the socket is connected to.
+///
+/// Fails if the socket is not a unix domain socket or not connected.
+pub fn peer_unix_addr<S: AsFd>(socket: &S) -> Result<UnixSocketAddr, io::Error> {
+    helpers::peer_addr(socket.as_fd().as_raw_fd())
+}
+
+/// Get the credentials of the process that created the other end of a
+/// connection or pair of sockets.
+///
+/// See [`UnixStreamExt::initial_peer_credentials()`](../trait.UnixStreamExt.html#method.initial_peer_credentials)
+/// for when these are available.
+pub fn peer_credentials<S: AsFd>(socket: &S) -> Result<ConnCredentials, io::Error> {
+    credentials::peer_credentials(socket.as_fd().as_raw_fd())
+}
+
+/// Get the type of the socket (`SO_TYPE`),
+/// such as `libc::SOCK_STREAM`, `libc::SOCK_DGRAM` or `libc::SOCK_SEQPACKET`.
+pub fn socket_type<S: AsFd>(socket: &S) -> Result<c_int, io::Error> {
+    helpers::socket_type(socket.as_fd().as_raw_fd())
+}
+
+/// Check whether the socket is listening for connections (`SO_ACCEPTCONN`).
+pub fn is_listening<S: AsFd>(socket: &S) -> Result<bool, io::Error> {
+    helpers::is_listening(socket.as_fd().as_raw_fd())
+}
@@ -13,6 +13,11 @@
 //! for stream and datagram sockets is provided via extension traits for
 //! existing types in `std::os::unix::net` and from [mio_uds](https://github.com/alexcrichton/mio_uds)
 //! (the latter is opt-in and must be enabled with `features=["mio_uds"]` in Cargo.toml).
+//! The same features are available without importing traits through this
+//! crate's own [`UnixStream`](struct.UnixStream.html),
+//! [`UnixListener`](struct.UnixListener.html) and
+//! [`UnixDatagram`](struct.UnixDatagram.html) types,
+//! with nonblocking versions in [`nonblocking`](nonblocking/index.html).
 //!
 //! See README for status of operating system support and other general info.
 
@@ -21,6 +26,12 @@
 // Too many features unavailable on solarish to bother cfg()ing individually.
 #![cfg_attr(any(target_os="illumos", target_os="solaris"), allow(unused))]
 
+// Many libc types differ between OSes, so casts and conversions that are
+// no-ops on one OS are needed on others.
+#![allow(clippy::unnecessary_cast, clippy::useless_conversion)]
+// The suggested replacements require a newer Rust than 1.70.
+#![allow(clippy::manual_is_multiple_of, clippy::io_other_error)]
+
 extern crate libc;
 #[cfg(feature="mio-uds")]
 extern crate mio_uds;
@@ -28,6 +39,26 @@ extern crate mio_uds;
 extern crate mio;
 #[cfg(feature="mio_07")]
 extern crate mio_07;
+#[cfg(feature="serde")]
+extern crate serde;
+#[cfg(feature="socket2")]
+extern crate socket2;
+#[cfg(feature="tokio")]
+extern crate tokio;
+#[cfg(feature="async-io")]
+extern crate async_io;
+#[cfg(any(feature="async-io", feature="tokio-util"))]
+extern crate futures_core;
+#[cfg(any(feature="async-io", feature="tokio-util"))]
+extern crate futures_sink;
+#[cfg(feature="tokio-util")]
+extern crate tokio_util;
+#[cfg(feature="tokio-util")]
+extern crate bytes;
+#[cfg(all(feature="io-uring", target_os="linux"))]
+extern crate io_uring;
+#[cfg(feature="async-std")]
+extern crate async_std;
 
 /// Get errno as io::Error on -1.
 macro_rules! cvt {($syscall:expr) => {
@@ -57,15 +88,68 @@ mod helpers;
 mod ancillary;
 mod traits;
 mod seqpacket;
+mod fragmenting;
+mod byte_stream;
+mod passing;
+mod namespace;
+mod builder;
+mod net;
+mod bound_path;
+mod portable;
+mod datagram_client;
+#[cfg(feature="tokio")]
+mod tokio_ext;
+#[cfg(feature="tokio")]
+mod tokio_seqpacket;
+#[cfg(feature="tokio-util")]
+mod tokio_codec;
+#[cfg(feature="async-io")]
+mod async_io_ext;
+#[cfg(feature="async-std")]
+mod async_std_ext;
+#[cfg(all(feature="memfd", any(target_os="linux", target_os="android")))]
+mod memfd;
+#[cfg(all(feature="io-uring", target_os="linux"))]
+mod uring;
+
+pub use addr::{UnixSocketAddr, UnixSocketAddrRef, ToUnixSocketAddr};
+pub use traits::{UnixListenerExt, UnixStreamExt, UnixDatagramExt, UnixSeqpacketConnExt};
+pub use seqpacket::{UnixSeqpacketListener, UnixSeqpacketConn, RecvPacket, Packets, RemoveOnDrop};
+pub use credentials::{ConnCredentials, SendCredentials, ReceivedCredentials};
+pub use ancillary::{SendAncillary, RecvInfo, ReceivedMessage, FdPolicy, FdPolicySocket};
+pub use ancillary::{Ancillary, AncillaryBuf, AncillaryMessage};
+pub use fragmenting::FragmentingSeqpacketConn;
+pub use byte_stream::SeqpacketByteStream;
+pub use passing::PassedSocket;
+pub use namespace::AddrNamespace;
+pub use builder::SocketBuilder;
+pub use net::{UnixStream, UnixListener, UnixDatagram};
+pub use bound_path::BoundPath;
+pub use portable::{PortableSeqpacketConn, PortableSeqpacketListener};
+pub use datagram_client::DatagramClient;
+#[cfg(feature="tokio")]
+pub use tokio_ext::{TokioUnixStreamExt, TokioUnixDatagramExt, IoFuture, TokioSeqpacketByteStream};
+#[cfg(feature="tokio")]
+pub use tokio_seqpacket::{TokioSeqpacketConn, OwnedRecvHalf, OwnedSendHalf};
+#[cfg(feature="tokio-util")]
+pub use tokio_codec::TokioSeqpacketFramed;
+#[cfg(feature="async-io")]
+pub use async_io_ext::{AsyncSeqpacketListenerExt, Incoming, AsyncPackets};
+#[cfg(feature="async-std")]
+pub use async_std_ext::{AsyncStdSeqpacketListener, AsyncStdIncoming, AsyncStdSeqpacketConn};
+#[cfg(all(feature="memfd", any(target_os="linux", target_os="android")))]
+pub use memfd::MemfdPayload;
+#[cfg(all(feature="io-uring", target_os="linux"))]
+pub use uring::UringBatch;
 
-pub use addr::{UnixSocketAddr, UnixSocketAddrRef};
-pub use traits::{UnixListenerExt, UnixStreamExt, UnixDatagramExt};
-pub use seqpacket::{UnixSeqpacketListener, UnixSeqpacketConn};
-pub use credentials::ConnCredentials;
+pub mod introspect;
 
 pub mod nonblocking {
     pub use crate::seqpacket::NonblockingUnixSeqpacketListener as UnixSeqpacketListener;
     pub use crate::seqpacket::NonblockingUnixSeqpacketConn as UnixSeqpacketConn;
+    pub use crate::net::NonblockingUnixStream as UnixStream;
+    pub use crate::net::NonblockingUnixListener as UnixListener;
+    pub use crate::net::NonblockingUnixDatagram as UnixDatagram;
 }
 
 #[cfg(debug_assertions)]
@@ -0,0 +1,132 @@
+use std::io::{self, ErrorKind, IoSlice, IoSliceMut, Write};
+use std::os::unix::io::{RawFd, AsRawFd, FromRawFd, OwnedFd};
+use std::ops::Deref;
+use std::fs::File;
+use std::convert::TryFrom;
+use std::{fmt, mem, ptr, slice};
+
+use libc::{c_int, c_void, fcntl, fstat, mmap, munmap, memfd_create};
+use libc::{MFD_CLOEXEC, MFD_ALLOW_SEALING, F_ADD_SEALS, F_GET_SEALS};
+use libc::{F_SEAL_SEAL, F_SEAL_SHRINK, F_SEAL_GROW, F_SEAL_WRITE};
+use libc::{PROT_READ, MAP_PRIVATE, MAP_FAILED};
+
+use crate::ancillary::{send_ancillary, recv_fds_limited};
+
+/// The seals the receiver requires, as without them the sender could
+/// modify the contents or shrink the file and cause SIGBUS while mapped.
+const REQUIRED_SEALS: c_int = F_SEAL_SHRINK | F_SEAL_GROW | F_SEAL_WRITE;
+
+/// A payload received with
+/// [`recv_memfd()`](struct.UnixSeqpacketConn.html#method.recv_memfd),
+/// mapped read-only into memory.
+///
+/// The memory is unmapped when this is dropped.
+///
+/// # Examples
+///
+/// ```
+/// let (a, b) = uds::UnixSeqpacketConn::pair().unwrap();
+/// let blob = vec![b'x'; 10_000_000];
+/// a.send_memfd(&blob).unwrap();
+/// let received = b.recv_memfd().unwrap();
+/// assert_eq!(&received[..], &blob[..]);
+/// ```
+pub struct MemfdPayload {
+    /// Null if the payload is empty, as `mmap()` doesn't accept zero length.
+    ptr: *mut c_void,
+    len: usize,
+}
+
+// The mapping is read-only and owned.
+unsafe impl Send for MemfdPayload {}
+unsafe impl Sync for MemfdPayload {}
+
+impl Deref for MemfdPayload {
+    type Target = [u8];
+    fn deref(&self) -> &[u8] {
+        if self.ptr.is_null() {
+            &[]
+        } else {
+            unsafe { slice::from_raw_parts(self.ptr as *const u8, self.len) }
+        }
+    }
+}
+
+impl AsRef<[u8]> for MemfdPayload {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl fmt::Debug for MemfdPayload {
+    fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmtr.debug_struct("MemfdPayload")
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+impl Drop for MemfdPayload {
+    fn drop(&mut self) {
+        if !self.ptr.is_null() {
+            unsafe { munmap(self.ptr, self.len) };
+        }
+    }
+}
+
+/// Write `payload` into a sealed memfd, and send it with its length.
+pub fn send_memfd(conn: RawFd,  flags: c_int,  payload: &[u8]) -> Result<(), io::Error> {
+    let name = b"uds payload\0".as_ptr() as *const _;
+    let fd = cvt!(unsafe { memfd_create(name, MFD_CLOEXEC | MFD_ALLOW_SEALING) })?;
+    let mut file = unsafe { File::from_raw_fd(fd) };
+    file.write_all(payload)?;
+    cvt!(unsafe { fcntl(fd, F_ADD_SEALS, REQUIRED_SEALS | F_SEAL_SEAL) })?;
+    let len = (payload.len() as u64).to_le_bytes();
+    send_ancillary(conn, None, flags, &[IoSlice::new(&len)], &[fd], None)?;
+    Ok(())
+}
+
+/// Receive a memfd sent by `send_memfd()` and map it.
+pub fn recv_memfd(conn: RawFd) -> Result<MemfdPayload, io::Error> {
+    let mut len = [0u8; 8];
+    let (received, truncated, mut fds) = recv_fds_limited(conn, None, &mut[IoSliceMut::new(&mut len)], 1)?;
+    let fd = match (received, truncated, fds.pop()) {
+        (8, false, Some(fd)) => fd,
+        (0, false, None) => {
+            return Err(io::Error::new(ErrorKind::UnexpectedEof, "connection closed"));
+        }
+        _ => {
+            let message = "received message is not a memfd payload";
+            return Err(io::Error::new(ErrorKind::InvalidData, message));
+        }
+    };
+    let len = u64::from_le_bytes(len);
+    map_sealed(fd, len)
+}
+
+fn map_sealed(fd: OwnedFd,  len: u64) -> Result<MemfdPayload, io::Error> {
+    // fails with EINVAL for file types that don't support sealing
+    let seals = unsafe { fcntl(fd.as_raw_fd(), F_GET_SEALS) };
+    if seals == -1 || seals & REQUIRED_SEALS != REQUIRED_SEALS {
+        let message = "received memfd is not sealed against modification";
+        return Err(io::Error::new(ErrorKind::InvalidData, message));
+    }
+    let mut stat = unsafe { mem::zeroed() };
+    cvt!(unsafe { fstat(fd.as_raw_fd(), &mut stat) })?;
+    if stat.st_size as u64 != len {
+        let message = "size of received memfd doesn't match the sent length";
+        return Err(io::Error::new(ErrorKind::InvalidData, message));
+    }
+    if len == 0 {
+        return Ok(MemfdPayload { ptr: ptr::null_mut(), len: 0 });
+    }
+    let len = usize::try_from(len).map_err(|_| {
+        io::Error::new(ErrorKind::InvalidData, "received memfd is too big to map")
+    })?;
+    let ptr = unsafe { mmap(ptr::null_mut(), len, PROT_READ, MAP_PRIVATE, fd.as_raw_fd(), 0) };
+    if ptr == MAP_FAILED {
+        return Err(io::Error::last_os_error());
+    }
+    // the mapping stays valid after the fd is closed
+    Ok(MemfdPayload { ptr, len })
+}
@@ -0,0 +1,188 @@
+use std::path::{Path, PathBuf};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+use std::io::{self, ErrorKind};
+use std::fs;
+
+use crate::addr::UnixSocketAddr;
+
+/// Creates addresses for an application from short logical names.
+///
+/// On Linux & Android the addresses are abstract, with names prefixed by the
+/// application name and a `'/'`.
+/// On other operating systems they are paths in a per-application directory
+/// inside `$XDG_RUNTIME_DIR` if set, and otherwise inside
+/// [`std::env::temp_dir()`](https://doc.rust-lang.org/std/env/fn.temp_dir.html).
+/// As other users can create that directory first,
+/// [`create_dir()`](#method.create_dir) must be called before the namespace
+/// is used, which fails if the directory is not private to the current user.
+///
+/// # Examples
+///
+/// ```
+/// # use uds::{AddrNamespace, UnixListenerExt, UnixStreamExt};
+/// # use std::os::unix::net::{UnixListener, UnixStream};
+/// let namespace = AddrNamespace::new("uds-namespace-example");
+/// namespace.create_dir().expect("create socket directory");
+/// let addr = namespace.addr("control").unwrap();
+/// let listener = UnixListener::bind_unix_addr(&addr).unwrap();
+///
+/// let client_addr = namespace.addr("client").unwrap();
+/// let _client = UnixStream::connect_from_to_unix_addr(&client_addr, &addr).unwrap();
+/// let (_conn, peer) = listener.accept_unix_addr().unwrap();
+/// assert_eq!(namespace.logical_name(&peer), Some(&b"client"[..]));
+/// # let _ = namespace.remove("control");
+/// # let _ = namespace.remove("client");
+/// ```
+#[derive(Clone, PartialEq,Eq,Hash, Debug)]
+pub struct AddrNamespace {
+    prefix: Prefix,
+}
+
+#[derive(Clone, PartialEq,Eq,Hash, Debug)]
+enum Prefix {
+    Abstract(Vec<u8>),
+    Directory(PathBuf),
+    /// Created by `new()` in a directory shared with other users.
+    PrivateDirectory(PathBuf),
+}
+
+impl AddrNamespace {
+    /// Create a namespace for the application `app` that uses abstract
+    /// addresses if supported by the OS, and a per-user runtime directory or
+    /// a temporary directory otherwise.
+    pub fn new(app: &str) -> Self {
+        if UnixSocketAddr::has_abstract_addresses() {
+            let mut prefix = app.as_bytes().to_vec();
+            prefix.push(b'/');
+            AddrNamespace { prefix: Prefix::Abstract(prefix) }
+        } else {
+            let runtime_dir = std::env::var_os("XDG_RUNTIME_DIR")
+                .map(PathBuf::from)
+                .filter(|dir| dir.is_absolute() );
+            let parent = runtime_dir.unwrap_or_else(std::env::temp_dir);
+            AddrNamespace { prefix: Prefix::PrivateDirectory(parent.join(app)) }
+        }
+    }
+
+    /// Create a namespace that prefixes abstract names with `prefix`.
+    ///
+    /// No separator is added between the prefix and logical names.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the OS doesn't support abstract addresses.
+    pub fn with_abstract_prefix(prefix: &[u8]) -> Result<Self, io::Error> {
+        if !UnixSocketAddr::has_abstract_addresses() {
+            return Err(io::Error::new(ErrorKind::AddrNotAvailable, format!(
+                    "abstract unix domain socket addresses are not available on {}",
+                    std::env::consts::OS
+            )));
+        }
+        Ok(AddrNamespace { prefix: Prefix::Abstract(prefix.to_vec()) })
+    }
+
+    /// Create a namespace of paths in the directory `dir`.
+    pub fn with_directory<P: AsRef<Path>+?Sized>(dir: &P) -> Self {
+        AddrNamespace { prefix: Prefix::Directory(dir.as_ref().to_path_buf()) }
+    }
+
+    /// Create the directory of a path-based namespace, including any missing
+    /// parent directories.
+    ///
+    /// Does nothing for abstract namespaces.
+    ///
+    /// # Errors
+    ///
+    /// For namespaces created by [`new()`](#method.new), the directory is
+    /// created with mode `0700`, and an error of kind `PermissionDenied` is
+    /// returned if it already exists but is not owned by the current user
+    /// or is writable by others.
+    pub fn create_dir(&self) -> Result<(), io::Error> {
+        match self.prefix {
+            Prefix::Directory(ref dir) => fs::create_dir_all(dir),
+            Prefix::PrivateDirectory(ref dir) => create_private_dir(dir),
+            Prefix::Abstract(_) => Ok(()),
+        }
+    }
+
+    /// Create the address for the logical name `name`.
+    ///
+    /// # Errors
+    ///
+    /// * `name` is empty or contains `'/'` or `'\0'`.
+    /// * The resulting address is too long.
+    pub fn addr(&self, name: &str) -> Result<UnixSocketAddr, io::Error> {
+        if name.is_empty() {
+            return Err(io::Error::new(ErrorKind::InvalidInput, "logical name is empty"));
+        } else if name.contains(&['/', '\0'][..]) {
+            let message = "logical name cannot contain '/' or nul bytes";
+            return Err(io::Error::new(ErrorKind::InvalidInput, message));
+        }
+        match self.prefix {
+            Prefix::Abstract(ref prefix) => {
+                let mut full = prefix.clone();
+                full.extend_from_slice(name.as_bytes());
+                UnixSocketAddr::from_abstract(&full)
+            }
+            Prefix::Directory(ref dir) | Prefix::PrivateDirectory(ref dir) => {
+                UnixSocketAddr::from_path(&dir.join(name))
+            }
+        }
+    }
+
+    /// Get the logical name of an address in this namespace.
+    ///
+    /// Returns `None` if the address is not part of the namespace.
+    pub fn logical_name<'a>(&self, addr: &'a UnixSocketAddr) -> Option<&'a [u8]> {
+        match self.prefix {
+            Prefix::Abstract(ref prefix) => addr.strip_abstract_prefix(prefix),
+            Prefix::Directory(ref dir) | Prefix::PrivateDirectory(ref dir) => {
+                let path = addr.as_pathname()?;
+                match path.parent() {
+                    Some(parent) if parent == dir => Some(path.file_name()?.as_bytes()),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// Remove the socket file for the logical name `name`.
+    ///
+    /// Does nothing for abstract namespaces, as abstract addresses
+    /// disappear when the socket bound to them is closed.
+    ///
+    /// # Errors
+    ///
+    /// Fails for the same names as [`addr()`](#method.addr), so that only
+    /// files inside the directory can be removed.
+    pub fn remove(&self, name: &str) -> Result<(), io::Error> {
+        let addr = self.addr(name)?;
+        match addr.as_pathname() {
+            Some(path) => fs::remove_file(path),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Create a directory that only the current user can access,
+/// or check that an existing one is.
+fn create_private_dir(dir: &Path) -> Result<(), io::Error> {
+    if let Some(parent) = dir.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    match fs::DirBuilder::new().mode(0o700).create(dir) {
+        Err(ref e) if e.kind() == ErrorKind::AlreadyExists => {}
+        result => return result,
+    }
+    // don't follow symlinks, as they could point anywhere
+    let metadata = fs::symlink_metadata(dir)?;
+    if !metadata.is_dir() || metadata.uid() != unsafe { libc::geteuid() }
+    || metadata.mode() & 0o022 != 0 {
+        return Err(io::Error::new(ErrorKind::PermissionDenied, format!(
+                "{} is not a directory private to the current user",
+                dir.display()
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,526 @@
+use std::io::{self, Read, Write, IoSlice, IoSliceMut};
+use std::net::Shutdown;
+use std::os::unix::io::{RawFd, AsFd, BorrowedFd, OwnedFd, AsRawFd, FromRawFd, IntoRawFd};
+use std::os::unix::net as std_net;
+use std::time::Duration;
+
+use libc::{SOCK_STREAM, SOCK_DGRAM};
+
+use crate::addr::{UnixSocketAddr, ToUnixSocketAddr};
+use crate::helpers::*;
+use crate::credentials::ConnCredentials;
+use crate::traits::{UnixStreamExt, UnixListenerExt, UnixDatagramExt};
+
+/// Implement fd traits and conversions for a type wrapping a std socket.
+macro_rules! impl_std_wrapper {($type:ident, $std:ty, nonblocking=$nonblocking:expr) => {
+    impl FromRawFd for $type {
+        unsafe fn from_raw_fd(fd: RawFd) -> Self {
+            $type { inner: <$std>::from_raw_fd(fd) }
+        }
+    }
+    impl AsRawFd for $type {
+        fn as_raw_fd(&self) -> RawFd {
+            self.inner.as_raw_fd()
+        }
+    }
+    impl AsFd for $type {
+        fn as_fd(&self) -> BorrowedFd<'_> {
+            self.inner.as_fd()
+        }
+    }
+    impl IntoRawFd for $type {
+        fn into_raw_fd(self) -> RawFd {
+            self.inner.into_raw_fd()
+        }
+    }
+    impl From<$type> for OwnedFd {
+        fn from(socket: $type) -> OwnedFd {
+            socket.inner.into()
+        }
+    }
+    impl From<$type> for $std {
+        fn from(socket: $type) -> $std {
+            socket.inner
+        }
+    }
+
+    impl $type {
+        /// Wrap a std socket, putting it into the blocking mode of this type.
+        pub fn from_std(socket: $std) -> Result<Self, io::Error> {
+            socket.set_nonblocking($nonblocking)?;
+            Ok($type { inner: socket })
+        }
+        /// Unwrap the std socket, which keeps the blocking mode of this type.
+        pub fn into_std(self) -> $std {
+            self.inner
+        }
+        /// Get the address of this side of the socket.
+        pub fn local_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+            local_addr(self.as_raw_fd())
+        }
+        /// Get and clear the value of the `SO_ERROR` option.
+        pub fn take_error(&self) -> Result<Option<io::Error>, io::Error> {
+            self.inner.take_error()
+        }
+        /// Create another file descriptor for the same socket,
+        /// which shares the blocking mode.
+        pub fn try_clone(&self) -> Result<Self, io::Error> {
+            Ok($type { inner: self.inner.try_clone()? })
+        }
+    }
+}}
+
+/// Implement methods and traits shared by blocking and nonblocking streams.
+macro_rules! impl_stream {($type:ident, nonblocking=$nonblocking:expr) => {
+    impl_std_wrapper!{$type, std_net::UnixStream, nonblocking=$nonblocking}
+
+    impl $type {
+        /// Bind to an address before connecting to a listening socket.
+        pub fn connect_from_to<F, T>(from: F,  to: T) -> Result<Self, io::Error>
+        where F: ToUnixSocketAddr, T: ToUnixSocketAddr {
+            let socket = Socket::new(SOCK_STREAM, $nonblocking)?;
+            bind_to(socket.as_raw_fd(), &from.to_unix_addr()?)?;
+            connect_in_progress_ok(socket.as_raw_fd(), &to.to_unix_addr()?)?;
+            Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
+        }
+        /// Create a pair of connected streams.
+        pub fn pair() -> Result<(Self, Self), io::Error> {
+            let (a, b) = Socket::pair(SOCK_STREAM, $nonblocking)?;
+            Ok(unsafe { (Self::from_raw_fd(a.into_raw_fd()), Self::from_raw_fd(b.into_raw_fd())) })
+        }
+        /// Get the address of the other side of the connection.
+        pub fn peer_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+            peer_addr(self.as_raw_fd())
+        }
+        /// Get the credentials of the process that created the other side
+        /// of the connection.
+        pub fn initial_peer_credentials(&self) -> Result<ConnCredentials, io::Error> {
+            UnixStreamExt::initial_peer_credentials(self)
+        }
+        /// Send bytes with associated file descriptors.
+        pub fn send_fds(&self,  bytes: &[u8],  fds: &[RawFd]) -> Result<usize, io::Error> {
+            UnixStreamExt::send_fds(self, bytes, fds)
+        }
+        /// Receive bytes and associated file descriptors.
+        ///
+        /// Returns the number of bytes and file descriptors received.
+        pub fn recv_fds(&self,  buf: &mut[u8],  fd_buf: &mut[RawFd])
+        -> Result<(usize, usize), io::Error> {
+            UnixStreamExt::recv_fds(self, buf, fd_buf)
+        }
+        /// Receive bytes and up to `max_fds` file descriptors,
+        /// which are closed when dropped.
+        pub fn recv_owned_fds(&self,  buf: &mut[u8],  max_fds: usize)
+        -> Result<(usize, Vec<OwnedFd>), io::Error> {
+            UnixStreamExt::recv_owned_fds(self, buf, max_fds)
+        }
+        /// Shut down the read, write, or both halves of the connection.
+        pub fn shutdown(&self,  how: Shutdown) -> Result<(), io::Error> {
+            self.inner.shutdown(how)
+        }
+    }
+
+    impl UnixStreamExt for $type {
+        fn connect_to_unix_addr<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error> {
+            Self::connect(addr)
+        }
+        fn connect_from_to_unix_addr<F, T>(from: F,  to: T) -> Result<Self, io::Error>
+        where F: ToUnixSocketAddr, T: ToUnixSocketAddr {
+            Self::connect_from_to(from, to)
+        }
+        fn connect_to_unix_addr_timeout<A: ToUnixSocketAddr>(addr: A,  timeout: Duration)
+        -> Result<Self, io::Error> {
+            let socket = Socket::connected_with_timeout($nonblocking, &addr.to_unix_addr()?, timeout)?;
+            Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
+        }
+    }
+
+    impl Read for $type {
+        fn read(&mut self,  buf: &mut[u8]) -> Result<usize, io::Error> {
+            self.inner.read(buf)
+        }
+        fn read_vectored(&mut self,  bufs: &mut[IoSliceMut]) -> Result<usize, io::Error> {
+            self.inner.read_vectored(bufs)
+        }
+    }
+    impl<'a> Read for &'a $type {
+        fn read(&mut self,  buf: &mut[u8]) -> Result<usize, io::Error> {
+            (&self.inner).read(buf)
+        }
+        fn read_vectored(&mut self,  bufs: &mut[IoSliceMut]) -> Result<usize, io::Error> {
+            (&self.inner).read_vectored(bufs)
+        }
+    }
+    impl Write for $type {
+        fn write(&mut self,  buf: &[u8]) -> Result<usize, io::Error> {
+            self.inner.write(buf)
+        }
+        fn write_vectored(&mut self,  bufs: &[IoSlice]) -> Result<usize, io::Error> {
+            self.inner.write_vectored(bufs)
+        }
+        fn flush(&mut self) -> Result<(), io::Error> {
+            Ok(())
+        }
+    }
+    impl<'a> Write for &'a $type {
+        fn write(&mut self,  buf: &[u8]) -> Result<usize, io::Error> {
+            (&self.inner).write(buf)
+        }
+        fn write_vectored(&mut self,  bufs: &[IoSlice]) -> Result<usize, io::Error> {
+            (&self.inner).write_vectored(bufs)
+        }
+        fn flush(&mut self) -> Result<(), io::Error> {
+            Ok(())
+        }
+    }
+}}
+
+/// Implement methods and traits shared by blocking and nonblocking listeners.
+macro_rules! impl_listener {($type:ident, $conn:ident, nonblocking=$nonblocking:expr) => {
+    impl_std_wrapper!{$type, std_net::UnixListener, nonblocking=$nonblocking}
+
+    impl $type {
+        /// Create a socket bound to an address and start listening on it.
+        pub fn bind<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error> {
+            let socket = Socket::new(SOCK_STREAM, $nonblocking)?;
+            bind_to(socket.as_raw_fd(), &addr.to_unix_addr()?)?;
+            socket.start_listening()?;
+            Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
+        }
+        /// Create a listener with a custom backlog of connections waiting to
+        /// be accepted, which the OS might limit further.
+        pub fn bind_with_backlog<A: ToUnixSocketAddr>(addr: A,  backlog: u32)
+        -> Result<Self, io::Error> {
+            let socket = Socket::new(SOCK_STREAM, $nonblocking)?;
+            bind_to(socket.as_raw_fd(), &addr.to_unix_addr()?)?;
+            socket.start_listening_with_backlog(backlog)?;
+            Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
+        }
+        /// Accept a connection, which has the blocking mode of this listener,
+        /// and get the address of the client.
+        pub fn accept(&self) -> Result<($conn, UnixSocketAddr), io::Error> {
+            let (socket, addr) = Socket::accept_from(self.as_raw_fd(), $nonblocking)?;
+            Ok((unsafe { $conn::from_raw_fd(socket.into_raw_fd()) }, addr))
+        }
+    }
+
+    impl UnixListenerExt for $type {
+        type Conn = $conn;
+        fn bind_unix_addr<A: ToUnixSocketAddr>(on: A) -> Result<Self, io::Error> {
+            Self::bind(on)
+        }
+        fn bind_unix_addr_with_backlog<A: ToUnixSocketAddr>(on: A,  backlog: u32)
+        -> Result<Self, io::Error> {
+            Self::bind_with_backlog(on, backlog)
+        }
+        fn accept_unix_addr(&self) -> Result<($conn, UnixSocketAddr), io::Error> {
+            self.accept()
+        }
+    }
+}}
+
+/// Implement methods and traits shared by blocking and nonblocking datagram sockets.
+macro_rules! impl_datagram {($type:ident, nonblocking=$nonblocking:expr) => {
+    impl_std_wrapper!{$type, std_net::UnixDatagram, nonblocking=$nonblocking}
+
+    impl $type {
+        /// Create a socket bound to an address.
+        pub fn bind<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error> {
+            let socket = Socket::new(SOCK_DGRAM, $nonblocking)?;
+            bind_to(socket.as_raw_fd(), &addr.to_unix_addr()?)?;
+            Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
+        }
+        /// Create a socket that isn't bound to any address.
+        pub fn unbound() -> Result<Self, io::Error> {
+            let socket = Socket::new(SOCK_DGRAM, $nonblocking)?;
+            Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
+        }
+        /// Create a pair of sockets connected to each other.
+        pub fn pair() -> Result<(Self, Self), io::Error> {
+            let (a, b) = Socket::pair(SOCK_DGRAM, $nonblocking)?;
+            Ok(unsafe { (Self::from_raw_fd(a.into_raw_fd()), Self::from_raw_fd(b.into_raw_fd())) })
+        }
+        /// Connect the socket to an address, so that `send()` sends to it,
+        /// and only datagrams from it are received.
+        pub fn connect<A: ToUnixSocketAddr>(&self,  addr: A) -> Result<(), io::Error> {
+            connect_to(self.as_raw_fd(), &addr.to_unix_addr()?)
+        }
+        /// Get the address the socket is connected to.
+        pub fn peer_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+            peer_addr(self.as_raw_fd())
+        }
+
+        /// Send a datagram to the connected address.
+        pub fn send(&self,  datagram: &[u8]) -> Result<usize, io::Error> {
+            self.inner.send(datagram)
+        }
+        /// Receive a datagram.
+        pub fn recv(&self,  buf: &mut[u8]) -> Result<usize, io::Error> {
+            self.inner.recv(buf)
+        }
+        /// Send a datagram to an address.
+        pub fn send_to<A: ToUnixSocketAddr>(&self,  datagram: &[u8],  addr: A)
+        -> Result<usize, io::Error> {
+            UnixDatagramExt::send_to_unix_addr(self, datagram, addr)
+        }
+        /// Receive a datagram and the address it was sent from.
+        pub fn recv_from(&self,  buf: &mut[u8]) -> Result<(usize, UnixSocketAddr), io::Error> {
+            UnixDatagramExt::recv_from_unix_addr(self, buf)
+        }
+        /// Read the next datagram and the address it was sent from,
+        /// without removing it from the queue.
+        pub fn peek_from(&self,  buf: &mut[u8]) -> Result<(usize, UnixSocketAddr), io::Error> {
+            UnixDatagramExt::peek_from_unix_addr(self, buf)
+        }
+        /// Send a datagram with file descriptors to the connected address.
+        pub fn send_fds(&self,  datagram: &[u8],  fds: &[RawFd]) -> Result<usize, io::Error> {
+            UnixDatagramExt::send_fds(self, datagram, fds)
+        }
+        /// Send a datagram with file descriptors to an address.
+        pub fn send_fds_to<A: ToUnixSocketAddr>(&self,  datagram: &[u8],  fds: &[RawFd],  addr: A)
+        -> Result<usize, io::Error> {
+            UnixDatagramExt::send_fds_to(self, datagram, fds, addr)
+        }
+        /// Receive a datagram with file descriptors.
+        ///
+        /// Returns the number of bytes and file descriptors received.
+        pub fn recv_fds(&self,  buf: &mut[u8],  fd_buf: &mut[RawFd])
+        -> Result<(usize, usize), io::Error> {
+            UnixDatagramExt::recv_fds(self, buf, fd_buf)
+        }
+        /// Receive a datagram with file descriptors, and the address it was sent from.
+        pub fn recv_fds_from(&self,  buf: &mut[u8],  fd_buf: &mut[RawFd])
+        -> Result<(usize, usize, UnixSocketAddr), io::Error> {
+            UnixDatagramExt::recv_fds_from(self, buf, fd_buf)
+        }
+        /// Shut down receiving, sending, or both.
+        pub fn shutdown(&self,  how: Shutdown) -> Result<(), io::Error> {
+            self.inner.shutdown(how)
+        }
+    }
+
+    impl UnixDatagramExt for $type {
+        fn bind_unix_addr<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error> {
+            Self::bind(addr)
+        }
+    }
+}}
+
+
+
+/// An unix domain stream connection with abstract address, fd-passing and
+/// credentials support built in.
+///
+/// This is a replacement for `std::os::unix::net::UnixStream`
+/// which doesn't require importing
+/// [`UnixStreamExt`](trait.UnixStreamExt.html),
+/// though it's implemented for the less common operations.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::{Read, Write};
+/// use std::os::unix::io::AsRawFd;
+/// use uds::UnixStream;
+///
+/// let (mut a, mut b) = UnixStream::pair().unwrap();
+/// a.send_fds(b"fd", &[a.as_raw_fd()]).unwrap();
+/// let mut fd_buf = [-1; 2];
+/// assert_eq!(b.recv_fds(&mut[0; 10], &mut fd_buf).unwrap(), (2, 1));
+/// # unsafe { libc::close(fd_buf[0]) };
+/// a.write_all(b"bytes").unwrap();
+/// let mut buf = [0; 5];
+/// b.read_exact(&mut buf).unwrap();
+/// assert_eq!(&buf, b"bytes");
+/// ```
+#[derive(Debug)]
+pub struct UnixStream {
+    inner: std_net::UnixStream,
+}
+
+impl_stream!{UnixStream, nonblocking=false}
+
+impl UnixStream {
+    /// Connect to a listening stream socket.
+    ///
+    /// Strings are parsed with
+    /// [`UnixSocketAddr::new()`](struct.UnixSocketAddr.html#method.new),
+    /// so `"@name"` is an abstract address. Pass a `Path` to avoid that.
+    pub fn connect<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error> {
+        let socket = Socket::new(SOCK_STREAM, false)?;
+        connect_to(socket.as_raw_fd(), &addr.to_unix_addr()?)?;
+        Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
+    }
+    /// Connect to a listening stream socket, waiting at most `timeout` for
+    /// the server to accept the connection into its backlog.
+    pub fn connect_timeout<A: ToUnixSocketAddr>(addr: A,  timeout: Duration)
+    -> Result<Self, io::Error> {
+        UnixStreamExt::connect_to_unix_addr_timeout(addr, timeout)
+    }
+    /// Convert into a nonblocking stream.
+    pub fn into_nonblocking(self) -> Result<NonblockingUnixStream, io::Error> {
+        NonblockingUnixStream::from_std(self.inner)
+    }
+
+    /// Set a timeout for reads, or remove it with `None`.
+    pub fn set_read_timeout(&self,  timeout: Option<Duration>) -> Result<(), io::Error> {
+        self.inner.set_read_timeout(timeout)
+    }
+    /// Get the read timeout, if any.
+    pub fn read_timeout(&self) -> Result<Option<Duration>, io::Error> {
+        self.inner.read_timeout()
+    }
+    /// Set a timeout for writes, or remove it with `None`.
+    pub fn set_write_timeout(&self,  timeout: Option<Duration>) -> Result<(), io::Error> {
+        self.inner.set_write_timeout(timeout)
+    }
+    /// Get the write timeout, if any.
+    pub fn write_timeout(&self) -> Result<Option<Duration>, io::Error> {
+        self.inner.write_timeout()
+    }
+}
+
+/// A nonblocking unix domain stream connection.
+///
+/// This is a replacement for a nonblocking `std::os::unix::net::UnixStream`,
+/// see [`UnixStream`](../struct.UnixStream.html).
+#[derive(Debug)]
+pub struct NonblockingUnixStream {
+    inner: std_net::UnixStream,
+}
+
+impl_stream!{NonblockingUnixStream, nonblocking=true}
+
+impl NonblockingUnixStream {
+    /// Start connecting to a listening stream socket.
+    ///
+    /// On Linux, connecting fails with an error of kind `WouldBlock` if the
+    /// listener's backlog is full.
+    /// On other operating systems the connect might then not have completed
+    /// when this returns, in which case the socket becomes writable once it
+    /// has.
+    pub fn connect<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error> {
+        let socket = Socket::new(SOCK_STREAM, true)?;
+        connect_in_progress_ok(socket.as_raw_fd(), &addr.to_unix_addr()?)?;
+        Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
+    }
+    /// Convert into a blocking stream.
+    pub fn into_blocking(self) -> Result<UnixStream, io::Error> {
+        UnixStream::from_std(self.inner)
+    }
+}
+
+/// An unix domain stream listener which returns this crate's
+/// [`UnixStream`](struct.UnixStream.html) and
+/// [`UnixSocketAddr`](struct.UnixSocketAddr.html) when accepting.
+///
+/// # Examples
+///
+/// ```
+/// use uds::{UnixListener, UnixStream, UnixSocketAddr};
+///
+/// let addr = UnixSocketAddr::new_unique().unwrap();
+/// let listener = UnixListener::bind(addr).unwrap();
+/// let client = UnixStream::connect(addr).unwrap();
+/// let (server_side, client_addr) = listener.accept().unwrap();
+/// assert!(client_addr.is_unnamed());
+/// assert_eq!(server_side.local_unix_addr().unwrap(), addr);
+/// # drop(client);
+/// # if let Some(path) = addr.as_pathname() { let _ = std::fs::remove_file(path); }
+/// ```
+#[derive(Debug)]
+pub struct UnixListener {
+    inner: std_net::UnixListener,
+}
+
+impl_listener!{UnixListener, UnixStream, nonblocking=false}
+
+impl UnixListener {
+    /// Convert into a nonblocking listener.
+    pub fn into_nonblocking(self) -> Result<NonblockingUnixListener, io::Error> {
+        NonblockingUnixListener::from_std(self.inner)
+    }
+}
+
+/// A nonblocking unix domain stream listener,
+/// which accepts nonblocking connections.
+#[derive(Debug)]
+pub struct NonblockingUnixListener {
+    inner: std_net::UnixListener,
+}
+
+impl_listener!{NonblockingUnixListener, NonblockingUnixStream, nonblocking=true}
+
+impl NonblockingUnixListener {
+    /// Convert into a blocking listener.
+    pub fn into_blocking(self) -> Result<UnixListener, io::Error> {
+        UnixListener::from_std(self.inner)
+    }
+}
+
+/// An unix domain datagram socket with abstract address and fd-passing
+/// support built in, which uses [`UnixSocketAddr`](struct.UnixSocketAddr.html)
+/// for all addresses.
+///
+/// # Examples
+///
+/// ```
+/// use uds::{UnixDatagram, UnixSocketAddr};
+///
+/// let addr = UnixSocketAddr::new_unique().unwrap();
+/// let server = UnixDatagram::bind(addr).unwrap();
+/// let client = UnixDatagram::unbound().unwrap();
+/// client.send_to(b"hello", addr).unwrap();
+/// let mut buf = [0; 10];
+/// let (len, from) = server.recv_from(&mut buf).unwrap();
+/// assert_eq!(&buf[..len], b"hello");
+/// assert!(from.is_unnamed());
+/// # if let Some(path) = addr.as_pathname() { let _ = std::fs::remove_file(path); }
+/// ```
+#[derive(Debug)]
+pub struct UnixDatagram {
+    inner: std_net::UnixDatagram,
+}
+
+impl_datagram!{UnixDatagram, nonblocking=false}
+
+impl UnixDatagram {
+    /// Convert into a nonblocking socket.
+    pub fn into_nonblocking(self) -> Result<NonblockingUnixDatagram, io::Error> {
+        NonblockingUnixDatagram::from_std(self.inner)
+    }
+
+    /// Set a timeout for receiving, or remove it with `None`.
+    pub fn set_read_timeout(&self,  timeout: Option<Duration>) -> Result<(), io::Error> {
+        self.inner.set_read_timeout(timeout)
+    }
+    /// Get the receive timeout, if any.
+    pub fn read_timeout(&self) -> Result<Option<Duration>, io::Error> {
+        self.inner.read_timeout()
+    }
+    /// Set a timeout for sending, or remove it with `None`.
+    pub fn set_write_timeout(&self,  timeout: Option<Duration>) -> Result<(), io::Error> {
+        self.inner.set_write_timeout(timeout)
+    }
+    /// Get the send timeout, if any.
+    pub fn write_timeout(&self) -> Result<Option<Duration>, io::Error> {
+        self.inner.write_timeout()
+    }
+}
+
+/// A nonblocking unix domain datagram socket.
+///
+/// See [`UnixDatagram`](../struct.UnixDatagram.html).
+#[derive(Debug)]
+pub struct NonblockingUnixDatagram {
+    inner: std_net::UnixDatagram,
+}
+
+impl_datagram!{NonblockingUnixDatagram, nonblocking=true}
+
+impl NonblockingUnixDatagram {
+    /// Convert into a blocking socket.
+    pub fn into_blocking(self) -> Result<UnixDatagram, io::Error> {
+        UnixDatagram::from_std(self.inner)
+    }
+}
@@ -0,0 +1,169 @@
+use std::io::{self, ErrorKind, IoSlice, IoSliceMut};
+use std::os::unix::io::{RawFd, AsRawFd, FromRawFd, IntoRawFd, OwnedFd};
+use std::os::unix::net::{UnixListener, UnixStream, UnixDatagram};
+
+use libc::{c_int, SOCK_STREAM, SOCK_DGRAM, SOCK_SEQPACKET};
+
+use crate::helpers::*;
+use crate::ancillary::{AncillaryBuf, AncillaryMessage, send_ancillary, recv_ancillary};
+use crate::seqpacket::{UnixSeqpacketListener, UnixSeqpacketConn, has_packet};
+
+const SEQPACKET_LISTENER: u8 = 1;
+const SEQPACKET_CONN: u8 = 2;
+const STREAM_LISTENER: u8 = 3;
+const STREAM: u8 = 4;
+const DATAGRAM: u8 = 5;
+
+/// A unix domain socket sent with
+/// [`send_socket()`](struct.UnixSeqpacketConn.html#method.send_socket)
+/// or received with [`recv_socket()`](struct.UnixSeqpacketConn.html#method.recv_socket).
+///
+/// The type of the socket is sent as a one-byte tag together with the file
+/// descriptor, and checked against the received socket, so that a
+/// supervisor process can hand off sockets to workers without the workers
+/// having to guess what they received.
+///
+/// # Examples
+///
+#[cfg_attr(not(target_vendor="apple"), doc="```")]
+#[cfg_attr(target_vendor="apple", doc="```no_run")]
+/// use std::os::unix::net::UnixStream;
+/// use uds::{UnixSeqpacketConn, PassedSocket};
+///
+/// let (supervisor, worker) = UnixSeqpacketConn::pair().unwrap();
+/// let (client, server_side) = UnixStream::pair().unwrap();
+/// supervisor.send_socket(&PassedSocket::from(server_side)).unwrap();
+/// match worker.recv_socket().unwrap() {
+///     PassedSocket::Stream(stream) => drop(stream),
+///     other => panic!("expected a stream, got {:?}", other),
+/// }
+/// # drop(client);
+/// ```
+#[derive(Debug)]
+pub enum PassedSocket {
+    /// A listening `SOCK_SEQPACKET` socket.
+    SeqpacketListener(UnixSeqpacketListener),
+    /// A connected `SOCK_SEQPACKET` socket.
+    SeqpacketConn(UnixSeqpacketConn),
+    /// A listening `SOCK_STREAM` socket.
+    StreamListener(UnixListener),
+    /// A connected `SOCK_STREAM` socket.
+    Stream(UnixStream),
+    /// A `SOCK_DGRAM` socket, connected or not.
+    Datagram(UnixDatagram),
+}
+
+impl PassedSocket {
+    fn tag(&self) -> u8 {
+        match self {
+            PassedSocket::SeqpacketListener(_) => SEQPACKET_LISTENER,
+            PassedSocket::SeqpacketConn(_) => SEQPACKET_CONN,
+            PassedSocket::StreamListener(_) => STREAM_LISTENER,
+            PassedSocket::Stream(_) => STREAM,
+            PassedSocket::Datagram(_) => DATAGRAM,
+        }
+    }
+}
+
+impl AsRawFd for PassedSocket {
+    fn as_raw_fd(&self) -> RawFd {
+        match self {
+            PassedSocket::SeqpacketListener(socket) => socket.as_raw_fd(),
+            PassedSocket::SeqpacketConn(socket) => socket.as_raw_fd(),
+            PassedSocket::StreamListener(socket) => socket.as_raw_fd(),
+            PassedSocket::Stream(socket) => socket.as_raw_fd(),
+            PassedSocket::Datagram(socket) => socket.as_raw_fd(),
+        }
+    }
+}
+
+impl From<UnixSeqpacketListener> for PassedSocket {
+    fn from(socket: UnixSeqpacketListener) -> Self {
+        PassedSocket::SeqpacketListener(socket)
+    }
+}
+impl From<UnixSeqpacketConn> for PassedSocket {
+    fn from(socket: UnixSeqpacketConn) -> Self {
+        PassedSocket::SeqpacketConn(socket)
+    }
+}
+impl From<UnixListener> for PassedSocket {
+    fn from(socket: UnixListener) -> Self {
+        PassedSocket::StreamListener(socket)
+    }
+}
+impl From<UnixStream> for PassedSocket {
+    fn from(socket: UnixStream) -> Self {
+        PassedSocket::Stream(socket)
+    }
+}
+impl From<UnixDatagram> for PassedSocket {
+    fn from(socket: UnixDatagram) -> Self {
+        PassedSocket::Datagram(socket)
+    }
+}
+
+pub fn send_socket(conn: RawFd,  flags: c_int,  socket: &PassedSocket)
+-> Result<(), io::Error> {
+    let tag = [socket.tag()];
+    send_ancillary(conn, None, flags, &[IoSlice::new(&tag)], &[socket.as_raw_fd()], None)?;
+    Ok(())
+}
+
+pub fn recv_socket(conn: RawFd) -> Result<PassedSocket, io::Error> {
+    if !has_packet(conn, 0)? {
+        return Err(io::Error::new(ErrorKind::UnexpectedEof, "connection closed"));
+    }
+    let mut tag = [0u8; 1];
+    // leave room for credentials and timestamps if they're enabled,
+    // so that they don't push out the file descriptor
+    #[cfg(not(any(target_os="illumos", target_os="solaris")))]
+    #[cfg_attr(not(any(target_os="linux", target_os="android")), allow(unused_mut))]
+    let mut capacity = AncillaryBuf::space_for_fds(1) + AncillaryBuf::space_for_timestamp();
+    #[cfg(any(target_os="linux", target_os="android"))] {
+        capacity += AncillaryBuf::space_for_credentials();
+    }
+    #[cfg(any(target_os="illumos", target_os="solaris"))]
+    let capacity = 1; // receiving ancillary messages fails with an error
+    let mut ancillary_buf = AncillaryBuf::with_capacity(capacity);
+    let mut bufs = [IoSliceMut::new(&mut tag)];
+    let (len, mut ancillary) = recv_ancillary(conn, None, 0, &mut bufs, &mut ancillary_buf)?;
+    let mut fds = Vec::new();
+    for message in &mut ancillary {
+        if let AncillaryMessage::Fds(received) = message {
+            fds.extend(received.iter().map(|&fd| unsafe { OwnedFd::from_raw_fd(fd) } ));
+        }
+    }
+    let fd = match (len, ancillary.message_truncated(), fds.pop(), fds.is_empty()) {
+        (1, false, Some(fd), true) => fd,
+        _ => {
+            let message = "received message is not a passed socket";
+            return Err(io::Error::new(ErrorKind::InvalidData, message));
+        }
+    };
+    let (expected_type, expect_listening) = match tag[0] {
+        SEQPACKET_LISTENER => (SOCK_SEQPACKET, true),
+        SEQPACKET_CONN => (SOCK_SEQPACKET, false),
+        STREAM_LISTENER => (SOCK_STREAM, true),
+        STREAM => (SOCK_STREAM, false),
+        DATAGRAM => (SOCK_DGRAM, false),
+        _ => return Err(io::Error::new(ErrorKind::InvalidData, "unknown socket type tag")),
+    };
+    if socket_type(fd.as_raw_fd())? != expected_type
+    || is_listening(fd.as_raw_fd())? != expect_listening {
+        let message = "received socket doesn't match its type tag";
+        return Err(io::Error::new(ErrorKind::InvalidData, message));
+    }
+    // fails if the address family is not AF_UNIX
+    local_addr(fd.as_raw_fd())?;
+    let fd = fd.into_raw_fd();
+    unsafe {
+        Ok(match tag[0] {
+            SEQPACKET_LISTENER => PassedSocket::SeqpacketListener(FromRawFd::from_raw_fd(fd)),
+            SEQPACKET_CONN => PassedSocket::SeqpacketConn(FromRawFd::from_raw_fd(fd)),
+            STREAM_LISTENER => PassedSocket::StreamListener(FromRawFd::from_raw_fd(fd)),
+            STREAM => PassedSocket::Stream(FromRawFd::from_raw_fd(fd)),
+            _ => PassedSocket::Datagram(FromRawFd::from_raw_fd(fd)),
+        })
+    }
+}
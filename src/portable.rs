@@ -0,0 +1,337 @@
+use std::io::{self, ErrorKind, IoSlice, Read, Write};
+use std::net::Shutdown;
+use std::os::unix::io::{RawFd, AsRawFd};
+use std::os::unix::net::{UnixStream, UnixListener};
+use std::sync::{Mutex, MutexGuard};
+
+use libc::{EPROTONOSUPPORT, ESOCKTNOSUPPORT, EPROTOTYPE};
+
+use crate::addr::{UnixSocketAddr, ToUnixSocketAddr};
+use crate::traits::{UnixStreamExt, UnixListenerExt};
+use crate::seqpacket::{UnixSeqpacketConn, UnixSeqpacketListener};
+
+/// Size of the length prefix of emulated packets.
+const HEADER_SIZE: usize = 4;
+
+/// Whether an error from creating a socket means the OS doesn't have
+/// `SOCK_SEQPACKET` for unix domain sockets.
+fn is_unsupported(error: &io::Error) -> bool {
+    matches!(error.raw_os_error(), Some(EPROTONOSUPPORT) | Some(ESOCKTNOSUPPORT) | Some(EPROTOTYPE))
+}
+
+/// Lock a mutex that only serializes I/O, and so cannot be left in an
+/// inconsistent state by a panic.
+fn lock(mutex: &Mutex<()>) -> MutexGuard<'_, ()> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner() )
+}
+
+/// A stream connection where every packet is prefixed with its length.
+#[derive(Debug)]
+struct Framed {
+    stream: UnixStream,
+    // prevent packets sent or received from multiple threads at the same time
+    // from being interleaved
+    send_lock: Mutex<()>,
+    recv_lock: Mutex<()>,
+}
+
+impl Framed {
+    fn new(stream: UnixStream) -> Self {
+        Framed { stream, send_lock: Mutex::new(()), recv_lock: Mutex::new(()) }
+    }
+
+    fn send(&self,  packet: &[u8]) -> Result<usize, io::Error> {
+        if packet.len() > u32::MAX as usize {
+            return Err(io::Error::new(ErrorKind::InvalidInput, "packet is too big"));
+        }
+        let header = (packet.len() as u32).to_be_bytes();
+        let _guard = lock(&self.send_lock);
+        let mut written = 0;
+        while written < HEADER_SIZE + packet.len() {
+            let result = match header.get(written..) {
+                Some(header) if !header.is_empty() => {
+                    (&self.stream).write_vectored(&[IoSlice::new(header), IoSlice::new(packet)])
+                }
+                _ => (&self.stream).write(&packet[written - HEADER_SIZE..]),
+            };
+            match result {
+                Ok(0) => return Err(ErrorKind::WriteZero.into()),
+                Ok(n) => written += n,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(packet.len())
+    }
+
+    fn recv(&self,  buffer: &mut[u8]) -> Result<(usize, bool), io::Error> {
+        let _guard = lock(&self.recv_lock);
+        let mut header = [0; HEADER_SIZE];
+        let mut read = 0;
+        while read < HEADER_SIZE {
+            match (&self.stream).read(&mut header[read..]) {
+                Ok(0) if read == 0 => return Ok((0, false)),
+                Ok(0) => return Err(ErrorKind::UnexpectedEof.into()),
+                Ok(n) => read += n,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        let len = u32::from_be_bytes(header) as usize;
+        let received = len.min(buffer.len());
+        (&self.stream).read_exact(&mut buffer[..received])?;
+        if received < len {
+            let rest = (len - received) as u64;
+            if io::copy(&mut (&self.stream).take(rest), &mut io::sink())? != rest {
+                return Err(ErrorKind::UnexpectedEof.into());
+            }
+        }
+        Ok((received, received < len))
+    }
+}
+
+#[derive(Debug)]
+enum Conn {
+    Native(UnixSeqpacketConn),
+    Emulated(Framed),
+}
+
+/// A packet-oriented connection that uses `SOCK_SEQPACKET` where the OS
+/// supports it, and emulates it over a stream socket otherwise.
+///
+/// Emulated packets are prefixed by their length as a 32-bit big-endian
+/// integer, so both sides must use this type (or the same framing),
+/// and packets cannot be bigger than 4 GiB.
+/// Ancillary data such as file descriptors is not supported through this
+/// type, as emulated packets could not carry it reliably.
+///
+/// Whether a connection is emulated is decided when creating the socket,
+/// and is the same for all sockets on an OS, so servers and clients agree.
+/// macOS and the other Apple OSes don't have `SOCK_SEQPACKET`.
+///
+/// # Examples
+///
+/// ```
+/// use uds::PortableSeqpacketConn;
+///
+/// let (a, b) = PortableSeqpacketConn::pair().unwrap();
+/// #[cfg(target_vendor="apple")]
+/// assert!(a.is_emulated());
+/// a.send(b"first").unwrap();
+/// a.send(b"second").unwrap();
+/// let mut buf = [0; 10];
+/// assert_eq!(b.recv(&mut buf).unwrap(), (5, false));
+/// assert_eq!(b.recv(&mut buf).unwrap(), (6, false));
+/// assert_eq!(&buf[..6], b"second");
+/// ```
+#[derive(Debug)]
+pub struct PortableSeqpacketConn {
+    conn: Conn,
+}
+
+impl PortableSeqpacketConn {
+    /// Connect to a listener bound to `addr`.
+    pub fn connect<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error> {
+        Self::connect_unix_addr(addr)
+    }
+    /// Connect to a listener bound to an address.
+    pub fn connect_unix_addr<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error> {
+        let addr = addr.to_unix_addr()?;
+        match UnixSeqpacketConn::connect_unix_addr(addr) {
+            Ok(conn) => Ok(conn.into()),
+            Err(ref e) if is_unsupported(e) => {
+                Self::from_stream(UnixStream::connect_to_unix_addr(addr)?)
+            }
+            Err(e) => Err(e),
+        }
+    }
+    /// Create a pair of connected sockets.
+    pub fn pair() -> Result<(Self, Self), io::Error> {
+        match UnixSeqpacketConn::pair() {
+            Ok((a, b)) => Ok((a.into(), b.into())),
+            Err(ref e) if is_unsupported(e) => {
+                let (a, b) = UnixStream::pair()?;
+                Ok((Self::from_stream(a)?, Self::from_stream(b)?))
+            }
+            Err(e) => Err(e),
+        }
+    }
+    /// Emulate a packet connection over a stream connection,
+    /// even if the OS supports `SOCK_SEQPACKET`.
+    ///
+    /// The other side of the connection must also be emulated.
+    /// The stream is put into blocking mode, as a packet that is partially
+    /// sent or received when an operation would block cannot be resumed.
+    ///
+    /// # Errors
+    ///
+    /// Fails with an error of kind `InvalidInput` if the stream has a read
+    /// or write timeout, for the same reason.
+    pub fn from_stream(stream: UnixStream) -> Result<Self, io::Error> {
+        if stream.read_timeout()?.is_some() || stream.write_timeout()?.is_some() {
+            let message = "emulated packet connections cannot have timeouts";
+            return Err(io::Error::new(ErrorKind::InvalidInput, message));
+        }
+        stream.set_nonblocking(false)?;
+        Ok(PortableSeqpacketConn { conn: Conn::Emulated(Framed::new(stream)) })
+    }
+
+    /// Whether packets are emulated over a stream socket.
+    pub fn is_emulated(&self) -> bool {
+        match self.conn {
+            Conn::Native(_) => false,
+            Conn::Emulated(_) => true,
+        }
+    }
+    /// Get the address of this side of the connection.
+    pub fn local_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+        match &self.conn {
+            Conn::Native(conn) => conn.local_unix_addr(),
+            Conn::Emulated(framed) => framed.stream.local_unix_addr(),
+        }
+    }
+    /// Get the address of the other side of the connection.
+    pub fn peer_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+        match &self.conn {
+            Conn::Native(conn) => conn.peer_unix_addr(),
+            Conn::Emulated(framed) => framed.stream.peer_unix_addr(),
+        }
+    }
+
+    /// Send a packet, blocking until all of it has been sent.
+    pub fn send(&self,  packet: &[u8]) -> Result<usize, io::Error> {
+        match &self.conn {
+            Conn::Native(conn) => conn.send(packet),
+            Conn::Emulated(framed) => framed.send(packet),
+        }
+    }
+    /// Receive a packet.
+    ///
+    /// The returned `bool` indicates whether the packet was truncated due to
+    /// the buffer being too small, in which case the rest of it is discarded.
+    /// Like for `SOCK_SEQPACKET`, a length of zero can also mean that the
+    /// other side has closed the connection.
+    pub fn recv(&self,  buffer: &mut[u8]) -> Result<(usize, bool), io::Error> {
+        match &self.conn {
+            Conn::Native(conn) => conn.recv(buffer),
+            Conn::Emulated(framed) => framed.recv(buffer),
+        }
+    }
+    /// Shut down the read, write, or both directions of the connection.
+    pub fn shutdown(&self,  how: Shutdown) -> Result<(), io::Error> {
+        match &self.conn {
+            Conn::Native(conn) => conn.shutdown(how),
+            Conn::Emulated(framed) => framed.stream.shutdown(how),
+        }
+    }
+}
+
+impl From<UnixSeqpacketConn> for PortableSeqpacketConn {
+    fn from(conn: UnixSeqpacketConn) -> Self {
+        PortableSeqpacketConn { conn: Conn::Native(conn) }
+    }
+}
+
+impl AsRawFd for PortableSeqpacketConn {
+    fn as_raw_fd(&self) -> RawFd {
+        match &self.conn {
+            Conn::Native(conn) => conn.as_raw_fd(),
+            Conn::Emulated(framed) => framed.stream.as_raw_fd(),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Listener {
+    Native(UnixSeqpacketListener),
+    Emulated(UnixListener),
+}
+
+/// A listener for [`PortableSeqpacketConn`](struct.PortableSeqpacketConn.html)s,
+/// which is a stream listener where the OS doesn't support `SOCK_SEQPACKET`.
+///
+/// # Examples
+///
+/// ```
+/// use uds::{PortableSeqpacketListener, PortableSeqpacketConn};
+///
+/// # let _ = std::fs::remove_file("portable_seqpacket.sock");
+/// let listener = PortableSeqpacketListener::bind("portable_seqpacket.sock").unwrap();
+/// let client = PortableSeqpacketConn::connect("portable_seqpacket.sock").unwrap();
+/// let (server, _) = listener.accept_unix_addr().unwrap();
+/// assert_eq!(server.is_emulated(), listener.is_emulated());
+/// client.send(b"packet").unwrap();
+/// assert_eq!(server.recv(&mut[0; 10]).unwrap(), (6, false));
+/// # std::fs::remove_file("portable_seqpacket.sock").unwrap();
+/// ```
+#[derive(Debug)]
+pub struct PortableSeqpacketListener {
+    listener: Listener,
+}
+
+impl PortableSeqpacketListener {
+    /// Create a listener bound to `addr`.
+    pub fn bind<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error> {
+        Self::bind_unix_addr(addr)
+    }
+    /// Create a listener bound to an address.
+    pub fn bind_unix_addr<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error> {
+        let addr = addr.to_unix_addr()?;
+        match UnixSeqpacketListener::bind_unix_addr(addr) {
+            Ok(listener) => Ok(listener.into()),
+            Err(ref e) if is_unsupported(e) => {
+                Ok(Self::from_stream_listener(UnixListener::bind_unix_addr(addr)?))
+            }
+            Err(e) => Err(e),
+        }
+    }
+    /// Accept emulated connections from a stream listener,
+    /// even if the OS supports `SOCK_SEQPACKET`.
+    pub fn from_stream_listener(listener: UnixListener) -> Self {
+        PortableSeqpacketListener { listener: Listener::Emulated(listener) }
+    }
+
+    /// Whether accepted connections are emulated over stream sockets.
+    pub fn is_emulated(&self) -> bool {
+        match self.listener {
+            Listener::Native(_) => false,
+            Listener::Emulated(_) => true,
+        }
+    }
+    /// Get the address this listener is bound to.
+    pub fn local_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+        match &self.listener {
+            Listener::Native(listener) => listener.local_unix_addr(),
+            Listener::Emulated(listener) => listener.local_unix_addr(),
+        }
+    }
+    /// Accept a connection, along with the client's address.
+    pub fn accept_unix_addr(&self)
+    -> Result<(PortableSeqpacketConn, UnixSocketAddr), io::Error> {
+        match &self.listener {
+            Listener::Native(listener) => {
+                let (conn, addr) = listener.accept_unix_addr()?;
+                Ok((conn.into(), addr))
+            }
+            Listener::Emulated(listener) => {
+                let (stream, addr) = listener.accept_unix_addr()?;
+                Ok((PortableSeqpacketConn::from_stream(stream)?, addr))
+            }
+        }
+    }
+}
+
+impl From<UnixSeqpacketListener> for PortableSeqpacketListener {
+    fn from(listener: UnixSeqpacketListener) -> Self {
+        PortableSeqpacketListener { listener: Listener::Native(listener) }
+    }
+}
+
+impl AsRawFd for PortableSeqpacketListener {
+    fn as_raw_fd(&self) -> RawFd {
+        match &self.listener {
+            Listener::Native(listener) => listener.as_raw_fd(),
+            Listener::Emulated(listener) => listener.as_raw_fd(),
+        }
+    }
+}
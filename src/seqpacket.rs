@@ -1,9 +1,18 @@
 use std::io::{self, ErrorKind, IoSlice, IoSliceMut};
 use std::mem;
-use std::os::unix::io::{RawFd, FromRawFd, AsRawFd, IntoRawFd};
-use std::path::Path;
+use std::net::Shutdown;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::fs;
+use std::time::{Duration, Instant, SystemTime};
+use std::os::unix::io::{RawFd, AsFd, BorrowedFd, OwnedFd, FromRawFd, AsRawFd, IntoRawFd};
+#[cfg(feature="socket2")]
+use std::convert::TryFrom;
 
-use libc::{SOCK_SEQPACKET, MSG_EOR, c_void, close, send};
+use libc::{SOCK_SEQPACKET, MSG_EOR, MSG_DONTWAIT, EINPROGRESS, c_int, c_void, close, send};
+use libc::{MSG_PEEK, POLLIN, POLLOUT, POLLHUP, POLLERR};
+#[cfg(any(target_os="linux", target_os="android"))]
+use libc::{MSG_TRUNC, POLLRDHUP, SO_PASSCRED, SO_TIMESTAMP, SO_TIMESTAMPNS};
 
 #[cfg(feature="mio")]
 use mio::{event::Evented, unix::EventedFd, Poll, Token as Token_06, Ready, PollOpt};
@@ -15,6 +24,9 @@ use crate::addr::*;
 use crate::helpers::*;
 use crate::ancillary::*;
 use crate::credentials::*;
+#[cfg(all(feature="memfd", any(target_os="linux", target_os="android")))]
+use crate::memfd::{MemfdPayload, send_memfd, recv_memfd};
+use crate::passing::{PassedSocket, send_socket, recv_socket};
 
 /// Implement traits apropriate for any file-descriptor-wrapping type.
 macro_rules! impl_rawfd_traits {($type:tt) => {
@@ -28,6 +40,11 @@ macro_rules! impl_rawfd_traits {($type:tt) => {
             self.fd
         }
     }
+    impl AsFd for $type {
+        fn as_fd(&self) -> BorrowedFd<'_> {
+            unsafe { BorrowedFd::borrow_raw(self.fd) }
+        }
+    }
     impl IntoRawFd for $type {
         fn into_raw_fd(self) -> RawFd {
             let fd = self.fd;
@@ -42,6 +59,272 @@ macro_rules! impl_rawfd_traits {($type:tt) => {
     }
 }}
 
+/// What was received by `recv_packet()`.
+#[derive(Clone,Copy, PartialEq,Eq, Debug)]
+pub enum RecvPacket {
+    /// A packet of `len` bytes, which might be zero.
+    ///
+    /// `truncated` is `true` if the packet didn't fit in the buffer.
+    Packet { len: usize,  truncated: bool },
+    /// The peer has closed the connection or shut down its write half,
+    /// and all packets sent before that have been received.
+    EndOfConnection,
+}
+
+/// Receive a packet, after peeking to tell an empty packet apart from
+/// end of connection.
+fn recv_packet(socket: RawFd,  flags: c_int,  buffer: &mut[u8])
+-> Result<RecvPacket, io::Error> {
+    if !has_packet(socket, flags)? {
+        return Ok(RecvPacket::EndOfConnection);
+    }
+    let mut buffers = [IoSliceMut::new(buffer)];
+    let (len, ancillary) = recv_ancillary(socket, None, flags, &mut buffers, &mut[])?;
+    Ok(RecvPacket::Packet { len, truncated: ancillary.message_truncated() })
+}
+
+/// Check whether there is a packet to receive, or end of connection,
+/// without removing anything from the queue.
+///
+/// Blocks like `recv()` does, unless `flags` contains `MSG_DONTWAIT`.
+pub(crate) fn has_packet(socket: RawFd,  flags: c_int) -> Result<bool, io::Error> {
+    // Any control data is discarded, but sets MSG_CTRUNC which shows that
+    // there is a packet.
+    let (len, ancillary) = recv_ancillary(socket, None, flags | MSG_PEEK, &mut[], &mut[])?;
+    if len != 0 || ancillary.message_truncated() || ancillary.end_of_record()
+    || ancillary.ancillary_truncated() {
+        return Ok(true);
+    }
+    // Linux doesn't set MSG_EOR, but only returns end of connection once the
+    // read half has been shut down, after which no more packets can arrive.
+    #[cfg(any(target_os="linux", target_os="android"))] {
+        if poll_now(socket, POLLRDHUP)? & (POLLRDHUP | POLLHUP) == 0 {
+            return Ok(true);
+        }
+        has_queued_packet(socket)
+    }
+    #[cfg(not(any(target_os="linux", target_os="android")))] {
+        Ok(false)
+    }
+}
+
+/// Check whether the receive queue contains a packet, which might be empty,
+/// after the peer has shut down.
+///
+/// Peeking at an empty packet looks like end of connection, but Linux
+/// timestamps every received packet when `SO_TIMESTAMP` is enabled,
+/// including those queued before enabling it.
+/// The option is therefore enabled while peeking, unless receiving
+/// credentials or timestamps is already enabled: Then the peek by
+/// `has_packet()` would have seen control data if there was a packet.
+#[cfg(any(target_os="linux", target_os="android"))]
+fn has_queued_packet(socket: RawFd) -> Result<bool, io::Error> {
+    for &option in &[SO_PASSCRED, SO_TIMESTAMP, SO_TIMESTAMPNS] {
+        if get_socket_flag(socket, option)? {
+            return Ok(false);
+        }
+    }
+    set_socket_flag(socket, SO_TIMESTAMP, true)?;
+    let mut ancillary_buf = AncillaryBuf::default();
+    let peeked = recv_ancillary(socket, None, MSG_PEEK | MSG_DONTWAIT, &mut[], &mut ancillary_buf)
+        .map(|(_, ancillary)| ancillary.has_control_messages() || ancillary.ancillary_truncated() );
+    set_socket_flag(socket, SO_TIMESTAMP, false)?;
+    match peeked {
+        Err(ref e) if e.kind() == ErrorKind::WouldBlock => Ok(false),
+        result => result,
+    }
+}
+
+/// Get the length of the next packet without removing it from the queue.
+fn peek_size(socket: RawFd) -> Result<usize, io::Error> {
+    #[cfg(any(target_os="linux", target_os="android"))] {
+        recv_ancillary(socket, None, MSG_PEEK | MSG_TRUNC, &mut[], &mut[])
+            .map(|(len, _)| len )
+    }
+    #[cfg(not(any(target_os="linux", target_os="android")))] {
+        // MSG_TRUNC only makes recv() return the full length on Linux,
+        // so peek with a bigger buffer until the packet fits.
+        let mut buffer = vec![0; 256];
+        loop {
+            let mut buffers = [IoSliceMut::new(&mut buffer)];
+            let (len, ancillary) = recv_ancillary(socket, None, MSG_PEEK, &mut buffers, &mut[])?;
+            if !ancillary.message_truncated() {
+                break Ok(len);
+            }
+            let new_len = buffer.len() * 2;
+            buffer.resize(new_len, 0);
+        }
+    }
+}
+
+/// Get how big an ancillary buffer must be to receive all ancillary messages
+/// of the next packet, without removing it from the queue.
+///
+/// Any file descriptors received while peeking are closed.
+fn peek_ancillary_capacity(socket: RawFd) -> Result<usize, io::Error> {
+    // Linux limits the number of file descriptors in one message to
+    // SCM_MAX_FD, and other OSes limit control messages to a few kilobytes.
+    #[cfg(any(target_os="linux", target_os="android"))]
+    const MAX_FDS: usize = 253;
+    #[cfg(not(any(target_os="linux", target_os="android", target_os="illumos", target_os="solaris")))]
+    const MAX_FDS: usize = 512;
+    #[cfg(not(any(target_os="illumos", target_os="solaris")))]
+    #[cfg_attr(not(any(target_os="linux", target_os="android")), allow(unused_mut))]
+    let (mut max_capacity, one_fd) = (
+        AncillaryBuf::space_for_fds(MAX_FDS) + AncillaryBuf::space_for_timestamp(),
+        AncillaryBuf::space_for_fds(1),
+    );
+    #[cfg(any(target_os="linux", target_os="android"))] {
+        max_capacity += AncillaryBuf::space_for_credentials();
+    }
+    // receiving ancillary messages fails with an error
+    #[cfg(any(target_os="illumos", target_os="solaris"))]
+    let (max_capacity, one_fd) = (AncillaryBuf::MAX_STACK_CAPACITY, 1);
+    let mut capacity = AncillaryBuf::MAX_STACK_CAPACITY;
+    loop {
+        let mut ancillary_buf = AncillaryBuf::with_capacity(capacity);
+        let (_, ancillary) = recv_ancillary(socket, None, MSG_PEEK, &mut[], &mut ancillary_buf)?;
+        // If there was room for more, the messages were truncated for another
+        // reason, such as the process running out of file descriptors,
+        // and a bigger buffer wouldn't help.
+        let unused = capacity - ancillary.ancillary_len();
+        if !ancillary.ancillary_truncated() || capacity >= max_capacity
+        || unused >= one_fd {
+            break Ok(ancillary.ancillary_len());
+        }
+        capacity = (capacity * 2).min(max_capacity);
+    }
+}
+
+/// Receive a packet into `packet`, which is resized to fit it.
+fn recv_to_vec(socket: RawFd,  packet: &mut Vec<u8>) -> Result<usize, io::Error> {
+    let size = peek_size(socket)?;
+    packet.clear();
+    packet.resize(size, 0);
+    let mut buffers = [IoSliceMut::new(packet)];
+    let (len, ancillary) = recv_ancillary(socket, None, 0, &mut buffers, &mut[])?;
+    packet.truncate(len);
+    if ancillary.message_truncated() {
+        // Another thread received the peeked packet, and the next one is
+        // bigger. It has now been removed from the queue, so cannot retry.
+        return Err(io::Error::new(
+            ErrorKind::Other,
+            "packet was truncated because it changed after peeking at its size"
+        ));
+    }
+    Ok(len)
+}
+
+/// Receive a packet into `packet`, which is resized to fit it,
+/// and tell an empty packet apart from end of connection.
+pub(crate) fn recv_packet_to_vec(socket: RawFd,  packet: &mut Vec<u8>)
+-> Result<RecvPacket, io::Error> {
+    packet.clear();
+    if !has_packet(socket, 0)? {
+        return Ok(RecvPacket::EndOfConnection);
+    }
+    let len = recv_to_vec(socket, packet)?;
+    Ok(RecvPacket::Packet { len, truncated: false })
+}
+
+/// An iterator over the packets received on a connection.
+///
+/// Created by [`UnixSeqpacketConn::packets()`](struct.UnixSeqpacketConn.html#method.packets).
+///
+/// Each packet is peeked at to find its size before it's received, and like
+/// [`recv_packet()`](struct.UnixSeqpacketConn.html#method.recv_packet)
+/// the iterator doesn't change any socket options.
+#[derive(Debug)]
+pub struct Packets<'a> {
+    conn: &'a UnixSeqpacketConn,
+}
+
+impl<'a> Iterator for Packets<'a> {
+    type Item = Result<Vec<u8>, io::Error>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let receive = || {
+            let mut packet = Vec::new();
+            match recv_packet_to_vec(self.conn.fd, &mut packet)? {
+                RecvPacket::Packet { .. } => Ok(Some(packet)),
+                RecvPacket::EndOfConnection => Ok(None),
+            }
+        };
+        receive().transpose()
+    }
+}
+
+/// A listener that removes its socket file when dropped.
+///
+/// Created by `bind_with_cleanup()` on the seqpacket listener types,
+/// and derefs to the listener.
+#[derive(Debug)]
+pub struct RemoveOnDrop<L> {
+    listener: L,
+    path: PathBuf,
+}
+
+impl<L> RemoveOnDrop<L> {
+    /// The path that will be removed.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl<L> Deref for RemoveOnDrop<L> {
+    type Target = L;
+    fn deref(&self) -> &L {
+        &self.listener
+    }
+}
+
+impl<L> Drop for RemoveOnDrop<L> {
+    fn drop(&mut self) {
+        // the file might have been removed or replaced by someone else,
+        // but there's nothing to do about that here.
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Implement conversions to and from `socket2::Socket` for a fd-wrapping type.
+macro_rules! impl_socket2_if_enabled {($type:tt, nonblocking=$nonblocking:expr) => {
+    #[cfg(feature="socket2")]
+    impl From<$type> for socket2::Socket {
+        fn from(socket: $type) -> Self {
+            unsafe { socket2::Socket::from_raw_fd(socket.into_raw_fd()) }
+        }
+    }
+
+    /// Fails if the socket is not an unix domain `SOCK_SEQPACKET` socket,
+    /// and then returns the socket together with the error.
+    ///
+    /// The socket is put into the blocking mode of this type.
+    #[cfg(feature="socket2")]
+    impl TryFrom<socket2::Socket> for $type {
+        type Error = (io::Error, socket2::Socket);
+        fn try_from(socket: socket2::Socket) -> Result<Self, Self::Error> {
+            let check = || {
+                if libc::c_int::from(socket.r#type()?) != SOCK_SEQPACKET {
+                    return Err(io::Error::new(ErrorKind::InvalidInput, "not a SOCK_SEQPACKET socket"));
+                }
+                // fails if the address family is not AF_UNIX
+                local_addr(socket.as_raw_fd())?;
+                set_nonblocking(socket.as_raw_fd(), $nonblocking)
+            };
+            match check() {
+                Ok(()) => Ok(unsafe { $type::from_raw_fd(socket.into_raw_fd()) }),
+                Err(e) => Err((e, socket)),
+            }
+        }
+    }
+}}
+
+/// Implement `async_io::IoSafe` for a fd-wrapping type.
+macro_rules! impl_async_io_if_enabled {($type:tt) => {
+    /// None of the methods close or replace the file descriptor.
+    #[cfg(feature="async-io")]
+    unsafe impl async_io::IoSafe for $type {}
+}}
+
 /// Implement `mio::Evented` and `mio::Source` for a fd-wrapping type.
 macro_rules! impl_mio_if_enabled {($type:tt) => {
     #[cfg(feature="mio")]
@@ -175,28 +458,31 @@ pub struct UnixSeqpacketConn {
 }
 
 impl_rawfd_traits!{UnixSeqpacketConn}
+impl_socket2_if_enabled!{UnixSeqpacketConn, nonblocking=false}
 
 impl UnixSeqpacketConn {
-    /// Connect to an unix seqpacket server listening at `path`.
+    /// Connect to an unix seqpacket server listening at `addr`.
     ///
-    /// This is a wrapper around [`connect_unix_addr()`](#method.connect_unix_addr)
-    /// for convenience and compatibility with std.
-    pub fn connect<P: AsRef<Path>>(path: P) -> Result<Self, io::Error> {
-        let addr = UnixSocketAddr::from_path(&path)?;
-        Self::connect_unix_addr(&addr)
+    /// This is identical to [`connect_unix_addr()`](#method.connect_unix_addr),
+    /// and exists for convenience and compatibility with std.
+    /// Note that strings are parsed with
+    /// [`UnixSocketAddr::new()`](struct.UnixSocketAddr.html#method.new),
+    /// so `"@name"` is an abstract address. Pass a `Path` to avoid that.
+    pub fn connect<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error> {
+        Self::connect_unix_addr(addr)
     }
     /// Connect to an unix seqpacket server listening at `addr`.
-    pub fn connect_unix_addr(addr: &UnixSocketAddr) -> Result<Self, io::Error> {
+    pub fn connect_unix_addr<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error> {
         let socket = Socket::new(SOCK_SEQPACKET, false)?;
-        connect_to(socket.as_raw_fd(), addr)?;
+        connect_to(socket.as_raw_fd(), &addr.to_unix_addr()?)?;
         Ok(UnixSeqpacketConn { fd: socket.into_raw_fd() })
     }
     /// Bind to an address before connecting to a listening sequplacet socket.
-    pub fn connect_from_to_unix_addr(from: &UnixSocketAddr,  to: &UnixSocketAddr)
-    -> Result<Self, io::Error> {
+    pub fn connect_from_to_unix_addr<F, T>(from: F,  to: T) -> Result<Self, io::Error>
+    where F: ToUnixSocketAddr, T: ToUnixSocketAddr {
         let socket = Socket::new(SOCK_SEQPACKET, false)?;
-        bind_to(socket.as_raw_fd(), from)?;
-        connect_to(socket.as_raw_fd(), to)?;
+        bind_to(socket.as_raw_fd(), &from.to_unix_addr()?)?;
+        connect_to(socket.as_raw_fd(), &to.to_unix_addr()?)?;
         Ok(UnixSeqpacketConn { fd: socket.into_raw_fd() })
     }
 
@@ -234,13 +520,116 @@ impl UnixSeqpacketConn {
     pub fn initial_peer_credentials(&self) -> Result<ConnCredentials, io::Error> {
         peer_credentials(self.fd)
     }
+    /// Get the supplementary groups of the peer process when the connection
+    /// was established.
+    ///
+    /// This is available on Linux 4.13 and later (through `SO_PEERGROUPS`),
+    /// FreeBSD and macOS. On FreeBSD and macOS at most 16 groups are returned,
+    /// and the first one is the effective group ID.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(any(target_os="linux", target_os="android"), doc="```")]
+    #[cfg_attr(not(any(target_os="linux", target_os="android")), doc="```no_run")]
+    /// let (a, _b) = uds::UnixSeqpacketConn::pair().unwrap();
+    /// let groups = a.initial_peer_groups().unwrap();
+    /// let mut own = [0; 256];
+    /// let count = unsafe { libc::getgroups(256, own.as_mut_ptr()) };
+    /// assert_eq!(groups.len(), count as usize);
+    /// ```
+    pub fn initial_peer_groups(&self) -> Result<Vec<u32>, io::Error> {
+        peer_groups(self.fd)
+    }
+    /// Get the security label of the peer process when the connection was
+    /// established (`SO_PEERSEC`).
+    ///
+    /// This is the SELinux context, AppArmor profile or Smack label,
+    /// depending on which security module is active.
+    /// The format is not interpreted, but a trailing NUL is removed.
+    ///
+    /// # Errors
+    ///
+    /// Fails with `ENOPROTOOPT` if no security module that supports labels
+    /// is active.
+    #[cfg(any(target_os="linux", target_os="android"))]
+    pub fn peer_security_label(&self) -> Result<Vec<u8>, io::Error> {
+        peer_security_label(self.fd)
+    }
+    /// Get a pidfd referring to the peer process when the connection was
+    /// established (`SO_PEERPIDFD`).
+    ///
+    /// Unlike the pid from [`initial_peer_credentials()`](#method.initial_peer_credentials),
+    /// a pidfd keeps referring to the same process even after it has
+    /// terminated, so it can't be confused with another process that later
+    /// gets the same pid.
+    /// The returned file descriptor is close-on-exec.
+    ///
+    /// # Errors
+    ///
+    /// Fails with `ENOPROTOOPT` on Linux versions older than 6.5.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::ErrorKind;
+    ///
+    /// let (a, _b) = uds::UnixSeqpacketConn::pair().unwrap();
+    /// match a.peer_pidfd() {
+    ///     Ok(pidfd) => println!("peer is {:?}", pidfd),
+    ///     Err(e) if e.raw_os_error() == Some(libc::ENOPROTOOPT) => println!("too old kernel"),
+    ///     Err(e) => panic!("{}", e),
+    /// }
+    /// ```
+    #[cfg(any(target_os="linux", target_os="android"))]
+    pub fn peer_pidfd(&self) -> Result<OwnedFd, io::Error> {
+        peer_pidfd(self.fd)
+    }
+    /// Enable or disable receiving the security label of the sender with
+    /// every packet (`SO_PASSSEC`).
+    ///
+    /// The labels are received as
+    /// [`AncillaryMessage::SecurityLabel`](enum.AncillaryMessage.html#variant.SecurityLabel)
+    /// by [`recv_vectored_ancillary()`](#method.recv_vectored_ancillary).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::IoSliceMut;
+    /// use uds::{UnixSeqpacketConn, AncillaryBuf, AncillaryMessage};
+    ///
+    /// let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    /// b.set_receive_security_label(true).unwrap();
+    /// a.send(b"labeled").unwrap();
+    /// let mut ancillary_buf = AncillaryBuf::with_capacity(256);
+    /// let mut buf = [0; 10];
+    /// let (_, ancillary) = b.recv_vectored_ancillary(
+    ///     &mut[IoSliceMut::new(&mut buf)],
+    ///     &mut ancillary_buf,
+    /// ).unwrap();
+    /// for message in ancillary {
+    ///     if let AncillaryMessage::SecurityLabel(label) = message {
+    ///         println!("sender has label {}", String::from_utf8_lossy(label));
+    ///     }
+    /// }
+    /// ```
+    #[cfg(any(target_os="linux", target_os="android"))]
+    pub fn set_receive_security_label(&self,  receive: bool) -> Result<(), io::Error> {
+        set_passsec(self.fd, receive)
+    }
 
 
     /// Send a packet to the peer.
+    ///
+    /// # Errors
+    ///
+    /// Packets bigger than [`max_send_size()`](#method.max_send_size)
+    /// fail with an error of kind `InvalidInput` instead of the OS error
+    /// for `EMSGSIZE`.
     pub fn send(&self,  packet: &[u8]) -> Result<usize, io::Error> {
         let ptr = packet.as_ptr() as *const c_void;
         let flags = MSG_NOSIGNAL | MSG_EOR;
-        let sent = cvt_r!(unsafe { send(self.fd, ptr, packet.len(), flags) })?;
+        let sent = cvt_r!(unsafe { send(self.fd, ptr, packet.len(), flags) })
+            .map_err(explain_too_big)?;
         Ok(sent as usize)
     }
     /// Receive a packet from the peer.
@@ -252,6 +641,114 @@ impl UnixSeqpacketConn {
         let (bytes, ancillary) = recv_ancillary(self.fd, None, 0, &mut buffers, &mut[])?;
         Ok((bytes, ancillary.message_truncated()))
     }
+    /// Receive a packet from the peer, and tell an empty packet apart from
+    /// end of connection.
+    ///
+    /// [`recv()`](#method.recv) returns `(0, false)` for both, as that's what
+    /// `recv()` and `recvmsg()` return.
+    /// The next packet is peeked at before it's received, and operating
+    /// systems other than Linux & Android are expected to mark packets with
+    /// `MSG_EOR`.
+    /// On Linux, an empty peek is only taken as end of connection if the
+    /// peer has shut down and no packets remain in the queue. To find out
+    /// whether an empty packet remains, `SO_TIMESTAMP` is enabled briefly
+    /// after the peer has shut down, unless receiving credentials or
+    /// timestamps is already enabled.
+    /// Any file descriptors or other ancillary data sent with the packet
+    /// are discarded.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// use uds::{UnixSeqpacketConn, RecvPacket};
+    ///
+    /// let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    /// a.send(b"").unwrap();
+    /// drop(a);
+    /// let mut buf = [0; 10];
+    /// assert_eq!(b.recv_packet(&mut buf).unwrap(), RecvPacket::Packet { len: 0, truncated: false });
+    /// assert_eq!(b.recv_packet(&mut buf).unwrap(), RecvPacket::EndOfConnection);
+    /// ```
+    pub fn recv_packet(&self,  buffer: &mut[u8]) -> Result<RecvPacket, io::Error> {
+        recv_packet(self.fd, 0, buffer)
+    }
+    /// Receive a packet into a `Vec` that is resized to fit it.
+    ///
+    /// Any previous content of `packet` is replaced, but its allocation is
+    /// reused if big enough.
+    /// The length of the packet is found by peeking at it first, so a
+    /// packet is never truncated as long as only one thread receives from
+    /// the connection.
+    ///
+    /// # Errors
+    ///
+    /// Fails with an error of kind `Other` if another thread received the
+    /// packet after its size was peeked, and the next packet didn't fit.
+    /// `packet` then contains the start of that packet, and the rest of it
+    /// is lost.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// let (a, b) = uds::UnixSeqpacketConn::pair().unwrap();
+    /// a.send(&[1; 3000]).unwrap();
+    /// a.send(b"short").unwrap();
+    /// let mut packet = Vec::new();
+    /// assert_eq!(b.recv_to_vec(&mut packet).unwrap(), 3000);
+    /// assert_eq!(packet, vec![1; 3000]);
+    /// b.recv_to_vec(&mut packet).unwrap();
+    /// assert_eq!(packet, b"short");
+    /// ```
+    pub fn recv_to_vec(&self,  packet: &mut Vec<u8>) -> Result<usize, io::Error> {
+        recv_to_vec(self.fd, packet)
+    }
+    /// Iterate over received packets, with each one in a `Vec` of the
+    /// exact length.
+    ///
+    /// The iterator ends when the peer closes the connection or shuts down
+    /// its write half.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// let (client, server) = uds::UnixSeqpacketConn::pair().unwrap();
+    /// client.send(b"ping").unwrap();
+    /// client.send(&[b'x'; 1000]).unwrap();
+    /// drop(client);
+    ///
+    /// let received = server.packets().collect::<Result<Vec<_>, _>>().unwrap();
+    /// assert_eq!(received, vec![b"ping".to_vec(), vec![b'x'; 1000]]);
+    /// ```
+    pub fn packets(&self) -> Packets<'_> {
+        Packets { conn: self }
+    }
+    /// Receive a packet from the peer, and get its full length even if it
+    /// didn't fit in the buffer.
+    ///
+    /// If the returned length is greater than the length of `buffer`, the
+    /// packet was truncated and the rest of it is lost.
+    ///
+    /// This uses `MSG_TRUNC`, which only Linux and Android support for
+    /// `recv()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let (a, b) = uds::UnixSeqpacketConn::pair().unwrap();
+    /// a.send(b"twelve bytes").unwrap();
+    /// let mut buf = [0; 6];
+    /// assert_eq!(b.recv_full_size(&mut buf).unwrap(), 12);
+    /// assert_eq!(&buf, b"twelve");
+    /// ```
+    #[cfg(any(target_os="linux", target_os="android"))]
+    pub fn recv_full_size(&self,  buffer: &mut[u8]) -> Result<usize, io::Error> {
+        let mut buffers = [IoSliceMut::new(buffer)];
+        recv_ancillary(self.fd, None, MSG_TRUNC, &mut buffers, &mut[])
+            .map(|(bytes, _)| bytes )
+    }
     /// Send a packet assembled from multiple byte slices.
     pub fn send_vectored(&self,  slices: &[IoSlice])
     -> Result<usize, io::Error> {
@@ -259,24 +756,647 @@ impl UnixSeqpacketConn {
         // and the flags accepted by pwritev2() aren't the one we need to pass.
         send_ancillary(self.as_raw_fd(), None, MSG_EOR, slices, &[], None)
     }
-    /// Read a packet into multiple buffers.
+    /// Read a packet into multiple buffers.
+    ///
+    /// The returned `bool` indicates whether the packet was truncated due to
+    /// too short buffers.
+    pub fn recv_vectored(&self,  buffers: &mut[IoSliceMut])
+    -> Result<(usize, bool), io::Error> {
+        recv_ancillary(self.fd, None, 0, buffers, &mut[])
+            .map(|(bytes, ancillary)| (bytes, ancillary.message_truncated()) )
+    }
+    /// Read a packet into multiple buffers, and get all the information
+    /// `recvmsg()` provides about it.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// use std::io::IoSliceMut;
+    ///
+    /// let (a, b) = uds::UnixSeqpacketConn::pair().unwrap();
+    /// a.send_fds(b"with fd", &[0]).unwrap();
+    /// let mut buf = [0; 4];
+    /// let info = b.recv_vectored_info(&mut[IoSliceMut::new(&mut buf)]).unwrap();
+    /// assert_eq!(info.len(), 4);
+    /// assert!(info.is_truncated());
+    /// assert!(info.is_control_truncated()); // there was no room for the fd
+    /// ```
+    pub fn recv_vectored_info(&self,  buffers: &mut[IoSliceMut])
+    -> Result<RecvInfo, io::Error> {
+        recv_ancillary(self.fd, None, 0, buffers, &mut[])
+            .map(|(bytes, ancillary)| RecvInfo::new(bytes, &ancillary) )
+    }
+    /// Get how big an [`AncillaryBuf`](struct.AncillaryBuf.html) must be
+    /// to receive all ancillary messages of the next packet,
+    /// without removing the packet from the queue.
+    ///
+    /// The OS doesn't report how much space was needed when ancillary
+    /// messages are truncated, and the discarded messages can't be received
+    /// again, so this peeks with increasingly bigger buffers until nothing
+    /// is truncated. Any file descriptors received while peeking are closed.
+    ///
+    /// The buffer grows no bigger than what the maximum number of file
+    /// descriptors per message plus credentials and a timestamp need,
+    /// and stops growing if messages are truncated for other reasons,
+    /// such as the process having too many open file descriptors.
+    /// The returned capacity might then not be enough.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// use uds::{UnixSeqpacketConn, AncillaryBuf};
+    /// use std::io::IoSliceMut;
+    /// use std::os::unix::io::AsRawFd;
+    ///
+    /// let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    /// a.send_fds(b"many", &[a.as_raw_fd(); 100]).unwrap();
+    /// let capacity = b.peek_ancillary_capacity().unwrap();
+    /// let mut ancillary_buf = AncillaryBuf::with_capacity(capacity);
+    /// let (_, ancillary) = b.recv_vectored_ancillary(
+    ///     &mut[IoSliceMut::new(&mut[0; 10])],
+    ///     &mut ancillary_buf,
+    /// ).unwrap();
+    /// assert!(!ancillary.ancillary_truncated());
+    /// ```
+    pub fn peek_ancillary_capacity(&self) -> Result<usize, io::Error> {
+        peek_ancillary_capacity(self.fd)
+    }
+    /// Read a packet into multiple buffers, and get an iterator over all
+    /// ancillary messages received with it.
+    ///
+    /// Ancillary messages that don't fit in `ancillary_buf` are discarded,
+    /// which can be detected with
+    /// [`ancillary_truncated()`](struct.Ancillary.html#method.ancillary_truncated).
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// use uds::{UnixSeqpacketConn, AncillaryBuf, AncillaryMessage};
+    /// use std::io::IoSliceMut;
+    /// use std::os::unix::io::AsRawFd;
+    ///
+    /// let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    /// a.send_fds(b"fd", &[a.as_raw_fd()]).unwrap();
+    /// let mut ancillary_buf = AncillaryBuf::default();
+    /// let (len, ancillary) = b.recv_vectored_ancillary(
+    ///     &mut[IoSliceMut::new(&mut[0; 10])],
+    ///     &mut ancillary_buf,
+    /// ).unwrap();
+    /// assert_eq!(len, 2);
+    /// for message in ancillary {
+    ///     match message {
+    ///         AncillaryMessage::Fds(fds) => {
+    ///             assert_eq!(fds.len(), 1);
+    ///             unsafe { libc::close(fds[0]) };
+    ///         }
+    ///         other => panic!("unexpected ancillary message {:?}", other),
+    ///     }
+    /// }
+    /// ```
+    pub fn recv_vectored_ancillary<'a>(&self,
+            buffers: &mut[IoSliceMut],  ancillary_buf: &'a mut AncillaryBuf,
+    ) -> Result<(usize, Ancillary<'a>), io::Error> {
+        recv_ancillary(self.fd, None, 0, buffers, ancillary_buf)
+    }
+    /// Send a packet with associated file descriptors.
+    pub fn send_fds(&self,  bytes: &[u8],  fds: &[RawFd])
+    -> Result<usize, io::Error> {
+        send_ancillary(self.fd, None, MSG_EOR, &[IoSlice::new(bytes)], fds, None)
+    }
+    /// Send a packet with file descriptors from values of any type that
+    /// implements `AsFd`, such as `File`s or `OwnedFd`s or references to them.
+    ///
+    /// Use `BorrowedFd`s to send file descriptors of different types.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// use std::os::unix::io::AsFd;
+    /// use std::fs::File;
+    ///
+    /// let (a, b) = uds::UnixSeqpacketConn::pair().unwrap();
+    /// let file = File::open("README.md").unwrap();
+    /// a.send_as_fds(b"one", [&file]).unwrap();
+    /// a.send_as_fds(b"two", [file.as_fd(), a.as_fd()]).unwrap();
+    /// assert_eq!(b.recv_owned_fds(&mut[0; 3], 2).unwrap().2.len(), 1);
+    /// assert_eq!(b.recv_owned_fds(&mut[0; 3], 2).unwrap().2.len(), 2);
+    /// ```
+    pub fn send_as_fds<I>(&self,  bytes: &[u8],  fds: I) -> Result<usize, io::Error>
+    where I: IntoIterator, I::Item: AsFd {
+        send_as_fds(self.fd, None, MSG_EOR, &[IoSlice::new(bytes)], fds)
+    }
+    /// Build a packet with any combination of content, file descriptors
+    /// and credentials, and then send it.
+    ///
+    /// See [`SendAncillary`](struct.SendAncillary.html) for an example.
+    pub fn send_ancillary(&self) -> SendAncillary<'_> {
+        SendAncillary::new(self.fd, MSG_EOR)
+    }
+    /// Send a socket together with a tag for its type.
+    ///
+    /// The socket is not closed, but as both processes then have it open,
+    /// the sender should usually drop it afterwards.
+    /// See [`PassedSocket`](enum.PassedSocket.html) for an example.
+    pub fn send_socket(&self,  socket: &PassedSocket) -> Result<(), io::Error> {
+        send_socket(self.fd, MSG_EOR, socket)
+    }
+    /// Receive a socket sent with [`send_socket()`](#method.send_socket),
+    /// as the correct type.
+    ///
+    /// The received socket is in the same blocking or nonblocking mode as
+    /// the sent socket, as the mode is shared by all copies of the file
+    /// descriptor and therefore isn't changed.
+    ///
+    /// # Errors
+    ///
+    /// Fails with an error of kind `UnexpectedEof` if the connection is closed,
+    /// and `InvalidData` if the packet is not a socket and type tag,
+    /// or the type of the received socket doesn't match the tag.
+    /// In the latter case the received file descriptor is closed.
+    pub fn recv_socket(&self) -> Result<PassedSocket, io::Error> {
+        recv_socket(self.fd)
+    }
+    /// Send a big payload in a sealed memfd instead of through the socket.
+    ///
+    /// The payload is copied once into an anonymous memory-backed file,
+    /// which is sealed against modification and sent together with its
+    /// length, so it isn't limited by the size of the socket buffers.
+    /// Receive it with [`recv_memfd()`](#method.recv_memfd).
+    ///
+    /// Requires the `memfd` feature.
+    #[cfg(all(feature="memfd", any(target_os="linux", target_os="android")))]
+    pub fn send_memfd(&self,  payload: &[u8]) -> Result<(), io::Error> {
+        send_memfd(self.fd, MSG_EOR, payload)
+    }
+    /// Receive a payload sent with [`send_memfd()`](#method.send_memfd)
+    /// and map it into memory.
+    ///
+    /// See [`MemfdPayload`](struct.MemfdPayload.html) for an example.
+    ///
+    /// # Errors
+    ///
+    /// Fails with an error of kind `UnexpectedEof` if the connection is closed,
+    /// and `InvalidData` if the packet is not a memfd and length, the memfd
+    /// is not sealed against writing, shrinking and growing, or its size
+    /// doesn't match the length.
+    /// The received file descriptor is closed in all cases.
+    #[cfg(all(feature="memfd", any(target_os="linux", target_os="android")))]
+    pub fn recv_memfd(&self) -> Result<MemfdPayload, io::Error> {
+        recv_memfd(self.fd)
+    }
+    /// Send a packet with explicit credentials (`SCM_CREDENTIALS`).
+    ///
+    /// The kernel checks that the credentials match the sending process
+    /// unless it has the necessary privileges, see
+    /// [`SendCredentials::Custom`](enum.SendCredentials.html#variant.Custom).
+    /// The receiver only gets them if it has enabled receiving credentials,
+    /// otherwise they are discarded.
+    /// This is how privileged processes that forward requests on behalf of
+    /// other processes can pass on their credentials.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use uds::{UnixSeqpacketConn, SendCredentials};
+    ///
+    /// let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    /// b.set_receive_credentials(true).unwrap();
+    /// let pid = std::process::id();
+    /// let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+    /// let creds = SendCredentials::Custom{ pid, uid, gid };
+    /// a.send_with_credentials(b"from me", creds).unwrap();
+    /// let (_, _, received) = b.recv_with_credentials(&mut[0; 10]).unwrap();
+    /// assert_eq!(received.unwrap().pid(), Some(pid));
+    /// ```
+    #[cfg(any(target_os="linux", target_os="android"))]
+    pub fn send_with_credentials(&self,  bytes: &[u8],  creds: SendCredentials)
+    -> Result<usize, io::Error> {
+        send_ancillary(self.fd, None, MSG_EOR, &[IoSlice::new(bytes)], &[], Some(creds))
+    }
+    /// Receive a packet and associated file descriptors.
+    pub fn recv_fds(&self,  byte_buffer: &mut[u8],  fd_buffer: &mut[RawFd])
+    -> Result<(usize, bool, usize), io::Error> {
+        recv_fds(self.fd, None, &mut[IoSliceMut::new(byte_buffer)], fd_buffer)
+    }
+    /// Receive a packet and up to `max_fds` associated file descriptors,
+    /// which are closed when dropped.
+    ///
+    /// The returned `bool` indicates whether the packet was truncated due to
+    /// too short buffer.
+    /// Any file descriptors beyond `max_fds` are closed.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// use std::os::unix::io::AsRawFd;
+    /// use std::fs::File;
+    ///
+    /// let (a, b) = uds::UnixSeqpacketConn::pair().unwrap();
+    /// let file = File::open("README.md").unwrap();
+    /// a.send_fds(b"file", &[file.as_raw_fd()]).unwrap();
+    /// let (len, _, mut fds) = b.recv_owned_fds(&mut[0; 10], 1).unwrap();
+    /// assert_eq!(len, 4);
+    /// let received = File::from(fds.remove(0));
+    /// assert!(received.metadata().unwrap().is_file());
+    /// ```
+    pub fn recv_owned_fds(&self,  byte_buffer: &mut[u8],  max_fds: usize)
+    -> Result<(usize, bool, Vec<OwnedFd>), io::Error> {
+        recv_owned_fds(self.fd, None, &mut[IoSliceMut::new(byte_buffer)], max_fds, true)
+    }
+    /// Receive a packet and at most `max_fds` associated file descriptors,
+    /// and fail if the peer sent more than that.
+    ///
+    /// Unlike [`recv_owned_fds()`](#method.recv_owned_fds), which closes
+    /// excess file descriptors and carries on, this makes it possible to
+    /// detect and reject peers that try to fill up the file descriptor table.
+    /// The returned `bool` indicates whether the packet was truncated due to
+    /// too short buffer.
+    ///
+    /// # Errors
+    ///
+    /// Fails with an error of kind `InvalidData` if more than `max_fds` file
+    /// descriptors were sent with the packet. All of them will then have been
+    /// closed, and the content of the packet is lost.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// use std::io::ErrorKind;
+    /// use std::os::unix::io::AsRawFd;
+    ///
+    /// let (a, b) = uds::UnixSeqpacketConn::pair().unwrap();
+    /// a.send_fds(b"one", &[a.as_raw_fd()]).unwrap();
+    /// a.send_fds(b"three", &[a.as_raw_fd(); 3]).unwrap();
+    /// assert_eq!(b.recv_fds_limited(&mut[0; 10], 2).unwrap().2.len(), 1);
+    /// let error = b.recv_fds_limited(&mut[0; 10], 2).unwrap_err();
+    /// assert_eq!(error.kind(), ErrorKind::InvalidData);
+    /// ```
+    pub fn recv_fds_limited(&self,  byte_buffer: &mut[u8],  max_fds: usize)
+    -> Result<(usize, bool, Vec<OwnedFd>), io::Error> {
+        recv_fds_limited(self.fd, None, &mut[IoSliceMut::new(byte_buffer)], max_fds)
+    }
+    /// Receive a packet and up to `max_fds` associated file descriptors,
+    /// without making them close-on-exec.
+    ///
+    /// This is for passing the received file descriptors on to processes
+    /// started with `exec()`.
+    /// On operating systems that support `MSG_CMSG_CLOEXEC` it is simply
+    /// not passed, while on others the file descriptors are not made
+    /// close-on-exec after receiving them.
+    /// [`recv_owned_fds()`](#method.recv_owned_fds) and all other methods
+    /// make received file descriptors close-on-exec.
+    pub fn recv_inheritable_fds(&self,  byte_buffer: &mut[u8],  max_fds: usize)
+    -> Result<(usize, bool, Vec<OwnedFd>), io::Error> {
+        recv_owned_fds(self.fd, None, &mut[IoSliceMut::new(byte_buffer)], max_fds, false)
+    }
+
+    /// Send a packet to the peer without blocking, even though the socket is
+    /// in blocking mode.
+    ///
+    /// Returns an error of kind `WouldBlock` if the send buffer is full.
+    /// This uses `MSG_DONTWAIT`, so the socket doesn't need to be switched
+    /// between blocking and nonblocking mode.
+    pub fn try_send(&self,  packet: &[u8]) -> Result<usize, io::Error> {
+        let ptr = packet.as_ptr() as *const c_void;
+        let flags = MSG_NOSIGNAL | MSG_EOR | MSG_DONTWAIT;
+        let sent = cvt_r!(unsafe { send(self.fd, ptr, packet.len(), flags) })
+            .map_err(explain_too_big)?;
+        Ok(sent as usize)
+    }
+    /// Receive a packet from the peer without blocking, even though the
+    /// socket is in blocking mode.
+    ///
+    /// Returns an error of kind `WouldBlock` if no packet is available.
+    /// The returned `bool` indicates whether the packet was truncated due to
+    /// too short buffer.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// # use std::io::ErrorKind;
+    /// let (a, b) = uds::UnixSeqpacketConn::pair().unwrap();
+    /// assert_eq!(b.try_recv(&mut[0; 10]).unwrap_err().kind(), ErrorKind::WouldBlock);
+    /// a.try_send(b"now").unwrap();
+    /// assert_eq!(b.try_recv(&mut[0; 10]).unwrap(), (3, false));
+    /// ```
+    pub fn try_recv(&self,  buffer: &mut[u8]) -> Result<(usize, bool), io::Error> {
+        self.try_recv_vectored(&mut[IoSliceMut::new(buffer)])
+    }
+    /// Receive a packet into multiple buffers without blocking, even though
+    /// the socket is in blocking mode.
+    ///
+    /// Returns an error of kind `WouldBlock` if no packet is available.
+    /// The returned `bool` indicates whether the packet was truncated due to
+    /// too short buffers.
+    pub fn try_recv_vectored(&self,  buffers: &mut[IoSliceMut])
+    -> Result<(usize, bool), io::Error> {
+        recv_ancillary(self.fd, None, MSG_DONTWAIT, buffers, &mut[])
+            .map(|(bytes, ancillary)| (bytes, ancillary.message_truncated()) )
+    }
+
+    /// Send a packet, blocking until `deadline` at most.
+    ///
+    /// Fails with an error of kind `TimedOut` if the packet cannot be sent
+    /// before the deadline.
+    /// Unlike `SO_SNDTIMEO` this only applies to this call.
+    /// If there is room for the packet it's sent even if the deadline has
+    /// passed.
+    pub fn send_deadline(&self,  packet: &[u8],  deadline: Instant)
+    -> Result<usize, io::Error> {
+        loop {
+            wait_until(self.fd, POLLOUT, deadline)?;
+            match self.try_send(packet) {
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => continue,
+                result => return result,
+            }
+        }
+    }
+    /// Receive a packet, blocking until `deadline` at most.
+    ///
+    /// Fails with an error of kind `TimedOut` if no packet arrives before the
+    /// deadline.
+    /// Unlike `SO_RCVTIMEO` this only applies to this call.
+    /// A packet that is already available is received even if the deadline
+    /// has passed.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// # use std::io::ErrorKind;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let (a, b) = uds::UnixSeqpacketConn::pair().unwrap();
+    /// let deadline = Instant::now() + Duration::from_millis(10);
+    /// assert_eq!(b.recv_deadline(&mut[0; 10], deadline).unwrap_err().kind(), ErrorKind::TimedOut);
+    /// a.send(b"ready").unwrap();
+    /// assert_eq!(b.recv_deadline(&mut[0; 10], deadline).unwrap(), (5, false));
+    /// ```
+    pub fn recv_deadline(&self,  buffer: &mut[u8],  deadline: Instant)
+    -> Result<(usize, bool), io::Error> {
+        loop {
+            wait_until(self.fd, POLLIN, deadline)?;
+            match self.try_recv(buffer) {
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => continue,
+                result => return result,
+            }
+        }
+    }
+
+    /// Shut down the read, write, or both halves of this connection.
+    ///
+    /// After shutting down the write half the peer receives end-of-file
+    /// (a zero-length read) once it has received all packets sent before,
+    /// while this side can keep receiving until the peer shuts down or closes.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// # use std::net::Shutdown;
+    /// let (a, b) = uds::UnixSeqpacketConn::pair().unwrap();
+    /// a.send(b"last").unwrap();
+    /// a.shutdown(Shutdown::Write).unwrap();
+    /// assert!(a.send(b"too late").is_err());
+    /// assert_eq!(b.recv(&mut[0; 10]).unwrap(), (4, false));
+    /// assert_eq!(b.recv(&mut[0; 10]).unwrap(), (0, false));
+    /// b.send(b"reply").unwrap();
+    /// assert_eq!(a.recv(&mut[0; 10]).unwrap(), (5, false));
+    /// ```
+    pub fn shutdown(&self,  how: Shutdown) -> Result<(), io::Error> {
+        shutdown(self.fd, how)
+    }
+
+    /// Enable or disable receiving the credentials of the sender with every
+    /// packet (`SO_PASSCRED`).
+    ///
+    /// The kernel attaches the pid, uid and gid of the sending process to
+    /// every packet when this is enabled, even if the sender doesn't send
+    /// them explicitly.
+    /// Use [`recv_with_credentials()`](#method.recv_with_credentials) to
+    /// get them.
+    #[cfg(any(target_os="linux", target_os="android"))]
+    pub fn set_receive_credentials(&self,  receive: bool) -> Result<(), io::Error> {
+        set_passcred(self.fd, receive)
+    }
+    /// Receive a packet and the credentials of the process that sent it.
+    ///
+    /// The credentials are `None` unless receiving credentials has been
+    /// enabled with [`set_receive_credentials()`](#method.set_receive_credentials).
+    /// The returned `bool` indicates whether the packet was truncated due to
+    /// too short buffer.
+    /// Any file descriptors sent with the packet are closed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let (a, b) = uds::UnixSeqpacketConn::pair().unwrap();
+    /// b.set_receive_credentials(true).unwrap();
+    /// a.send(b"who am I?").unwrap();
+    /// let mut buf = [0; 20];
+    /// let (len, _, creds) = b.recv_with_credentials(&mut buf).unwrap();
+    /// assert_eq!(&buf[..len], b"who am I?");
+    /// assert_eq!(creds.unwrap().pid(), Some(std::process::id()));
+    /// ```
+    #[cfg(any(target_os="linux", target_os="android"))]
+    pub fn recv_with_credentials(&self,  buffer: &mut[u8])
+    -> Result<(usize, bool, Option<ReceivedCredentials>), io::Error> {
+        recv_with_credentials(self.fd, None, buffer)
+    }
+
+    /// Receive a packet together with up to `max_fds` file descriptors and
+    /// any credentials or timestamp attached to it.
+    ///
+    /// This is for when more than one of
+    /// [`recv_owned_fds()`](#method.recv_owned_fds),
+    /// [`recv_with_credentials()`](#method.recv_with_credentials) and
+    /// [`recv_with_timestamp()`](#method.recv_with_timestamp) is needed.
+    /// Credentials and timestamps are only received if enabled with
+    /// `set_receive_credentials()` and `set_receive_timestamps()`.
+    /// Received file descriptors are close-on-exec.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// use std::fs::File;
+    ///
+    /// let (a, b) = uds::UnixSeqpacketConn::pair().unwrap();
+    /// let file = File::open("README.md").unwrap();
+    /// a.send_as_fds(b"everything", [&file]).unwrap();
+    /// let mut buf = [0; 20];
+    /// let received = b.recv_full(&mut buf, 2).unwrap();
+    /// assert_eq!(&buf[..received.len()], b"everything");
+    /// assert!(!received.info().is_truncated());
+    /// assert_eq!(received.fds().len(), 1);
+    /// ```
+    pub fn recv_full(&self,  buffer: &mut[u8],  max_fds: usize)
+    -> Result<ReceivedMessage, io::Error> {
+        recv_full(self.fd, buffer, max_fds)
+    }
+
+    /// Receive a packet, and close the file descriptors sent with it that
+    /// `policy` doesn't accept.
+    ///
+    /// This is for services that don't expect file descriptors (or only a
+    /// few), so that peers can't make them accumulate open files.
+    /// The number of closed file descriptors is reported by
+    /// [`ReceivedMessage::refused_fds()`](struct.ReceivedMessage.html#method.refused_fds).
+    /// Like with [`recv_full()`](#method.recv_full), any credentials or
+    /// timestamp sent with the packet are also returned.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// use uds::{UnixSeqpacketConn, FdPolicy};
+    ///
+    /// let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    /// a.send_fds(b"unwanted", &[0, 1, 2]).unwrap();
+    /// let mut buf = [0; 10];
+    /// let received = b.recv_with_fd_policy(&mut buf, FdPolicy::RefuseFds).unwrap();
+    /// assert_eq!(&buf[..received.len()], b"unwanted");
+    /// assert_eq!(received.fds().len(), 0);
+    /// assert_eq!(received.refused_fds(), 3);
+    /// ```
+    pub fn recv_with_fd_policy(&self,  buffer: &mut[u8],  policy: FdPolicy)
+    -> Result<ReceivedMessage, io::Error> {
+        recv_with_fd_policy(self.fd, buffer, policy)
+    }
+
+    /// Enable or disable receiving a timestamp of when the kernel received
+    /// every packet.
+    ///
+    /// This uses `SO_TIMESTAMPNS` on Linux and Android, and `SO_TIMESTAMP`
+    /// (which has microsecond resolution) elsewhere.
+    /// Use [`recv_with_timestamp()`](#method.recv_with_timestamp) to get them.
+    #[cfg(not(any(target_os="illumos", target_os="solaris")))]
+    pub fn set_receive_timestamps(&self,  receive: bool) -> Result<(), io::Error> {
+        set_timestamps(self.fd, receive)
+    }
+    /// Receive a packet and the timestamp of when the kernel received it.
+    ///
+    /// The timestamp is `None` unless enabled with
+    /// [`set_receive_timestamps()`](#method.set_receive_timestamps).
+    /// The returned `bool` indicates whether the packet was truncated due to
+    /// too short buffer.
+    /// Any file descriptors sent with the packet are closed.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// use std::time::SystemTime;
+    ///
+    /// let (a, b) = uds::UnixSeqpacketConn::pair().unwrap();
+    /// b.set_receive_timestamps(true).unwrap();
+    /// a.send(b"when?").unwrap();
+    /// let (len, _, timestamp) = b.recv_with_timestamp(&mut[0; 10]).unwrap();
+    /// assert_eq!(len, 5);
+    /// assert!(timestamp.unwrap() <= SystemTime::now());
+    /// ```
+    #[cfg(not(any(target_os="illumos", target_os="solaris")))]
+    pub fn recv_with_timestamp(&self,  buffer: &mut[u8])
+    -> Result<(usize, bool, Option<SystemTime>), io::Error> {
+        recv_with_timestamp(self.fd, None, buffer)
+    }
+
+    /// Get the size of the biggest packet that can be sent.
+    ///
+    /// This is derived from the size of the send buffer (`SO_SNDBUF`),
+    /// minus what Linux reserves for bookkeeping.
+    /// Sending bigger packets fails with an error of kind `InvalidInput`.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// # use std::io::ErrorKind;
+    /// let (a, _b) = uds::UnixSeqpacketConn::pair().unwrap();
+    /// let max = a.max_send_size().unwrap();
+    /// let error = a.send(&vec![0; max + 1]).unwrap_err();
+    /// assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    /// ```
+    pub fn max_send_size(&self) -> Result<usize, io::Error> {
+        max_send_size(self.fd)
+    }
+
+    /// Get the number of bytes that can be received without blocking.
+    ///
+    /// This is the total size of all queued packets on Linux, but might be
+    /// only the size of the next packet on other operating systems.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// let (a, b) = uds::UnixSeqpacketConn::pair().unwrap();
+    /// assert_eq!(b.bytes_available().unwrap(), 0);
+    /// a.send(b"pending").unwrap();
+    /// assert_eq!(b.bytes_available().unwrap(), 7);
+    /// ```
+    pub fn bytes_available(&self) -> Result<usize, io::Error> {
+        bytes_available(self.fd)
+    }
+
+    /// Get and clear the pending socket error (`SO_ERROR`), if any.
     ///
-    /// The returned `bool` indicates whether the packet was truncated due to
-    /// too short buffers.
-    pub fn recv_vectored(&self,  buffers: &mut[IoSliceMut])
-    -> Result<(usize, bool), io::Error> {
-        recv_ancillary(self.fd, None, 0, buffers, &mut[])
-            .map(|(bytes, ancillary)| (bytes, ancillary.message_truncated()) )
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// let (a, _b) = uds::UnixSeqpacketConn::pair().unwrap();
+    /// assert!(a.take_error().unwrap().is_none());
+    /// ```
+    pub fn take_error(&self) -> Result<Option<io::Error>, io::Error> {
+        take_error(self.fd)
     }
-    /// Send a packet with associated file descriptors.
-    pub fn send_fds(&self,  bytes: &[u8],  fds: &[RawFd])
-    -> Result<usize, io::Error> {
-        send_ancillary(self.fd, None, MSG_EOR, &[IoSlice::new(bytes)], fds, None)
+
+    /// Check whether the peer is still connected, without receiving anything
+    /// or waiting.
+    ///
+    /// This returns `false` once the peer has closed the connection
+    /// or an error is pending, even if there are still packets to receive.
+    /// A peer that only shut down its write half is still considered alive.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// let (a, b) = uds::UnixSeqpacketConn::pair().unwrap();
+    /// assert!(b.is_peer_alive().unwrap());
+    /// drop(a);
+    /// assert!(!b.is_peer_alive().unwrap());
+    /// ```
+    pub fn is_peer_alive(&self) -> Result<bool, io::Error> {
+        Ok(poll_now(self.fd, 0)? & (POLLHUP | POLLERR) == 0)
     }
-    /// Receive a packet and associated file descriptors.
-    pub fn recv_fds(&self,  byte_buffer: &mut[u8],  fd_buffer: &mut[RawFd])
-    -> Result<(usize, bool, usize), io::Error> {
-        recv_fds(self.fd, None, &mut[IoSliceMut::new(byte_buffer)], fd_buffer)
+
+    /// Check whether the file descriptor is in nonblocking mode.
+    ///
+    /// This is useful for verifying sockets created with `from_raw_fd()`,
+    /// as the mode is not checked or changed then.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// use std::os::unix::io::{FromRawFd, IntoRawFd};
+    /// use uds::{UnixSeqpacketConn, nonblocking};
+    ///
+    /// let (a, _b) = nonblocking::UnixSeqpacketConn::pair().unwrap();
+    /// let a = unsafe { UnixSeqpacketConn::from_raw_fd(a.into_raw_fd()) };
+    /// assert!(a.is_nonblocking().unwrap());
+    /// ```
+    pub fn is_nonblocking(&self) -> Result<bool, io::Error> {
+        is_nonblocking(self.fd)
     }
 
     /// Create a new file descriptor also pointing to this side of this connection.
@@ -386,22 +1506,107 @@ pub struct UnixSeqpacketListener {
     fd: RawFd
 }
 impl_rawfd_traits!{UnixSeqpacketListener}
+impl_socket2_if_enabled!{UnixSeqpacketListener, nonblocking=false}
 impl UnixSeqpacketListener {
-    pub fn bind<P: AsRef<Path>>(path: P) -> Result<Self, io::Error> {
-        let addr = UnixSocketAddr::from_path(path.as_ref())?;
-        Self::bind_unix_addr(&addr)
+    /// Create a socket bound to `addr` and start listening on it.
+    ///
+    /// This is identical to [`bind_unix_addr()`](#method.bind_unix_addr),
+    /// and exists for convenience and compatibility with std.
+    /// Note that strings are parsed with
+    /// [`UnixSocketAddr::new()`](struct.UnixSocketAddr.html#method.new),
+    /// so `"@name"` is an abstract address. Pass a `Path` to avoid that.
+    pub fn bind<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error> {
+        Self::bind_unix_addr(addr)
+    }
+    /// Create a socket bound to `addr` and start listening on it.
+    pub fn bind_unix_addr<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error> {
+        let socket = Socket::new(SOCK_SEQPACKET, false)?;
+        bind_to(socket.as_raw_fd(), &addr.to_unix_addr()?)?;
+        socket.start_listening()?;
+        Ok(UnixSeqpacketListener { fd: socket.into_raw_fd() })
     }
-    pub fn bind_unix_addr(addr: &UnixSocketAddr) -> Result<Self, io::Error> {
+    /// Create a socket bound to `addr` and start listening on it,
+    /// replacing the socket file left behind by a server that is no longer
+    /// running.
+    ///
+    /// If binding fails because the path exists, this tries to connect to it,
+    /// and only removes the file if that is refused and it's a socket file.
+    ///
+    /// # Errors
+    ///
+    /// Fails with an error of kind `AddrInUse` if another server is listening
+    /// on the path, and `AlreadyExists` if the path isn't a socket.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// use std::io::ErrorKind;
+    /// use uds::UnixSeqpacketListener;
+    ///
+    /// # let _ = std::fs::remove_file("seqpacket_takeover.socket");
+    /// drop(UnixSeqpacketListener::bind("seqpacket_takeover.socket").unwrap());
+    /// let server = UnixSeqpacketListener::bind_unix_addr_taking_over("seqpacket_takeover.socket")
+    ///     .unwrap();
+    /// let err = UnixSeqpacketListener::bind_unix_addr_taking_over("seqpacket_takeover.socket")
+    ///     .unwrap_err();
+    /// assert_eq!(err.kind(), ErrorKind::AddrInUse);
+    /// # drop(server);
+    /// # std::fs::remove_file("seqpacket_takeover.socket").unwrap();
+    /// ```
+    pub fn bind_unix_addr_taking_over<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error> {
         let socket = Socket::new(SOCK_SEQPACKET, false)?;
-        bind_to(socket.as_raw_fd(), addr)?;
+        bind_to_taking_over(socket.as_raw_fd(), &addr.to_unix_addr()?, SOCK_SEQPACKET)?;
         socket.start_listening()?;
         Ok(UnixSeqpacketListener { fd: socket.into_raw_fd() })
     }
+    /// Create a socket bound to the path `path` and start listening on it,
+    /// and remove the socket file when the returned listener is dropped.
+    ///
+    /// An existing file at `path` is not removed first, as it might belong
+    /// to another running server.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// # let _ = std::fs::remove_file("seqpacket_cleanup.socket");
+    /// let listener = uds::UnixSeqpacketListener::bind_with_cleanup("seqpacket_cleanup.socket")
+    ///     .expect("create seqpacket listener");
+    /// assert!(listener.path().exists());
+    /// let _client = uds::UnixSeqpacketConn::connect("seqpacket_cleanup.socket").unwrap();
+    /// let _conn = listener.accept().unwrap();
+    /// drop(listener);
+    /// assert!(!std::path::Path::new("seqpacket_cleanup.socket").exists());
+    /// ```
+    pub fn bind_with_cleanup<P: AsRef<Path>>(path: P) -> Result<RemoveOnDrop<Self>, io::Error> {
+        let path = path.as_ref();
+        let listener = Self::bind_unix_addr(UnixSocketAddr::from_path(path)?)?;
+        Ok(RemoveOnDrop { listener, path: path.to_path_buf() })
+    }
 
     pub fn local_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
         local_addr(self.fd)
     }
 
+    /// Check whether the file descriptor is in nonblocking mode.
+    ///
+    /// This is useful for verifying sockets created with `from_raw_fd()`,
+    /// as the mode is not checked or changed then.
+    pub fn is_nonblocking(&self) -> Result<bool, io::Error> {
+        is_nonblocking(self.fd)
+    }
+
+    /// Accept a connection, without getting the address of the peer.
+    ///
+    /// Use [`accept_unix_addr()`](#method.accept_unix_addr) to get the
+    /// address too.
+    pub fn accept(&self) -> Result<UnixSeqpacketConn, io::Error> {
+        let socket = Socket::accept_without_addr(self.fd, false)?;
+        Ok(UnixSeqpacketConn { fd: socket.into_raw_fd() })
+    }
+
+    /// Accept a connection and get the address of the peer.
     pub fn accept_unix_addr(&self)
     -> Result<(UnixSeqpacketConn, UnixSocketAddr), io::Error> {
         let (socket, addr) = Socket::accept_from(self.fd, false)?;
@@ -409,6 +1614,79 @@ impl UnixSeqpacketListener {
         Ok((conn, addr))
     }
 
+    /// Accept a connection and get the address and credentials of the peer.
+    ///
+    /// The credentials are those of the process when it connected,
+    /// as returned by
+    /// [`initial_peer_credentials()`](struct.UnixSeqpacketConn.html#method.initial_peer_credentials),
+    /// which makes it possible to decide whether to serve the peer before
+    /// the connection is handed to anything else.
+    ///
+    /// The connection is closed if getting the credentials fails.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(any(target_os="linux", target_os="android", target_os="freebsd"), doc="```")]
+    #[cfg_attr(not(any(target_os="linux", target_os="android", target_os="freebsd")), doc="```no_run")]
+    /// let addr = uds::UnixSocketAddr::new_unique().unwrap();
+    /// let listener = uds::UnixSeqpacketListener::bind_unix_addr(addr).unwrap();
+    /// let _client = uds::UnixSeqpacketConn::connect_unix_addr(addr).unwrap();
+    /// let (conn, _, creds) = listener.accept_with_credentials().unwrap();
+    /// if creds.euid() != 0 {
+    ///     conn.send(b"go away!").unwrap();
+    /// }
+    /// # if let Some(path) = addr.as_pathname() { let _ = std::fs::remove_file(path); }
+    /// ```
+    pub fn accept_with_credentials(&self)
+    -> Result<(UnixSeqpacketConn, UnixSocketAddr, ConnCredentials), io::Error> {
+        let (conn, addr) = self.accept_unix_addr()?;
+        let credentials = conn.initial_peer_credentials()?;
+        Ok((conn, addr, credentials))
+    }
+
+    /// Accept a connection without setting close-on-exec on it,
+    /// so that it's inherited by processes started with `exec()`.
+    ///
+    /// Close-on-exec is left unset atomically where `accept4()` is supported,
+    /// instead of being cleared after `accept()`.
+    /// All other file descriptors created by this crate are close-on-exec.
+    pub fn accept_inheritable(&self)
+    -> Result<(UnixSeqpacketConn, UnixSocketAddr), io::Error> {
+        let (socket, addr) = Socket::accept_inheritable(self.fd, false)?;
+        let conn = UnixSeqpacketConn { fd: socket.into_raw_fd() };
+        Ok((conn, addr))
+    }
+
+    /// Block until a connection is available, and accept it as a nonblocking
+    /// connection.
+    ///
+    /// The connection is made nonblocking atomically with `accept4()` where
+    /// that is supported.
+    /// This is useful for servers that accept connections on a dedicated
+    /// thread and hand them off to an event loop.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// # use uds::{UnixSeqpacketListener, UnixSeqpacketConn, UnixSocketAddr};
+    /// # use std::io::ErrorKind;
+    /// let addr = UnixSocketAddr::new_unique().unwrap();
+    /// let listener = UnixSeqpacketListener::bind_unix_addr(addr).unwrap();
+    /// let _client = UnixSeqpacketConn::connect_unix_addr(addr).unwrap();
+    /// let (conn, _) = listener.accept_nonblocking_unix_addr().unwrap();
+    /// assert_eq!(conn.recv(&mut[0; 10]).unwrap_err().kind(), ErrorKind::WouldBlock);
+    /// # if let Some(path) = addr.as_pathname() {
+    /// #     std::fs::remove_file(path).unwrap();
+    /// # }
+    /// ```
+    pub fn accept_nonblocking_unix_addr(&self)
+    -> Result<(NonblockingUnixSeqpacketConn, UnixSocketAddr), io::Error> {
+        let (socket, addr) = Socket::accept_from(self.fd, true)?;
+        let conn = NonblockingUnixSeqpacketConn { fd: socket.into_raw_fd() };
+        Ok((conn, addr))
+    }
+
     /// Create a new file descriptor listening for the same connections.
     pub fn try_clone(&self) -> Result<Self, io::Error> {
         let cloned = Socket::try_clone_from(self.fd)?;
@@ -519,36 +1797,126 @@ pub struct NonblockingUnixSeqpacketConn {
 }
 
 impl_rawfd_traits!{NonblockingUnixSeqpacketConn}
+impl_socket2_if_enabled!{NonblockingUnixSeqpacketConn, nonblocking=true}
 impl_mio_if_enabled!{NonblockingUnixSeqpacketConn}
+impl_async_io_if_enabled!{NonblockingUnixSeqpacketConn}
 
 // can't Deref<Target=UnixSeqpacketConn> because that would include try_clone()
 // and later set_(read|write)_timeout()
 impl NonblockingUnixSeqpacketConn {
-    /// Connect to an unix seqpacket server listening at `path`.
+    /// Connect to an unix seqpacket server listening at `addr`.
     ///
-    /// This is a wrapper around [`connect_unix_addr()`](#method.connect_unix_addr)
-    /// for convenience and compatibility with std.
-    pub fn connect<P: AsRef<Path>>(path: P) -> Result<Self, io::Error> {
-        let addr = UnixSocketAddr::from_path(&path)?;
-        Self::connect_unix_addr(&addr)
+    /// This is identical to [`connect_unix_addr()`](#method.connect_unix_addr),
+    /// and exists for convenience and compatibility with std.
+    /// Note that strings are parsed with
+    /// [`UnixSocketAddr::new()`](struct.UnixSocketAddr.html#method.new),
+    /// so `"@name"` is an abstract address. Pass a `Path` to avoid that.
+    pub fn connect<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error> {
+        Self::connect_unix_addr(addr)
     }
     /// Connect to an unix seqpacket server listening at `addr`.
-    pub fn connect_unix_addr(addr: &UnixSocketAddr) -> Result<Self, io::Error> {
+    ///
+    /// The socket is nonblocking from creation, so the connect itself
+    /// doesn't block.
+    ///
+    /// # Incomplete connects
+    ///
+    /// Unix socket connections are usually established immediately,
+    /// but if the OS reports that the connection is in progress (`EINPROGRESS`),
+    /// the connection is returned anyway, like mio does.
+    /// Wait for it to become writable and then call
+    /// [`poll_connected()`](#method.poll_connected) to find out whether
+    /// connecting succeeded, or use [`start_connect()`](#method.start_connect)
+    /// to know whether it's necessary.
+    ///
+    /// On Linux, an error of kind `WouldBlock` is returned instead if the
+    /// listener's backlog is full. Retry later, or use the blocking type.
+    pub fn connect_unix_addr<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error> {
+        let addr = addr.to_unix_addr()?;
         let socket = Socket::new(SOCK_SEQPACKET, true)?;
-        connect_to(socket.as_raw_fd(), addr)?;
+        connect_in_progress_ok(socket.as_raw_fd(), &addr)?;
         Ok(NonblockingUnixSeqpacketConn { fd: socket.into_raw_fd() })
     }
+    /// Start connecting to an unix seqpacket server listening at `addr`,
+    /// and tell whether the connection was established immediately.
+    ///
+    /// If it wasn't, wait for the connection to become writable with an event
+    /// loop and then call [`poll_connected()`](#method.poll_connected),
+    /// or call [`finish_connect()`](#method.finish_connect) to wait for it.
+    ///
+    /// On Linux, an error of kind `WouldBlock` is returned if the
+    /// listener's backlog is full, as no connect is in progress then.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// use uds::nonblocking::{UnixSeqpacketListener, UnixSeqpacketConn};
+    ///
+    /// # let addr = uds::UnixSocketAddr::new_unique().unwrap();
+    /// let listener = UnixSeqpacketListener::bind_unix_addr(addr).unwrap();
+    /// let (conn, connected) = UnixSeqpacketConn::start_connect(addr).unwrap();
+    /// if !connected {
+    ///     conn.finish_connect(None).unwrap();
+    /// }
+    /// assert!(conn.poll_connected().unwrap());
+    /// # if let Some(path) = addr.as_pathname() { let _ = std::fs::remove_file(path); }
+    /// ```
+    pub fn start_connect<A: ToUnixSocketAddr>(addr: A) -> Result<(Self, bool), io::Error> {
+        let addr = addr.to_unix_addr()?;
+        let socket = Socket::new(SOCK_SEQPACKET, true)?;
+        let connected = match connect_to(socket.as_raw_fd(), &addr) {
+            Ok(()) => true,
+            Err(ref e) if e.raw_os_error() == Some(EINPROGRESS) => false,
+            Err(e) => return Err(e),
+        };
+        Ok((NonblockingUnixSeqpacketConn { fd: socket.into_raw_fd() }, connected))
+    }
     /// Bind to an address before connecting to a listening seqpacket socket.
-    pub fn connect_from_to_unix_addr(from: &UnixSocketAddr,  to: &UnixSocketAddr)
-    -> Result<Self, io::Error> {
+    ///
+    /// See [`connect_unix_addr()`](#method.connect_unix_addr) for how
+    /// incomplete connects are handled.
+    pub fn connect_from_to_unix_addr<F, T>(from: F,  to: T) -> Result<Self, io::Error>
+    where F: ToUnixSocketAddr, T: ToUnixSocketAddr {
+        let (from, to) = (from.to_unix_addr()?, to.to_unix_addr()?);
         let socket = Socket::new(SOCK_SEQPACKET, true)?;
-        bind_to(socket.as_raw_fd(), from)?;
-        connect_to(socket.as_raw_fd(), to)?;
+        bind_to(socket.as_raw_fd(), &from)?;
+        connect_in_progress_ok(socket.as_raw_fd(), &to)?;
         Ok(NonblockingUnixSeqpacketConn { fd: socket.into_raw_fd() })
     }
+    /// Check whether an in-progress connect has completed, without waiting.
+    ///
+    /// Returns `Ok(true)` once connected and `Ok(false)` while the connect
+    /// is still in progress, and the error if connecting failed.
+    pub fn poll_connected(&self) -> Result<bool, io::Error> {
+        if let Some(error) = take_error(self.fd)? {
+            return Err(error);
+        }
+        match peer_addr(self.fd) {
+            Ok(_) => Ok(true),
+            Err(ref e) if e.kind() == ErrorKind::NotConnected => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+    /// Wait for an in-progress connect to complete, and get its outcome.
+    ///
+    /// Waits indefinitely if `timeout` is `None`,
+    /// and fails with an error of kind `TimedOut` if it expires.
+    pub fn finish_connect(&self,  timeout: Option<Duration>) -> Result<(), io::Error> {
+        wait_for(self.fd, POLLOUT, timeout)?;
+        if self.poll_connected()? {
+            Ok(())
+        } else {
+            Err(io::Error::new(ErrorKind::NotConnected, "socket is not connecting"))
+        }
+    }
 
     /// Create a pair of nonblocking unix-domain seqpacket conneections connected to each other.
     ///
+    /// Both sockets are created nonblocking and close-on-exec atomically
+    /// with `SOCK_NONBLOCK` and `SOCK_CLOEXEC` where supported,
+    /// and have the flags set afterwards on other operating systems.
+    ///
     /// # Examples
     ///
     #[cfg_attr(not(target_vendor="apple"), doc="```")]
@@ -577,16 +1945,62 @@ impl NonblockingUnixSeqpacketConn {
     }
     /// Get information about the process of the peer when the connection was established.
     ///
+    /// The credentials are stored when the connection is established,
+    /// so this never blocks and can be called right after accepting a
+    /// connection, before any packets have been received.
+    ///
     /// See documentation of the returned type for details.
     pub fn initial_peer_credentials(&self) -> Result<ConnCredentials, io::Error> {
         peer_credentials(self.fd)
     }
+    /// Get the supplementary groups of the peer process when the connection
+    /// was established.
+    ///
+    /// See [`UnixSeqpacketConn::initial_peer_groups()`](../struct.UnixSeqpacketConn.html#method.initial_peer_groups)
+    /// for details.
+    pub fn initial_peer_groups(&self) -> Result<Vec<u32>, io::Error> {
+        peer_groups(self.fd)
+    }
+    /// Get the security label of the peer process when the connection was
+    /// established (`SO_PEERSEC`).
+    ///
+    /// See [`UnixSeqpacketConn::peer_security_label()`](../struct.UnixSeqpacketConn.html#method.peer_security_label)
+    /// for details.
+    #[cfg(any(target_os="linux", target_os="android"))]
+    pub fn peer_security_label(&self) -> Result<Vec<u8>, io::Error> {
+        peer_security_label(self.fd)
+    }
+    /// Get a pidfd referring to the peer process when the connection was
+    /// established (`SO_PEERPIDFD`).
+    ///
+    /// See [`UnixSeqpacketConn::peer_pidfd()`](../struct.UnixSeqpacketConn.html#method.peer_pidfd)
+    /// for details.
+    #[cfg(any(target_os="linux", target_os="android"))]
+    pub fn peer_pidfd(&self) -> Result<OwnedFd, io::Error> {
+        peer_pidfd(self.fd)
+    }
+    /// Enable or disable receiving the security label of the sender with
+    /// every packet (`SO_PASSSEC`).
+    ///
+    /// See [`UnixSeqpacketConn::set_receive_security_label()`](../struct.UnixSeqpacketConn.html#method.set_receive_security_label)
+    /// for details.
+    #[cfg(any(target_os="linux", target_os="android"))]
+    pub fn set_receive_security_label(&self,  receive: bool) -> Result<(), io::Error> {
+        set_passsec(self.fd, receive)
+    }
 
     /// Send a packet to the peer.
+    ///
+    /// # Errors
+    ///
+    /// Packets bigger than [`max_send_size()`](#method.max_send_size)
+    /// fail with an error of kind `InvalidInput` instead of the OS error
+    /// for `EMSGSIZE`.
     pub fn send(&self,  packet: &[u8]) -> Result<usize, io::Error> {
         let ptr = packet.as_ptr() as *const c_void;
         let flags = MSG_NOSIGNAL | MSG_EOR;
-        let sent = cvt_r!(unsafe { send(self.fd, ptr, packet.len(), flags) })?;
+        let sent = cvt_r!(unsafe { send(self.fd, ptr, packet.len(), flags) })
+            .map_err(explain_too_big)?;
         Ok(sent as usize)
     }
     /// Receive a packet from the peer.
@@ -598,6 +2012,45 @@ impl NonblockingUnixSeqpacketConn {
         let (bytes, ancillary) = recv_ancillary(self.fd, None, 0, &mut buffers, &mut[])?;
         Ok((bytes, ancillary.message_truncated()))
     }
+    /// Receive a packet into a `Vec` that is resized to fit it.
+    ///
+    /// See [`UnixSeqpacketConn::recv_to_vec()`](../struct.UnixSeqpacketConn.html#method.recv_to_vec)
+    /// for details.
+    pub fn recv_to_vec(&self,  packet: &mut Vec<u8>) -> Result<usize, io::Error> {
+        recv_to_vec(self.fd, packet)
+    }
+    /// Receive a packet from the peer, and tell an empty packet apart from
+    /// end of connection.
+    ///
+    /// [`recv()`](#method.recv) returns `(0, false)` for both, as that's what
+    /// `recv()` and `recvmsg()` return.
+    /// The next packet is peeked at before it's received, and operating
+    /// systems other than Linux & Android are expected to mark packets with
+    /// `MSG_EOR`.
+    /// On Linux, an empty peek is only taken as end of connection if the
+    /// peer has shut down and no packets remain in the queue. To find out
+    /// whether an empty packet remains, `SO_TIMESTAMP` is enabled briefly
+    /// after the peer has shut down, unless receiving credentials or
+    /// timestamps is already enabled.
+    /// Any file descriptors or other ancillary data sent with the packet
+    /// are discarded.
+    ///
+    /// Returns an error of kind `WouldBlock` if no packet is available
+    /// and the peer is still connected.
+    pub fn recv_packet(&self,  buffer: &mut[u8]) -> Result<RecvPacket, io::Error> {
+        recv_packet(self.fd, 0, buffer)
+    }
+    /// Receive a packet from the peer, and get its full length even if it
+    /// didn't fit in the buffer.
+    ///
+    /// See [`UnixSeqpacketConn::recv_full_size()`](../struct.UnixSeqpacketConn.html#method.recv_full_size)
+    /// for details.
+    #[cfg(any(target_os="linux", target_os="android"))]
+    pub fn recv_full_size(&self,  buffer: &mut[u8]) -> Result<usize, io::Error> {
+        let mut buffers = [IoSliceMut::new(buffer)];
+        recv_ancillary(self.fd, None, MSG_TRUNC, &mut buffers, &mut[])
+            .map(|(bytes, _)| bytes )
+    }
     /// Send a packet assembled from multiple byte slices.
     pub fn send_vectored(&self,  slices: &[IoSlice])
     -> Result<usize, io::Error> {
@@ -614,16 +2067,287 @@ impl NonblockingUnixSeqpacketConn {
         recv_ancillary(self.fd, None, 0, buffers, &mut[])
             .map(|(bytes, ancillary)| (bytes, ancillary.message_truncated()) )
     }
+    /// Read a packet into multiple buffers, and get all the information
+    /// `recvmsg()` provides about it.
+    pub fn recv_vectored_info(&self,  buffers: &mut[IoSliceMut])
+    -> Result<RecvInfo, io::Error> {
+        recv_ancillary(self.fd, None, 0, buffers, &mut[])
+            .map(|(bytes, ancillary)| RecvInfo::new(bytes, &ancillary) )
+    }
+    /// Get how big an [`AncillaryBuf`](../struct.AncillaryBuf.html) must be
+    /// to receive all ancillary messages of the next packet,
+    /// without removing the packet from the queue.
+    ///
+    /// See [`UnixSeqpacketConn::peek_ancillary_capacity()`](../struct.UnixSeqpacketConn.html#method.peek_ancillary_capacity)
+    /// for details.
+    pub fn peek_ancillary_capacity(&self) -> Result<usize, io::Error> {
+        peek_ancillary_capacity(self.fd)
+    }
+    /// Read a packet into multiple buffers, and get an iterator over all
+    /// ancillary messages received with it.
+    ///
+    /// See [`UnixSeqpacketConn::recv_vectored_ancillary()`](../struct.UnixSeqpacketConn.html#method.recv_vectored_ancillary)
+    /// for details.
+    pub fn recv_vectored_ancillary<'a>(&self,
+            buffers: &mut[IoSliceMut],  ancillary_buf: &'a mut AncillaryBuf,
+    ) -> Result<(usize, Ancillary<'a>), io::Error> {
+        recv_ancillary(self.fd, None, 0, buffers, ancillary_buf)
+    }
     /// Send a packet with associated file descriptors.
     pub fn send_fds(&self,  bytes: &[u8],  fds: &[RawFd])
     -> Result<usize, io::Error> {
         send_ancillary(self.fd, None, MSG_EOR, &[IoSlice::new(bytes)], fds, None)
     }
+    /// Send a packet with file descriptors from values of any type that
+    /// implements `AsFd`.
+    ///
+    /// See [`UnixSeqpacketConn::send_as_fds()`](../struct.UnixSeqpacketConn.html#method.send_as_fds)
+    /// for details.
+    pub fn send_as_fds<I>(&self,  bytes: &[u8],  fds: I) -> Result<usize, io::Error>
+    where I: IntoIterator, I::Item: AsFd {
+        send_as_fds(self.fd, None, MSG_EOR, &[IoSlice::new(bytes)], fds)
+    }
+    /// Build a packet with any combination of content, file descriptors
+    /// and credentials, and then send it.
+    ///
+    /// See [`SendAncillary`](struct.SendAncillary.html) for an example.
+    pub fn send_ancillary(&self) -> SendAncillary<'_> {
+        SendAncillary::new(self.fd, MSG_EOR)
+    }
+    /// Send a socket together with a tag for its type.
+    ///
+    /// See [`UnixSeqpacketConn::send_socket()`](../struct.UnixSeqpacketConn.html#method.send_socket)
+    /// for details.
+    pub fn send_socket(&self,  socket: &PassedSocket) -> Result<(), io::Error> {
+        send_socket(self.fd, MSG_EOR, socket)
+    }
+    /// Receive a socket sent with [`send_socket()`](#method.send_socket),
+    /// as the correct type.
+    ///
+    /// See [`UnixSeqpacketConn::recv_socket()`](../struct.UnixSeqpacketConn.html#method.recv_socket)
+    /// for details.
+    pub fn recv_socket(&self) -> Result<PassedSocket, io::Error> {
+        recv_socket(self.fd)
+    }
+    /// Send a big payload in a sealed memfd instead of through the socket.
+    ///
+    /// See [`UnixSeqpacketConn::send_memfd()`](../struct.UnixSeqpacketConn.html#method.send_memfd)
+    /// for details.
+    #[cfg(all(feature="memfd", any(target_os="linux", target_os="android")))]
+    pub fn send_memfd(&self,  payload: &[u8]) -> Result<(), io::Error> {
+        send_memfd(self.fd, MSG_EOR, payload)
+    }
+    /// Receive a payload sent with [`send_memfd()`](#method.send_memfd)
+    /// and map it into memory.
+    ///
+    /// See [`UnixSeqpacketConn::recv_memfd()`](../struct.UnixSeqpacketConn.html#method.recv_memfd)
+    /// for details.
+    #[cfg(all(feature="memfd", any(target_os="linux", target_os="android")))]
+    pub fn recv_memfd(&self) -> Result<MemfdPayload, io::Error> {
+        recv_memfd(self.fd)
+    }
+    /// Send a packet with explicit credentials (`SCM_CREDENTIALS`).
+    ///
+    /// See [`UnixSeqpacketConn::send_with_credentials()`](../struct.UnixSeqpacketConn.html#method.send_with_credentials)
+    /// for details.
+    #[cfg(any(target_os="linux", target_os="android"))]
+    pub fn send_with_credentials(&self,  bytes: &[u8],  creds: SendCredentials)
+    -> Result<usize, io::Error> {
+        send_ancillary(self.fd, None, MSG_EOR, &[IoSlice::new(bytes)], &[], Some(creds))
+    }
     /// Receive a packet and associated file descriptors.
     pub fn recv_fds(&self,  byte_buffer: &mut[u8],  fd_buffer: &mut[RawFd])
     -> Result<(usize, bool, usize), io::Error> {
         recv_fds(self.fd, None, &mut[IoSliceMut::new(byte_buffer)], fd_buffer)
     }
+    /// Receive a packet and up to `max_fds` associated file descriptors,
+    /// which are closed when dropped.
+    ///
+    /// See [`UnixSeqpacketConn::recv_owned_fds()`](../struct.UnixSeqpacketConn.html#method.recv_owned_fds)
+    /// for details.
+    pub fn recv_owned_fds(&self,  byte_buffer: &mut[u8],  max_fds: usize)
+    -> Result<(usize, bool, Vec<OwnedFd>), io::Error> {
+        recv_owned_fds(self.fd, None, &mut[IoSliceMut::new(byte_buffer)], max_fds, true)
+    }
+    /// Receive a packet and at most `max_fds` associated file descriptors,
+    /// and fail if the peer sent more than that.
+    ///
+    /// See [`UnixSeqpacketConn::recv_fds_limited()`](../struct.UnixSeqpacketConn.html#method.recv_fds_limited)
+    /// for details.
+    pub fn recv_fds_limited(&self,  byte_buffer: &mut[u8],  max_fds: usize)
+    -> Result<(usize, bool, Vec<OwnedFd>), io::Error> {
+        recv_fds_limited(self.fd, None, &mut[IoSliceMut::new(byte_buffer)], max_fds)
+    }
+    /// Receive a packet and up to `max_fds` associated file descriptors,
+    /// without making them close-on-exec.
+    ///
+    /// This is for passing the received file descriptors on to processes
+    /// started with `exec()`.
+    /// On operating systems that support `MSG_CMSG_CLOEXEC` it is simply
+    /// not passed, while on others the file descriptors are not made
+    /// close-on-exec after receiving them.
+    /// [`recv_owned_fds()`](#method.recv_owned_fds) and all other methods
+    /// make received file descriptors close-on-exec.
+    pub fn recv_inheritable_fds(&self,  byte_buffer: &mut[u8],  max_fds: usize)
+    -> Result<(usize, bool, Vec<OwnedFd>), io::Error> {
+        recv_owned_fds(self.fd, None, &mut[IoSliceMut::new(byte_buffer)], max_fds, false)
+    }
+
+    /// Shut down the read, write, or both halves of this connection.
+    ///
+    /// See [`UnixSeqpacketConn::shutdown()`](../struct.UnixSeqpacketConn.html#method.shutdown)
+    /// for details.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// # use std::net::Shutdown;
+    /// # use std::io::ErrorKind;
+    /// let (a, b) = uds::nonblocking::UnixSeqpacketConn::pair().unwrap();
+    /// assert_eq!(b.recv(&mut[0; 10]).unwrap_err().kind(), ErrorKind::WouldBlock);
+    /// a.shutdown(Shutdown::Write).unwrap();
+    /// assert_eq!(b.recv(&mut[0; 10]).unwrap(), (0, false));
+    /// ```
+    pub fn shutdown(&self,  how: Shutdown) -> Result<(), io::Error> {
+        shutdown(self.fd, how)
+    }
+
+    /// Enable or disable receiving the credentials of the sender with every
+    /// packet (`SO_PASSCRED`).
+    ///
+    /// The kernel attaches the pid, uid and gid of the sending process to
+    /// every packet when this is enabled, even if the sender doesn't send
+    /// them explicitly.
+    #[cfg(any(target_os="linux", target_os="android"))]
+    pub fn set_receive_credentials(&self,  receive: bool) -> Result<(), io::Error> {
+        set_passcred(self.fd, receive)
+    }
+    /// Receive a packet and the credentials of the process that sent it.
+    ///
+    /// See [`UnixSeqpacketConn::recv_with_credentials()`](../struct.UnixSeqpacketConn.html#method.recv_with_credentials)
+    /// for details.
+    #[cfg(any(target_os="linux", target_os="android"))]
+    pub fn recv_with_credentials(&self,  buffer: &mut[u8])
+    -> Result<(usize, bool, Option<ReceivedCredentials>), io::Error> {
+        recv_with_credentials(self.fd, None, buffer)
+    }
+
+    /// Receive a packet together with up to `max_fds` file descriptors and
+    /// any credentials or timestamp attached to it.
+    ///
+    /// See [`UnixSeqpacketConn::recv_full()`](../struct.UnixSeqpacketConn.html#method.recv_full)
+    /// for details.
+    pub fn recv_full(&self,  buffer: &mut[u8],  max_fds: usize)
+    -> Result<ReceivedMessage, io::Error> {
+        recv_full(self.fd, buffer, max_fds)
+    }
+
+    /// Receive a packet, and close the file descriptors sent with it that
+    /// `policy` doesn't accept.
+    ///
+    /// See [`UnixSeqpacketConn::recv_with_fd_policy()`](../struct.UnixSeqpacketConn.html#method.recv_with_fd_policy)
+    /// for details.
+    pub fn recv_with_fd_policy(&self,  buffer: &mut[u8],  policy: FdPolicy)
+    -> Result<ReceivedMessage, io::Error> {
+        recv_with_fd_policy(self.fd, buffer, policy)
+    }
+
+    /// Enable or disable receiving a timestamp of when the kernel received
+    /// every packet.
+    ///
+    /// See [`UnixSeqpacketConn::set_receive_timestamps()`](../struct.UnixSeqpacketConn.html#method.set_receive_timestamps)
+    /// for details.
+    #[cfg(not(any(target_os="illumos", target_os="solaris")))]
+    pub fn set_receive_timestamps(&self,  receive: bool) -> Result<(), io::Error> {
+        set_timestamps(self.fd, receive)
+    }
+    /// Receive a packet and the timestamp of when the kernel received it.
+    ///
+    /// See [`UnixSeqpacketConn::recv_with_timestamp()`](../struct.UnixSeqpacketConn.html#method.recv_with_timestamp)
+    /// for details.
+    #[cfg(not(any(target_os="illumos", target_os="solaris")))]
+    pub fn recv_with_timestamp(&self,  buffer: &mut[u8])
+    -> Result<(usize, bool, Option<SystemTime>), io::Error> {
+        recv_with_timestamp(self.fd, None, buffer)
+    }
+
+    /// Get the size of the biggest packet that can be sent.
+    ///
+    /// This is derived from the size of the send buffer (`SO_SNDBUF`),
+    /// minus what Linux reserves for bookkeeping.
+    /// Sending bigger packets fails with an error of kind `InvalidInput`.
+    pub fn max_send_size(&self) -> Result<usize, io::Error> {
+        max_send_size(self.fd)
+    }
+
+    /// Get the number of bytes that can be received without blocking.
+    ///
+    /// This is the total size of all queued packets on Linux, but might be
+    /// only the size of the next packet on other operating systems.
+    pub fn bytes_available(&self) -> Result<usize, io::Error> {
+        bytes_available(self.fd)
+    }
+
+    /// Get and clear the pending socket error (`SO_ERROR`), if any.
+    ///
+    /// When a nonblocking connect doesn't complete immediately, this is where
+    /// the outcome is reported once the socket becomes writable.
+    pub fn take_error(&self) -> Result<Option<io::Error>, io::Error> {
+        take_error(self.fd)
+    }
+
+    /// Wait until a packet can be received or the peer has disconnected,
+    /// without an event loop.
+    ///
+    /// Waits indefinitely if `timeout` is `None`,
+    /// and fails with an error of kind `TimedOut` if it expires.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// use std::io::ErrorKind;
+    /// use std::time::Duration;
+    /// use uds::nonblocking::UnixSeqpacketConn;
+    ///
+    /// let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    /// let timeout = Some(Duration::from_millis(10));
+    /// assert_eq!(b.wait_readable(timeout).unwrap_err().kind(), ErrorKind::TimedOut);
+    /// a.send(b"ready").unwrap();
+    /// b.wait_readable(timeout).unwrap();
+    /// assert_eq!(b.recv(&mut[0; 10]).unwrap(), (5, false));
+    /// ```
+    pub fn wait_readable(&self,  timeout: Option<Duration>) -> Result<(), io::Error> {
+        wait_for(self.fd, POLLIN, timeout)
+    }
+
+    /// Wait until a packet can be sent, or the connection has failed,
+    /// without an event loop.
+    ///
+    /// Waits indefinitely if `timeout` is `None`,
+    /// and fails with an error of kind `TimedOut` if it expires.
+    pub fn wait_writable(&self,  timeout: Option<Duration>) -> Result<(), io::Error> {
+        wait_for(self.fd, POLLOUT, timeout)
+    }
+
+    /// Check whether the peer is still connected, without receiving anything
+    /// or waiting.
+    ///
+    /// This returns `false` once the peer has closed the connection
+    /// or an error is pending, even if there are still packets to receive.
+    /// A peer that only shut down its write half is still considered alive.
+    pub fn is_peer_alive(&self) -> Result<bool, io::Error> {
+        Ok(poll_now(self.fd, 0)? & (POLLHUP | POLLERR) == 0)
+    }
+
+    /// Check whether the file descriptor is in nonblocking mode.
+    ///
+    /// This is useful for verifying sockets created with `from_raw_fd()`,
+    /// as the mode is not checked or changed then.
+    pub fn is_nonblocking(&self) -> Result<bool, io::Error> {
+        is_nonblocking(self.fd)
+    }
 
 
     /// Create a new file descriptor also pointing to this side of this connection.
@@ -695,16 +2419,20 @@ pub struct NonblockingUnixSeqpacketListener {
 }
 
 impl_rawfd_traits!{NonblockingUnixSeqpacketListener}
+impl_socket2_if_enabled!{NonblockingUnixSeqpacketListener, nonblocking=true}
 impl_mio_if_enabled!{NonblockingUnixSeqpacketListener}
+impl_async_io_if_enabled!{NonblockingUnixSeqpacketListener}
 
 impl NonblockingUnixSeqpacketListener {
-    /// Connect to an unix seqpacket server listening at `path`.
+    /// Create a socket bound to `addr` and start listening on it.
     ///
-    /// This is a wrapper around [`connect_unix_addr()`](#method.connect_unix_addr)
-    /// for convenience and compatibility with std.
-    pub fn bind<P: AsRef<Path>>(path: P) -> Result<Self, io::Error> {
-        let addr = UnixSocketAddr::from_path(&path)?;
-        Self::bind_unix_addr(&addr)
+    /// This is identical to [`bind_unix_addr()`](#method.bind_unix_addr),
+    /// and exists for convenience and compatibility with std.
+    /// Note that strings are parsed with
+    /// [`UnixSocketAddr::new()`](struct.UnixSocketAddr.html#method.new),
+    /// so `"@name"` is an abstract address. Pass a `Path` to avoid that.
+    pub fn bind<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error> {
+        Self::bind_unix_addr(addr)
     }
     /// `accept_unix_addr()` doesn't block if no connections are waiting:
     ///
@@ -719,18 +2447,58 @@ impl NonblockingUnixSeqpacketListener {
     /// assert_eq!(listener.accept_unix_addr().unwrap_err().kind(), ErrorKind::WouldBlock);
     /// std::fs::remove_file("nonblocking_seqpacket_listener.socket").unwrap();
     /// ```
-    pub fn bind_unix_addr(addr: &UnixSocketAddr) -> Result<Self, io::Error> {
+    pub fn bind_unix_addr<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error> {
+        let socket = Socket::new(SOCK_SEQPACKET, true)?;
+        bind_to(socket.as_raw_fd(), &addr.to_unix_addr()?)?;
+        socket.start_listening()?;
+        Ok(NonblockingUnixSeqpacketListener { fd: socket.into_raw_fd() })
+    }
+    /// Create a socket bound to `addr` and start listening on it,
+    /// replacing the socket file left behind by a server that is no longer
+    /// running.
+    ///
+    /// See [`UnixSeqpacketListener::bind_unix_addr_taking_over()`](../struct.UnixSeqpacketListener.html#method.bind_unix_addr_taking_over)
+    /// for details.
+    pub fn bind_unix_addr_taking_over<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error> {
         let socket = Socket::new(SOCK_SEQPACKET, true)?;
-        bind_to(socket.as_raw_fd(), addr)?;
+        bind_to_taking_over(socket.as_raw_fd(), &addr.to_unix_addr()?, SOCK_SEQPACKET)?;
         socket.start_listening()?;
         Ok(NonblockingUnixSeqpacketListener { fd: socket.into_raw_fd() })
     }
+    /// Create a socket bound to the path `path` and start listening on it,
+    /// and remove the socket file when the returned listener is dropped.
+    ///
+    /// An existing file at `path` is not removed first, as it might belong
+    /// to another running server.
+    pub fn bind_with_cleanup<P: AsRef<Path>>(path: P) -> Result<RemoveOnDrop<Self>, io::Error> {
+        let path = path.as_ref();
+        let listener = Self::bind_unix_addr(UnixSocketAddr::from_path(path)?)?;
+        Ok(RemoveOnDrop { listener, path: path.to_path_buf() })
+    }
 
     /// Get the address this listener was bound to.
     pub fn local_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
         local_addr(self.fd)
     }
 
+    /// Check whether the file descriptor is in nonblocking mode.
+    ///
+    /// This is useful for verifying sockets created with `from_raw_fd()`,
+    /// as the mode is not checked or changed then.
+    pub fn is_nonblocking(&self) -> Result<bool, io::Error> {
+        is_nonblocking(self.fd)
+    }
+
+    /// Accept a non-blocking connection, non-blockingly,
+    /// without getting the address of the peer.
+    ///
+    /// Use [`accept_unix_addr()`](#method.accept_unix_addr) to get the
+    /// address too.
+    pub fn accept(&self) -> Result<NonblockingUnixSeqpacketConn, io::Error> {
+        let socket = Socket::accept_without_addr(self.fd, true)?;
+        Ok(NonblockingUnixSeqpacketConn { fd: socket.into_raw_fd() })
+    }
+
     /// Accept a non-blocking connection, non-blockingly.
     pub fn accept_unix_addr(&self)
     -> Result<(NonblockingUnixSeqpacketConn, UnixSocketAddr), io::Error> {
@@ -739,6 +2507,43 @@ impl NonblockingUnixSeqpacketListener {
         Ok((conn, addr))
     }
 
+    /// Accept a connection and get the address and credentials of the peer.
+    ///
+    /// The credentials are those of the process when it connected,
+    /// as returned by
+    /// [`initial_peer_credentials()`](struct.UnixSeqpacketConn.html#method.initial_peer_credentials),
+    /// which makes it possible to decide whether to serve the peer before
+    /// the connection is handed to anything else.
+    ///
+    /// The connection is closed if getting the credentials fails.
+    pub fn accept_with_credentials(&self)
+    -> Result<(NonblockingUnixSeqpacketConn, UnixSocketAddr, ConnCredentials), io::Error> {
+        let (conn, addr) = self.accept_unix_addr()?;
+        let credentials = conn.initial_peer_credentials()?;
+        Ok((conn, addr, credentials))
+    }
+
+    /// Accept a connection without setting close-on-exec on it,
+    /// so that it's inherited by processes started with `exec()`.
+    ///
+    /// Close-on-exec is left unset atomically where `accept4()` is supported,
+    /// instead of being cleared after `accept()`.
+    /// All other file descriptors created by this crate are close-on-exec.
+    pub fn accept_inheritable(&self)
+    -> Result<(NonblockingUnixSeqpacketConn, UnixSocketAddr), io::Error> {
+        let (socket, addr) = Socket::accept_inheritable(self.fd, true)?;
+        let conn = NonblockingUnixSeqpacketConn { fd: socket.into_raw_fd() };
+        Ok((conn, addr))
+    }
+
+    /// Wait until a connection can be accepted, without an event loop.
+    ///
+    /// Waits indefinitely if `timeout` is `None`,
+    /// and fails with an error of kind `TimedOut` if it expires.
+    pub fn wait_readable(&self,  timeout: Option<Duration>) -> Result<(), io::Error> {
+        wait_for(self.fd, POLLIN, timeout)
+    }
+
     /// Create a new file descriptor listening for the same connections.
     pub fn try_clone(&self) -> Result<Self, io::Error> {
         let cloned = Socket::try_clone_from(self.fd)?;
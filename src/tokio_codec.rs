@@ -0,0 +1,147 @@
+use std::io::{self, ErrorKind};
+use std::net::Shutdown;
+use std::os::unix::io::AsRawFd;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+use bytes::BytesMut;
+use futures_core::Stream;
+use futures_sink::Sink;
+use tokio::io::unix::AsyncFd;
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::seqpacket::{NonblockingUnixSeqpacketConn, RecvPacket, recv_packet_to_vec};
+
+/// Run a tokio-util codec over a nonblocking seqpacket connection,
+/// with each encoded frame sent as one packet.
+///
+/// Unlike tokio-util's `Framed`, the codec doesn't need to find frame
+/// boundaries, as each received packet is decoded on its own with
+/// `decode_eof()`. Receiving a packet that decodes to more or less than one
+/// frame is an error of kind `InvalidData`, except that empty packets which
+/// decode to nothing are skipped.
+///
+/// The stream ends when the peer closes the connection or shuts down its
+/// write half, and closing the sink shuts down the write half of this side.
+///
+/// Requires the `tokio-util` feature, and must be created inside a tokio
+/// runtime.
+///
+/// # Examples
+///
+#[cfg_attr(not(target_vendor="apple"), doc="```")]
+#[cfg_attr(target_vendor="apple", doc="```no_run")]
+/// use futures_util::{SinkExt, StreamExt};
+/// use tokio_util::codec::LinesCodec;
+/// use uds::nonblocking::UnixSeqpacketConn;
+/// use uds::TokioSeqpacketFramed;
+///
+/// # let runtime = tokio::runtime::Builder::new_current_thread().enable_io().build().unwrap();
+/// # runtime.block_on(async {
+/// let (a, b) = UnixSeqpacketConn::pair().unwrap();
+/// let mut a = TokioSeqpacketFramed::new(a, LinesCodec::new()).unwrap();
+/// let mut b = TokioSeqpacketFramed::new(b, LinesCodec::new()).unwrap();
+/// a.send("hello").await.unwrap();
+/// // the packet contains the newline
+/// assert_eq!(b.next().await.unwrap().unwrap(), "hello");
+/// # });
+/// ```
+#[derive(Debug)]
+pub struct TokioSeqpacketFramed<C> {
+    conn: AsyncFd<NonblockingUnixSeqpacketConn>,
+    codec: C,
+    /// An encoded frame which hasn't been sent yet.
+    unsent: Option<BytesMut>,
+}
+
+impl<C> TokioSeqpacketFramed<C> {
+    /// Register the connection with tokio, and use `codec` for the packets.
+    pub fn new(conn: NonblockingUnixSeqpacketConn,  codec: C) -> Result<Self, io::Error> {
+        Ok(TokioSeqpacketFramed { conn: AsyncFd::new(conn)?, codec, unsent: None })
+    }
+
+    /// Get the wrapped connection.
+    pub fn get_ref(&self) -> &NonblockingUnixSeqpacketConn {
+        self.conn.get_ref()
+    }
+    /// Get the codec.
+    pub fn codec(&self) -> &C {
+        &self.codec
+    }
+    /// Get the codec mutably, to change its settings.
+    pub fn codec_mut(&mut self) -> &mut C {
+        &mut self.codec
+    }
+
+    /// Send the encoded frame, if any.
+    fn poll_send_unsent(&mut self,  cx: &mut Context<'_>) -> Poll<Result<(), io::Error>> {
+        while let Some(packet) = self.unsent.as_ref() {
+            let mut guard = ready!(self.conn.poll_write_ready(cx))?;
+            match guard.try_io(|conn| conn.get_ref().send(packet) ) {
+                Ok(Ok(_)) => self.unsent = None,
+                Ok(Err(e)) => return Poll::Ready(Err(e)),
+                Err(_would_block) => {}
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<C: Decoder + Unpin> Stream for TokioSeqpacketFramed<C> {
+    type Item = Result<C::Item, C::Error>;
+    fn poll_next(self: Pin<&mut Self>,  cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let mut packet = Vec::new();
+        loop {
+            let mut guard = ready!(this.conn.poll_read_ready(cx))?;
+            match guard.try_io(|conn| recv_packet_to_vec(conn.as_raw_fd(), &mut packet) ) {
+                Ok(Ok(RecvPacket::EndOfConnection)) => return Poll::Ready(None),
+                Ok(Ok(RecvPacket::Packet { .. })) => {}
+                Ok(Err(e)) => return Poll::Ready(Some(Err(e.into()))),
+                Err(_would_block) => continue,
+            }
+            let mut buf = BytesMut::from(&packet[..]);
+            match this.codec.decode_eof(&mut buf) {
+                Ok(Some(_)) if !buf.is_empty() => {
+                    let message = "packet contains more than one frame";
+                    let error = io::Error::new(ErrorKind::InvalidData, message);
+                    return Poll::Ready(Some(Err(error.into())));
+                }
+                Ok(Some(frame)) => return Poll::Ready(Some(Ok(frame))),
+                Ok(None) if packet.is_empty() => {}
+                Ok(None) => {
+                    let message = "packet doesn't contain a complete frame";
+                    let error = io::Error::new(ErrorKind::InvalidData, message);
+                    return Poll::Ready(Some(Err(error.into())));
+                }
+                Err(e) => return Poll::Ready(Some(Err(e))),
+            }
+        }
+    }
+}
+
+impl<I, C: Encoder<I> + Unpin> Sink<I> for TokioSeqpacketFramed<C> {
+    type Error = C::Error;
+    fn poll_ready(self: Pin<&mut Self>,  cx: &mut Context<'_>) -> Poll<Result<(), C::Error>> {
+        self.get_mut().poll_send_unsent(cx).map_err(Into::into)
+    }
+    fn start_send(self: Pin<&mut Self>,  item: I) -> Result<(), C::Error> {
+        let this = self.get_mut();
+        if this.unsent.is_some() {
+            let message = "start_send() called without poll_ready() returning Ready";
+            return Err(io::Error::new(ErrorKind::InvalidInput, message).into());
+        }
+        let mut packet = BytesMut::new();
+        this.codec.encode(item, &mut packet)?;
+        this.unsent = Some(packet);
+        Ok(())
+    }
+    fn poll_flush(self: Pin<&mut Self>,  cx: &mut Context<'_>) -> Poll<Result<(), C::Error>> {
+        self.get_mut().poll_send_unsent(cx).map_err(Into::into)
+    }
+    fn poll_close(self: Pin<&mut Self>,  cx: &mut Context<'_>) -> Poll<Result<(), C::Error>> {
+        let this = self.get_mut();
+        ready!(this.poll_send_unsent(cx))?;
+        Poll::Ready(this.get_ref().shutdown(Shutdown::Write).map_err(Into::into))
+    }
+}
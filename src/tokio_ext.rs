@@ -0,0 +1,340 @@
+use std::future::Future;
+use std::io::{self, ErrorKind, IoSlice, IoSliceMut};
+use std::net::Shutdown;
+use std::os::unix::io::{RawFd, AsRawFd, FromRawFd, IntoRawFd, OwnedFd};
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+use tokio::io::{AsyncRead, AsyncWrite, Interest, ReadBuf};
+use tokio::io::unix::AsyncFd;
+use tokio::net::{UnixStream, UnixDatagram};
+
+use libc::SOCK_DGRAM;
+
+use crate::addr::{UnixSocketAddr, ToUnixSocketAddr};
+use crate::helpers::*;
+use crate::ancillary::{send_ancillary, recv_fds, recv_owned_fds};
+use crate::credentials::{ConnCredentials, peer_credentials};
+use crate::seqpacket::{NonblockingUnixSeqpacketConn, RecvPacket, recv_packet_to_vec};
+
+/// The future returned by the async methods of
+/// [`TokioUnixStreamExt`](trait.TokioUnixStreamExt.html) and
+/// [`TokioUnixDatagramExt`](trait.TokioUnixDatagramExt.html).
+///
+/// (Async functions in traits require a newer Rust than what this crate supports.)
+pub type IoFuture<'a, T> = Pin<Box<dyn Future<Output=Result<T, io::Error>> + Send + 'a>>;
+
+/// Extension trait for `tokio::net::UnixStream`.
+///
+/// Requires the `tokio` feature.
+///
+/// # Examples
+///
+/// ```
+/// use std::os::unix::io::AsRawFd;
+/// use tokio::net::UnixStream;
+/// use uds::TokioUnixStreamExt;
+///
+/// # let runtime = tokio::runtime::Builder::new_current_thread().enable_io().build().unwrap();
+/// # runtime.block_on(async {
+/// let (a, b) = UnixStream::pair().unwrap();
+/// a.send_fds(b"fd", &[a.as_raw_fd()]).await.unwrap();
+/// let (len, fds) = b.recv_owned_fds(&mut[0; 10], 2).await.unwrap();
+/// assert_eq!((len, fds.len()), (2, 1));
+/// # });
+/// ```
+pub trait TokioUnixStreamExt: AsRawFd {
+    /// Get the address of this side of the connection.
+    fn local_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+        local_addr(self.as_raw_fd())
+    }
+    /// Get the address of the other side of the connection.
+    fn peer_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+        peer_addr(self.as_raw_fd())
+    }
+    /// Get the credentials of the peer process when the connection was
+    /// established.
+    fn initial_peer_credentials(&self) -> Result<ConnCredentials, io::Error> {
+        peer_credentials(self.as_raw_fd())
+    }
+
+    /// Send bytes and file descriptors, waiting for the socket to become
+    /// writable if necessary.
+    fn send_fds<'a>(&'a self,  bytes: &'a[u8],  fds: &'a[RawFd]) -> IoFuture<'a, usize>;
+    /// Receive bytes and file descriptors, waiting for the socket to become
+    /// readable if necessary.
+    ///
+    /// Returns the number of bytes and file descriptors received.
+    fn recv_fds<'a>(&'a self,  buf: &'a mut[u8],  fd_buf: &'a mut[RawFd])
+    -> IoFuture<'a, (usize, usize)>;
+    /// Receive bytes and up to `max_fds` file descriptors,
+    /// which are closed when dropped.
+    fn recv_owned_fds<'a>(&'a self,  buf: &'a mut[u8],  max_fds: usize)
+    -> IoFuture<'a, (usize, Vec<OwnedFd>)>;
+}
+
+impl TokioUnixStreamExt for UnixStream {
+    fn send_fds<'a>(&'a self,  bytes: &'a[u8],  fds: &'a[RawFd]) -> IoFuture<'a, usize> {
+        Box::pin(self.async_io(Interest::WRITABLE, move || {
+            send_ancillary(self.as_raw_fd(), None, 0, &[IoSlice::new(bytes)], fds, None)
+        }))
+    }
+    fn recv_fds<'a>(&'a self,  buf: &'a mut[u8],  fd_buf: &'a mut[RawFd])
+    -> IoFuture<'a, (usize, usize)> {
+        Box::pin(self.async_io(Interest::READABLE, move || {
+            recv_fds(self.as_raw_fd(), None, &mut[IoSliceMut::new(buf)], fd_buf)
+                .map(|(bytes, _, fds)| (bytes, fds) )
+        }))
+    }
+    fn recv_owned_fds<'a>(&'a self,  buf: &'a mut[u8],  max_fds: usize)
+    -> IoFuture<'a, (usize, Vec<OwnedFd>)> {
+        Box::pin(self.async_io(Interest::READABLE, move || {
+            recv_owned_fds(self.as_raw_fd(), None, &mut[IoSliceMut::new(buf)], max_fds, true)
+                .map(|(bytes, _, fds)| (bytes, fds) )
+        }))
+    }
+}
+
+
+/// Extension trait for `tokio::net::UnixDatagram`.
+///
+/// Requires the `tokio` feature.
+///
+/// # Examples
+///
+#[cfg_attr(any(target_os="linux", target_os="android"), doc="```")]
+#[cfg_attr(not(any(target_os="linux", target_os="android")), doc="```no_run")]
+/// use std::os::unix::io::AsRawFd;
+/// use tokio::net::UnixDatagram;
+/// use uds::TokioUnixDatagramExt;
+///
+/// # let runtime = tokio::runtime::Builder::new_current_thread().enable_io().build().unwrap();
+/// # runtime.block_on(async {
+/// let server = UnixDatagram::bind_unix_addr("@uds tokio datagram example").unwrap();
+/// let client = UnixDatagram::bind_unix_addr("@uds tokio datagram client").unwrap();
+/// client.send_fds_to(b"fd", &[client.as_raw_fd()], "@uds tokio datagram example")
+///     .await.unwrap();
+/// let mut fd_buf = [-1; 2];
+/// let (len, fds, from) = server.recv_fds_from(&mut[0; 10], &mut fd_buf).await.unwrap();
+/// assert_eq!((len, fds), (2, 1));
+/// assert_eq!(from, client.local_unix_addr().unwrap());
+/// # unsafe { libc::close(fd_buf[0]) };
+/// # });
+/// ```
+pub trait TokioUnixDatagramExt: AsRawFd + Sync + Sized {
+    /// Create a socket bound to a `UnixSocketAddr`, which can be abstract.
+    ///
+    /// Must be called inside a tokio runtime.
+    fn bind_unix_addr<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error>;
+    /// Connect the socket to a `UnixSocketAddr`, which can be abstract.
+    fn connect_to_unix_addr<A: ToUnixSocketAddr>(&self,  addr: A) -> Result<(), io::Error> {
+        connect_to(self.as_raw_fd(), &addr.to_unix_addr()?)
+    }
+
+    /// Get the address this socket is bound to.
+    fn local_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+        local_addr(self.as_raw_fd())
+    }
+    /// Get the address this socket is connected to.
+    fn peer_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+        peer_addr(self.as_raw_fd())
+    }
+
+    /// Send a datagram to an address, waiting for room in the send buffer
+    /// if necessary.
+    fn send_to_unix_addr<'a,  A: ToUnixSocketAddr>(&'a self,  datagram: &'a[u8],  addr: A)
+    -> IoFuture<'a, usize> {
+        self.send_fds_to(datagram, &[], addr)
+    }
+    /// Send a datagram with file descriptors to an address, waiting for room
+    /// in the send buffer if necessary.
+    fn send_fds_to<'a,  A: ToUnixSocketAddr>
+    (&'a self,  datagram: &'a[u8],  fds: &'a[RawFd],  addr: A)
+    -> IoFuture<'a, usize>;
+    /// Receive a datagram and the address it was sent from,
+    /// waiting for one if necessary.
+    fn recv_from_unix_addr<'a>(&'a self,  buf: &'a mut[u8])
+    -> IoFuture<'a, (usize, UnixSocketAddr)> {
+        Box::pin(async move {
+            let (len, _, addr) = self.recv_fds_from(buf, &mut[]).await?;
+            Ok((len, addr))
+        })
+    }
+    /// Receive a datagram with file descriptors, and the address it was sent
+    /// from, waiting for one if necessary.
+    ///
+    /// Returns the number of bytes and file descriptors received,
+    /// and the address.
+    fn recv_fds_from<'a>(&'a self,  buf: &'a mut[u8],  fd_buf: &'a mut[RawFd])
+    -> IoFuture<'a, (usize, usize, UnixSocketAddr)>;
+}
+
+impl TokioUnixDatagramExt for UnixDatagram {
+    fn bind_unix_addr<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error> {
+        let socket = Socket::new(SOCK_DGRAM, true)?;
+        bind_to(socket.as_raw_fd(), &addr.to_unix_addr()?)?;
+        let socket = unsafe { std::os::unix::net::UnixDatagram::from_raw_fd(socket.into_raw_fd()) };
+        UnixDatagram::from_std(socket)
+    }
+
+    fn send_fds_to<'a,  A: ToUnixSocketAddr>
+    (&'a self,  datagram: &'a[u8],  fds: &'a[RawFd],  addr: A)
+    -> IoFuture<'a, usize> {
+        let addr = addr.to_unix_addr();
+        Box::pin(async move {
+            let addr = addr?;
+            self.async_io(Interest::WRITABLE, || {
+                send_ancillary(self.as_raw_fd(), Some(&addr), 0, &[IoSlice::new(datagram)], fds, None)
+            }).await
+        })
+    }
+    fn recv_fds_from<'a>(&'a self,  buf: &'a mut[u8],  fd_buf: &'a mut[RawFd])
+    -> IoFuture<'a, (usize, usize, UnixSocketAddr)> {
+        Box::pin(self.async_io(Interest::READABLE, move || {
+            let mut addr = UnixSocketAddr::default();
+            recv_fds(self.as_raw_fd(), Some(&mut addr), &mut[IoSliceMut::new(buf)], fd_buf)
+                .map(|(bytes, _, fds)| (bytes, fds, addr) )
+        }))
+    }
+}
+
+/// An adaptor that implements tokio's `AsyncRead` and `AsyncWrite` over a
+/// nonblocking seqpacket connection, for running stream-oriented codecs and
+/// protocol libraries over a seqpacket transport.
+///
+/// This is the async counterpart of
+/// [`SeqpacketByteStream`](struct.SeqpacketByteStream.html):
+/// Written bytes are buffered, and sent as one packet (ending a record)
+/// when the adaptor is flushed or the buffer reaches the maximum packet size.
+/// Received packets are buffered until they have been completely read,
+/// so packet boundaries are not visible to readers.
+/// Shutting down flushes and then shuts down the write half of the connection.
+///
+/// Requires the `tokio` feature, and must be created inside a tokio runtime.
+///
+/// # Examples
+///
+#[cfg_attr(not(target_vendor="apple"), doc="```")]
+#[cfg_attr(target_vendor="apple", doc="```no_run")]
+/// use tokio::io::{AsyncReadExt, AsyncWriteExt};
+/// use uds::nonblocking::UnixSeqpacketConn;
+/// use uds::TokioSeqpacketByteStream;
+///
+/// # let runtime = tokio::runtime::Builder::new_current_thread().enable_io().build().unwrap();
+/// # runtime.block_on(async {
+/// let (a, b) = UnixSeqpacketConn::pair().unwrap();
+/// let mut writer = TokioSeqpacketByteStream::new(a).unwrap();
+/// writer.write_all(b"one record").await.unwrap();
+/// writer.shutdown().await.unwrap();
+///
+/// let mut text = String::new();
+/// TokioSeqpacketByteStream::new(b).unwrap().read_to_string(&mut text).await.unwrap();
+/// assert_eq!(text, "one record");
+/// # });
+/// ```
+#[derive(Debug)]
+pub struct TokioSeqpacketByteStream {
+    conn: AsyncFd<NonblockingUnixSeqpacketConn>,
+    max_packet_size: usize,
+    read_buf: Vec<u8>,
+    read_pos: usize,
+    write_buf: Vec<u8>,
+}
+
+impl TokioSeqpacketByteStream {
+    /// Wrap a connection, and send packets of up to
+    /// [`max_send_size()`](nonblocking/struct.UnixSeqpacketConn.html#method.max_send_size).
+    pub fn new(conn: NonblockingUnixSeqpacketConn) -> Result<Self, io::Error> {
+        let max_packet_size = conn.max_send_size()?;
+        Self::with_max_packet_size(conn, max_packet_size)
+    }
+
+    /// Wrap a connection, and send packets of up to `max_packet_size` bytes.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `max_packet_size` is zero, or if registering the connection
+    /// with the tokio runtime fails.
+    pub fn with_max_packet_size(conn: NonblockingUnixSeqpacketConn,  max_packet_size: usize)
+    -> Result<Self, io::Error> {
+        if max_packet_size == 0 {
+            return Err(io::Error::new(ErrorKind::InvalidInput, "max packet size cannot be zero"));
+        }
+        Ok(TokioSeqpacketByteStream {
+            conn: AsyncFd::new(conn)?,
+            max_packet_size,
+            read_buf: Vec::new(),
+            read_pos: 0,
+            write_buf: Vec::new(),
+        })
+    }
+
+    /// Get the wrapped connection.
+    pub fn get_ref(&self) -> &NonblockingUnixSeqpacketConn {
+        self.conn.get_ref()
+    }
+}
+
+impl AsyncRead for TokioSeqpacketByteStream {
+    fn poll_read(self: Pin<&mut Self>,  cx: &mut Context<'_>,  buf: &mut ReadBuf<'_>)
+    -> Poll<Result<(), io::Error>> {
+        let this = self.get_mut();
+        while this.read_pos == this.read_buf.len() {
+            let mut guard = ready!(this.conn.poll_read_ready(cx))?;
+            let read_buf = &mut this.read_buf;
+            match guard.try_io(|conn| recv_packet_to_vec(conn.as_raw_fd(), read_buf) ) {
+                Ok(Ok(RecvPacket::EndOfConnection)) => return Poll::Ready(Ok(())),
+                // skip empty packets, which would otherwise look like end of file
+                Ok(Ok(RecvPacket::Packet { .. })) => this.read_pos = 0,
+                Ok(Err(e)) => return Poll::Ready(Err(e)),
+                Err(_would_block) => {}
+            }
+        }
+        let unread = &this.read_buf[this.read_pos..];
+        let len = unread.len().min(buf.remaining());
+        buf.put_slice(&unread[..len]);
+        this.read_pos += len;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncWrite for TokioSeqpacketByteStream {
+    fn poll_write(mut self: Pin<&mut Self>,  cx: &mut Context<'_>,  buf: &[u8])
+    -> Poll<Result<usize, io::Error>> {
+        if self.write_buf.len() == self.max_packet_size {
+            ready!(self.as_mut().poll_flush(cx))?;
+        }
+        let len = buf.len().min(self.max_packet_size - self.write_buf.len());
+        self.write_buf.extend_from_slice(&buf[..len]);
+        Poll::Ready(Ok(len))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>,  cx: &mut Context<'_>) -> Poll<Result<(), io::Error>> {
+        let this = self.get_mut();
+        while !this.write_buf.is_empty() {
+            let mut guard = ready!(this.conn.poll_write_ready(cx))?;
+            let packet = &this.write_buf;
+            match guard.try_io(|conn| conn.get_ref().send(packet) ) {
+                Ok(Ok(_)) => this.write_buf.clear(),
+                Ok(Err(e)) => return Poll::Ready(Err(e)),
+                Err(_would_block) => {}
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>,  cx: &mut Context<'_>)
+    -> Poll<Result<(), io::Error>> {
+        ready!(self.as_mut().poll_flush(cx))?;
+        Poll::Ready(self.get_ref().shutdown(Shutdown::Write))
+    }
+}
+
+impl Drop for TokioSeqpacketByteStream {
+    // try to send any buffered bytes without waiting, ignoring errors
+    fn drop(&mut self) {
+        if !self.write_buf.is_empty() {
+            let _ = self.get_ref().send(&self.write_buf);
+        }
+    }
+}
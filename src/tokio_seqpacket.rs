@@ -0,0 +1,311 @@
+use std::future::poll_fn;
+use std::io;
+use std::net::Shutdown;
+use std::os::unix::io::{RawFd, AsRawFd, FromRawFd, IntoRawFd};
+use std::sync::Arc;
+use std::task::{Context, Poll, ready};
+
+use tokio::io::unix::AsyncFd;
+
+use crate::helpers::set_nonblocking;
+use crate::seqpacket::{UnixSeqpacketConn, NonblockingUnixSeqpacketConn};
+
+/// Poll for the connection to become writable and run `op` until it doesn't
+/// fail with `WouldBlock`.
+fn poll_write_with<R>(conn: &AsyncFd<NonblockingUnixSeqpacketConn>,  cx: &mut Context<'_>,
+        mut op: impl FnMut(&NonblockingUnixSeqpacketConn) -> Result<R, io::Error>,
+) -> Poll<Result<R, io::Error>> {
+    loop {
+        let mut guard = ready!(conn.poll_write_ready(cx))?;
+        if let Ok(result) = guard.try_io(|conn| op(conn.get_ref()) ) {
+            return Poll::Ready(result);
+        }
+    }
+}
+
+/// Poll for the connection to become readable and run `op` until it doesn't
+/// fail with `WouldBlock`.
+fn poll_read_with<R>(conn: &AsyncFd<NonblockingUnixSeqpacketConn>,  cx: &mut Context<'_>,
+        mut op: impl FnMut(&NonblockingUnixSeqpacketConn) -> Result<R, io::Error>,
+) -> Poll<Result<R, io::Error>> {
+    loop {
+        let mut guard = ready!(conn.poll_read_ready(cx))?;
+        if let Ok(result) = guard.try_io(|conn| op(conn.get_ref()) ) {
+            return Poll::Ready(result);
+        }
+    }
+}
+
+/// A seqpacket connection registered with tokio, with async methods for
+/// sending and receiving packets.
+///
+/// Requires the `tokio` feature, and must be created inside a tokio runtime.
+///
+/// # Examples
+///
+#[cfg_attr(not(target_vendor="apple"), doc="```")]
+#[cfg_attr(target_vendor="apple", doc="```no_run")]
+/// use uds::TokioSeqpacketConn;
+///
+/// # let runtime = tokio::runtime::Builder::new_current_thread().enable_io().build().unwrap();
+/// # runtime.block_on(async {
+/// let (a, b) = TokioSeqpacketConn::pair().unwrap();
+/// a.send(b"hello").await.unwrap();
+/// let mut buf = [0; 10];
+/// assert_eq!(b.recv(&mut buf).await.unwrap(), (5, false));
+/// # });
+/// ```
+#[derive(Debug)]
+pub struct TokioSeqpacketConn {
+    conn: AsyncFd<NonblockingUnixSeqpacketConn>,
+}
+
+impl TokioSeqpacketConn {
+    /// Register a nonblocking connection with tokio.
+    pub fn new(conn: NonblockingUnixSeqpacketConn) -> Result<Self, io::Error> {
+        Ok(TokioSeqpacketConn { conn: AsyncFd::new(conn)? })
+    }
+    /// Put a blocking connection into nonblocking mode and register it with tokio.
+    ///
+    /// This is for sockets created or configured synchronously,
+    /// such as ones inherited from systemd.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// use uds::{UnixSeqpacketConn, TokioSeqpacketConn};
+    ///
+    /// # let runtime = tokio::runtime::Builder::new_current_thread().enable_io().build().unwrap();
+    /// # runtime.block_on(async {
+    /// let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    /// let a = TokioSeqpacketConn::from_std(a).unwrap();
+    /// a.send(b"async").await.unwrap();
+    /// assert_eq!(b.recv(&mut[0; 10]).unwrap(), (5, false));
+    /// let a = a.into_std().unwrap();
+    /// assert!(!a.is_nonblocking().unwrap());
+    /// # });
+    /// ```
+    pub fn from_std(conn: UnixSeqpacketConn) -> Result<Self, io::Error> {
+        conn.set_nonblocking(true)?;
+        Self::new(unsafe { NonblockingUnixSeqpacketConn::from_raw_fd(conn.into_raw_fd()) })
+    }
+    /// Deregister the connection from tokio and put it back into blocking mode.
+    pub fn into_std(self) -> Result<UnixSeqpacketConn, io::Error> {
+        let conn = self.into_inner();
+        set_nonblocking(conn.as_raw_fd(), false)?;
+        Ok(unsafe { UnixSeqpacketConn::from_raw_fd(conn.into_raw_fd()) })
+    }
+    /// Create a pair of connected sockets registered with tokio.
+    pub fn pair() -> Result<(Self, Self), io::Error> {
+        let (a, b) = NonblockingUnixSeqpacketConn::pair()?;
+        Ok((Self::new(a)?, Self::new(b)?))
+    }
+
+    /// Get the wrapped connection.
+    pub fn get_ref(&self) -> &NonblockingUnixSeqpacketConn {
+        self.conn.get_ref()
+    }
+    /// Deregister the connection from tokio and unwrap it.
+    pub fn into_inner(self) -> NonblockingUnixSeqpacketConn {
+        self.conn.into_inner()
+    }
+
+    /// Send a packet, waiting for there to be room for it.
+    pub async fn send(&self,  packet: &[u8]) -> Result<usize, io::Error> {
+        poll_fn(|cx| self.poll_send(cx, packet) ).await
+    }
+    /// Send a packet with associated file descriptors.
+    pub async fn send_fds(&self,  bytes: &[u8],  fds: &[RawFd]) -> Result<usize, io::Error> {
+        poll_fn(|cx| self.poll_send_fds(cx, bytes, fds) ).await
+    }
+    /// Wait for a packet and receive it.
+    ///
+    /// The returned `bool` indicates whether the packet was truncated due to
+    /// the buffer being too small.
+    pub async fn recv(&self,  buffer: &mut[u8]) -> Result<(usize, bool), io::Error> {
+        poll_fn(|cx| self.poll_recv(cx, buffer) ).await
+    }
+    /// Wait for a packet and receive it along with associated file descriptors.
+    pub async fn recv_fds(&self,  byte_buffer: &mut[u8],  fd_buffer: &mut[RawFd])
+    -> Result<(usize, bool, usize), io::Error> {
+        poll_fn(|cx| self.poll_recv_fds(cx, byte_buffer, fd_buffer) ).await
+    }
+
+    /// Try to send a packet, or register the task to be woken when there
+    /// might be room for it.
+    ///
+    /// This is for implementing futures by hand; a waker registered by
+    /// one `poll_send*()` call can be replaced by the next one.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// use std::future::poll_fn;
+    /// use uds::TokioSeqpacketConn;
+    ///
+    /// # let runtime = tokio::runtime::Builder::new_current_thread().enable_io().build().unwrap();
+    /// # runtime.block_on(async {
+    /// let (a, b) = TokioSeqpacketConn::pair().unwrap();
+    /// poll_fn(|cx| a.poll_send(cx, b"polled") ).await.unwrap();
+    /// let mut buf = [0; 10];
+    /// let received = poll_fn(|cx| b.poll_recv(cx, &mut buf) ).await.unwrap();
+    /// assert_eq!(received, (6, false));
+    /// # });
+    /// ```
+    pub fn poll_send(&self,  cx: &mut Context<'_>,  packet: &[u8])
+    -> Poll<Result<usize, io::Error>> {
+        poll_write_with(&self.conn, cx, |conn| conn.send(packet) )
+    }
+    /// Try to send a packet with associated file descriptors, or register
+    /// the task to be woken when there might be room for it.
+    pub fn poll_send_fds(&self,  cx: &mut Context<'_>,  bytes: &[u8],  fds: &[RawFd])
+    -> Poll<Result<usize, io::Error>> {
+        poll_write_with(&self.conn, cx, |conn| conn.send_fds(bytes, fds) )
+    }
+    /// Try to receive a packet, or register the task to be woken when one
+    /// might be available.
+    ///
+    /// A waker registered by one `poll_recv*()` call can be replaced by the
+    /// next one.
+    pub fn poll_recv(&self,  cx: &mut Context<'_>,  buffer: &mut[u8])
+    -> Poll<Result<(usize, bool), io::Error>> {
+        poll_read_with(&self.conn, cx, |conn| conn.recv(buffer) )
+    }
+    /// Try to receive a packet with associated file descriptors,
+    /// or register the task to be woken when one might be available.
+    pub fn poll_recv_fds(&self,  cx: &mut Context<'_>,
+            byte_buffer: &mut[u8],  fd_buffer: &mut[RawFd],
+    ) -> Poll<Result<(usize, bool, usize), io::Error>> {
+        poll_read_with(&self.conn, cx, |conn| conn.recv_fds(byte_buffer, fd_buffer) )
+    }
+
+    /// Split the connection into a receiving half and a sending half that
+    /// can be moved to different tasks.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// use uds::TokioSeqpacketConn;
+    ///
+    /// # let runtime = tokio::runtime::Builder::new_current_thread().enable_io().build().unwrap();
+    /// # runtime.block_on(async {
+    /// let (a, b) = TokioSeqpacketConn::pair().unwrap();
+    /// let (a_recv, a_send) = a.into_split();
+    /// let echo = tokio::spawn(async move {
+    ///     let mut buf = [0; 10];
+    ///     let (len, _) = a_recv.recv(&mut buf).await.unwrap();
+    ///     a_send.send(&buf[..len]).await.unwrap();
+    /// });
+    /// b.send(b"echo").await.unwrap();
+    /// let mut buf = [0; 10];
+    /// assert_eq!(b.recv(&mut buf).await.unwrap(), (4, false));
+    /// echo.await.unwrap();
+    /// # });
+    /// ```
+    pub fn into_split(self) -> (OwnedRecvHalf, OwnedSendHalf) {
+        let conn = Arc::new(self.conn);
+        (OwnedRecvHalf { conn: conn.clone() }, OwnedSendHalf { conn })
+    }
+}
+
+/// The receiving half of a [`TokioSeqpacketConn`](struct.TokioSeqpacketConn.html),
+/// created by [`into_split()`](struct.TokioSeqpacketConn.html#method.into_split).
+///
+/// The connection is closed when both halves have been dropped.
+#[derive(Debug)]
+pub struct OwnedRecvHalf {
+    conn: Arc<AsyncFd<NonblockingUnixSeqpacketConn>>,
+}
+
+impl OwnedRecvHalf {
+    /// Get the shared connection.
+    pub fn get_ref(&self) -> &NonblockingUnixSeqpacketConn {
+        self.conn.get_ref()
+    }
+    /// Wait for a packet and receive it.
+    ///
+    /// See [`TokioSeqpacketConn::recv()`](struct.TokioSeqpacketConn.html#method.recv).
+    pub async fn recv(&self,  buffer: &mut[u8]) -> Result<(usize, bool), io::Error> {
+        poll_fn(|cx| self.poll_recv(cx, buffer) ).await
+    }
+    /// Wait for a packet and receive it along with associated file descriptors.
+    pub async fn recv_fds(&self,  byte_buffer: &mut[u8],  fd_buffer: &mut[RawFd])
+    -> Result<(usize, bool, usize), io::Error> {
+        poll_fn(|cx| self.poll_recv_fds(cx, byte_buffer, fd_buffer) ).await
+    }
+    /// Try to receive a packet, or register the task to be woken when one
+    /// might be available.
+    ///
+    /// See [`TokioSeqpacketConn::poll_recv()`](struct.TokioSeqpacketConn.html#method.poll_recv).
+    pub fn poll_recv(&self,  cx: &mut Context<'_>,  buffer: &mut[u8])
+    -> Poll<Result<(usize, bool), io::Error>> {
+        poll_read_with(&self.conn, cx, |conn| conn.recv(buffer) )
+    }
+    /// Try to receive a packet with associated file descriptors,
+    /// or register the task to be woken when one might be available.
+    pub fn poll_recv_fds(&self,  cx: &mut Context<'_>,
+            byte_buffer: &mut[u8],  fd_buffer: &mut[RawFd],
+    ) -> Poll<Result<(usize, bool, usize), io::Error>> {
+        poll_read_with(&self.conn, cx, |conn| conn.recv_fds(byte_buffer, fd_buffer) )
+    }
+    /// Put the halves back together.
+    ///
+    /// Returns the halves unchanged if they came from different connections.
+    pub fn reunite(self,  send: OwnedSendHalf)
+    -> Result<TokioSeqpacketConn, (OwnedRecvHalf, OwnedSendHalf)> {
+        if !Arc::ptr_eq(&self.conn, &send.conn) {
+            return Err((self, send));
+        }
+        drop(send);
+        match Arc::try_unwrap(self.conn) {
+            Ok(conn) => Ok(TokioSeqpacketConn { conn }),
+            Err(_) => unreachable!("a connection is only split into two halves"),
+        }
+    }
+}
+
+/// The sending half of a [`TokioSeqpacketConn`](struct.TokioSeqpacketConn.html),
+/// created by [`into_split()`](struct.TokioSeqpacketConn.html#method.into_split).
+///
+/// Dropping this half doesn't shut down the connection,
+/// use [`shutdown()`](#method.shutdown) for that.
+#[derive(Debug)]
+pub struct OwnedSendHalf {
+    conn: Arc<AsyncFd<NonblockingUnixSeqpacketConn>>,
+}
+
+impl OwnedSendHalf {
+    /// Get the shared connection.
+    pub fn get_ref(&self) -> &NonblockingUnixSeqpacketConn {
+        self.conn.get_ref()
+    }
+    /// Send a packet, waiting for there to be room for it.
+    pub async fn send(&self,  packet: &[u8]) -> Result<usize, io::Error> {
+        poll_fn(|cx| self.poll_send(cx, packet) ).await
+    }
+    /// Send a packet with associated file descriptors.
+    pub async fn send_fds(&self,  bytes: &[u8],  fds: &[RawFd]) -> Result<usize, io::Error> {
+        poll_fn(|cx| self.poll_send_fds(cx, bytes, fds) ).await
+    }
+    /// Try to send a packet, or register the task to be woken when there
+    /// might be room for it.
+    ///
+    /// See [`TokioSeqpacketConn::poll_send()`](struct.TokioSeqpacketConn.html#method.poll_send).
+    pub fn poll_send(&self,  cx: &mut Context<'_>,  packet: &[u8])
+    -> Poll<Result<usize, io::Error>> {
+        poll_write_with(&self.conn, cx, |conn| conn.send(packet) )
+    }
+    /// Try to send a packet with associated file descriptors, or register
+    /// the task to be woken when there might be room for it.
+    pub fn poll_send_fds(&self,  cx: &mut Context<'_>,  bytes: &[u8],  fds: &[RawFd])
+    -> Poll<Result<usize, io::Error>> {
+        poll_write_with(&self.conn, cx, |conn| conn.send_fds(bytes, fds) )
+    }
+    /// Shut down the write direction, which the peer sees as end of connection.
+    pub fn shutdown(&self) -> Result<(), io::Error> {
+        self.conn.get_ref().shutdown(Shutdown::Write)
+    }
+}
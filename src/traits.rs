@@ -1,13 +1,22 @@
-use std::os::unix::io::{RawFd, AsRawFd, FromRawFd, IntoRawFd};
+use std::os::unix::io::{RawFd, AsFd, OwnedFd, AsRawFd, FromRawFd, IntoRawFd};
 use std::os::unix::net::{UnixStream, UnixListener, UnixDatagram};
 use std::io::{self, IoSlice, IoSliceMut};
+use std::net::Shutdown;
+use std::num::NonZeroU32;
+use std::time::{Duration, SystemTime};
 
-use libc::SOCK_STREAM;
+use libc::{SOCK_STREAM, SOCK_DGRAM, MSG_PEEK, MSG_DONTWAIT};
+#[cfg(any(target_os="linux", target_os="android"))]
+use libc::MSG_TRUNC;
 
-use crate::addr::UnixSocketAddr;
+use crate::addr::{UnixSocketAddr, ToUnixSocketAddr};
 use crate::helpers::*;
 use crate::ancillary::*;
 use crate::credentials::*;
+#[cfg(all(feature="memfd", any(target_os="linux", target_os="android")))]
+use crate::memfd::{MemfdPayload, send_memfd, recv_memfd};
+use crate::seqpacket::{UnixSeqpacketConn, NonblockingUnixSeqpacketConn};
+use crate::builder::SocketBuilder;
 
 pub trait UnixStreamExt: AsRawFd + FromRawFd + Sized {
     fn local_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
@@ -17,66 +26,283 @@ pub trait UnixStreamExt: AsRawFd + FromRawFd + Sized {
         peer_addr(self.as_raw_fd())
     }
 
-    fn connect_to_unix_addr(addr: &UnixSocketAddr) -> Result<Self, io::Error>;
-    fn connect_from_to_unix_addr(from: &UnixSocketAddr,  to: &UnixSocketAddr)
-    -> Result<Self, io::Error>;
+    fn connect_to_unix_addr<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error>;
+    fn connect_from_to_unix_addr<F, T>(from: F,  to: T) -> Result<Self, io::Error>
+    where F: ToUnixSocketAddr, T: ToUnixSocketAddr;
+    /// Connect to an address, waiting at most `timeout` for the server to
+    /// accept the connection into its backlog.
+    ///
+    /// # Errors
+    ///
+    /// Fails with an error of kind `TimedOut` if the connection could not be
+    /// established within the timeout, and `InvalidInput` if `timeout` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::os::unix::net::{UnixListener, UnixStream};
+    /// use std::time::Duration;
+    /// use uds::{UnixListenerExt, UnixStreamExt, UnixSocketAddr};
+    ///
+    /// let addr = UnixSocketAddr::new_unique().unwrap();
+    /// let _listener = UnixListener::bind_unix_addr(addr).unwrap();
+    /// let timeout = Duration::from_secs(1);
+    /// let _conn = UnixStream::connect_to_unix_addr_timeout(addr, timeout).unwrap();
+    /// # if let Some(path) = addr.as_pathname() { let _ = std::fs::remove_file(path); }
+    /// ```
+    ///
+    /// The provided implementation creates a blocking socket,
+    /// so implementations for nonblocking types should override it.
+    fn connect_to_unix_addr_timeout<A: ToUnixSocketAddr>(addr: A,  timeout: Duration)
+    -> Result<Self, io::Error> {
+        let socket = Socket::connected_with_timeout(false, &addr.to_unix_addr()?, timeout)?;
+        Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
+    }
 
     fn send_fds(&self,  bytes: &[u8],  fds: &[RawFd]) -> Result<usize, io::Error> {
         send_ancillary(self.as_raw_fd(), None, 0, &[IoSlice::new(bytes)], fds, None)
     }
+    /// Send bytes with explicit credentials (`SCM_CREDENTIALS`).
+    ///
+    /// Credentials other than those of this process require privileges,
+    /// see [`SendCredentials::Custom`](enum.SendCredentials.html#variant.Custom).
+    #[cfg(any(target_os="linux", target_os="android"))]
+    fn send_with_credentials(&self,  bytes: &[u8],  creds: SendCredentials)
+    -> Result<usize, io::Error> {
+        send_ancillary(self.as_raw_fd(), None, 0, &[IoSlice::new(bytes)], &[], Some(creds))
+    }
+    /// Send bytes with file descriptors from values of any type that
+    /// implements `AsFd`.
+    fn send_as_fds<I>(&self,  bytes: &[u8],  fds: I) -> Result<usize, io::Error>
+    where I: IntoIterator, I::Item: AsFd {
+        send_as_fds(self.as_raw_fd(), None, 0, &[IoSlice::new(bytes)], fds)
+    }
     fn recv_fds(&self,  buf: &mut[u8],  fd_buf: &mut[RawFd]) -> Result<(usize, usize), io::Error> {
         recv_fds(self.as_raw_fd(), None, &mut[IoSliceMut::new(buf)], fd_buf)
             .map(|(bytes, _, fds)| (bytes, fds) )
     }
+    /// Send bytes from multiple buffers together with file descriptors,
+    /// in one `sendmsg()` call.
+    fn send_vectored_with_fds(&self,  bufs: &[IoSlice],  fds: &[RawFd])
+    -> Result<usize, io::Error> {
+        send_ancillary(self.as_raw_fd(), None, 0, bufs, fds, None)
+    }
+    /// Receive bytes into multiple buffers together with file descriptors,
+    /// in one `recvmsg()` call.
+    ///
+    /// Returns the number of bytes and file descriptors received.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{IoSlice, IoSliceMut};
+    /// use std::os::unix::io::AsRawFd;
+    /// use std::os::unix::net::UnixStream;
+    /// use uds::UnixStreamExt;
+    ///
+    /// let (a, b) = UnixStream::pair().unwrap();
+    /// let header = [4, 0];
+    /// a.send_vectored_with_fds(&[IoSlice::new(&header), IoSlice::new(b"body")], &[a.as_raw_fd()])
+    ///     .unwrap();
+    /// let (mut header, mut body) = ([0; 2], [0; 4]);
+    /// let mut fd_buf = [-1; 2];
+    /// let (bytes, fds) = b.recv_vectored_with_fds(
+    ///     &mut[IoSliceMut::new(&mut header), IoSliceMut::new(&mut body)],
+    ///     &mut fd_buf,
+    /// ).unwrap();
+    /// assert_eq!((bytes, fds), (6, 1));
+    /// assert_eq!((header, &body), ([4, 0], b"body"));
+    /// # unsafe { libc::close(fd_buf[0]) };
+    /// ```
+    fn recv_vectored_with_fds(&self,  bufs: &mut[IoSliceMut],  fd_buf: &mut[RawFd])
+    -> Result<(usize, usize), io::Error> {
+        recv_fds(self.as_raw_fd(), None, bufs, fd_buf)
+            .map(|(bytes, _, fds)| (bytes, fds) )
+    }
+    /// Receive bytes and up to `max_fds` file descriptors,
+    /// which are closed when dropped.
+    fn recv_owned_fds(&self,  buf: &mut[u8],  max_fds: usize)
+    -> Result<(usize, Vec<OwnedFd>), io::Error> {
+        recv_owned_fds(self.as_raw_fd(), None, &mut[IoSliceMut::new(buf)], max_fds, true)
+            .map(|(bytes, _, fds)| (bytes, fds) )
+    }
+    /// Like `recv_owned_fds()`, but fails with an error of kind
+    /// `InvalidData` and closes all received file descriptors if more than
+    /// `max_fds` were sent.
+    fn recv_fds_limited(&self,  buf: &mut[u8],  max_fds: usize)
+    -> Result<(usize, Vec<OwnedFd>), io::Error> {
+        recv_fds_limited(self.as_raw_fd(), None, &mut[IoSliceMut::new(buf)], max_fds)
+            .map(|(bytes, _, fds)| (bytes, fds) )
+    }
+    /// Like `recv_owned_fds()`, but without making the received
+    /// file descriptors close-on-exec.
+    fn recv_inheritable_fds(&self,  buf: &mut[u8],  max_fds: usize)
+    -> Result<(usize, Vec<OwnedFd>), io::Error> {
+        recv_owned_fds(self.as_raw_fd(), None, &mut[IoSliceMut::new(buf)], max_fds, false)
+            .map(|(bytes, _, fds)| (bytes, fds) )
+    }
 
     fn initial_peer_credentials(&self) -> Result<ConnCredentials, io::Error> {
         peer_credentials(self.as_raw_fd())
     }
+    /// Get the process ID of the initial peer of the connection.
+    ///
+    /// This is available on Linux and Android, where it's also part of
+    /// [`initial_peer_credentials()`](#method.initial_peer_credentials),
+    /// and on macOS, where it's retrieved with `LOCAL_PEERPID`.
+    fn initial_peer_pid(&self) -> Result<NonZeroU32, io::Error> {
+        peer_pid(self.as_raw_fd())
+    }
+    /// Get the process ID of the process the initial peer connected on
+    /// behalf of, which differs from
+    /// [`initial_peer_pid()`](#method.initial_peer_pid) for connections
+    /// made by system daemons for apps. (`LOCAL_PEEREPID`)
+    #[cfg(target_vendor="apple")]
+    fn initial_peer_effective_pid(&self) -> Result<NonZeroU32, io::Error> {
+        peer_effective_pid(self.as_raw_fd())
+    }
+    /// Get the UUID of the executable of the process the initial peer
+    /// connected on behalf of. (`LOCAL_PEEREUUID`)
+    #[cfg(target_vendor="apple")]
+    fn initial_peer_effective_uuid(&self) -> Result<[u8; 16], io::Error> {
+        peer_effective_uuid(self.as_raw_fd())
+    }
+    /// Get the supplementary groups of the peer process when the connection
+    /// was established.
+    ///
+    /// This is available on Linux 4.13 and later, FreeBSD and macOS.
+    fn initial_peer_groups(&self) -> Result<Vec<u32>, io::Error> {
+        peer_groups(self.as_raw_fd())
+    }
+    /// Get the security label of the peer process when the connection was
+    /// established (`SO_PEERSEC`), without any trailing NUL.
+    #[cfg(any(target_os="linux", target_os="android"))]
+    fn peer_security_label(&self) -> Result<Vec<u8>, io::Error> {
+        peer_security_label(self.as_raw_fd())
+    }
+    /// Get a pidfd referring to the peer process when the connection was
+    /// established (`SO_PEERPIDFD`, Linux 6.5 and later).
+    #[cfg(any(target_os="linux", target_os="android"))]
+    fn peer_pidfd(&self) -> Result<OwnedFd, io::Error> {
+        peer_pidfd(self.as_raw_fd())
+    }
+    /// Get the number of bytes that can be read without blocking.
+    /// (`FIONREAD`)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use std::os::unix::net::UnixStream;
+    /// use uds::UnixStreamExt;
+    ///
+    /// let (mut a, b) = UnixStream::pair().unwrap();
+    /// assert_eq!(b.bytes_available().unwrap(), 0);
+    /// a.write_all(b"pending").unwrap();
+    /// assert_eq!(b.bytes_available().unwrap(), 7);
+    /// ```
+    fn bytes_available(&self) -> Result<usize, io::Error> {
+        bytes_available(self.as_raw_fd())
+    }
+
+    /// Move up to `len` received bytes into a pipe, without copying them
+    /// through userspace. (`splice()`)
+    ///
+    /// Returns the number of bytes moved, which is zero at end of stream.
+    /// To move bytes into a file or another socket, splice them from the
+    /// pipe with [`splice_from()`](#method.splice_from) on a socket or
+    /// `libc::splice()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::fs::File;
+    /// use std::io::{Read, Write};
+    /// use std::os::unix::io::FromRawFd;
+    /// use std::os::unix::net::UnixStream;
+    /// use uds::UnixStreamExt;
+    ///
+    /// let mut fds = [-1; 2];
+    /// assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+    /// let (mut pipe_read, pipe_write) = unsafe {
+    ///     (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1]))
+    /// };
+    ///
+    /// let (mut a, b) = UnixStream::pair().unwrap();
+    /// a.write_all(b"zero-copy").unwrap();
+    /// assert_eq!(b.splice_to(&pipe_write, 100).unwrap(), 9);
+    /// let mut buf = [0; 9];
+    /// pipe_read.read_exact(&mut buf).unwrap();
+    /// assert_eq!(&buf, b"zero-copy");
+    /// ```
+    #[cfg(any(target_os="linux", target_os="android"))]
+    fn splice_to<P: AsFd>(&self,  pipe: &P,  len: usize) -> Result<usize, io::Error> {
+        splice(self.as_raw_fd(), pipe.as_fd().as_raw_fd(), len)
+    }
+    /// Send up to `len` bytes from a pipe, without copying them through
+    /// userspace. (`splice()`)
+    ///
+    /// Returns the number of bytes moved, which is zero if the write end of
+    /// the pipe is closed and the pipe is empty.
+    #[cfg(any(target_os="linux", target_os="android"))]
+    fn splice_from<P: AsFd>(&self,  pipe: &P,  len: usize) -> Result<usize, io::Error> {
+        splice(pipe.as_fd().as_raw_fd(), self.as_raw_fd(), len)
+    }
 }
 
 impl UnixStreamExt for UnixStream {
-    fn connect_to_unix_addr(addr: &UnixSocketAddr) -> Result<Self, io::Error> {
+    fn connect_to_unix_addr<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error> {
         let socket = Socket::new(SOCK_STREAM, false)?;
-        connect_to(socket.as_raw_fd(), addr)?;
+        connect_to(socket.as_raw_fd(), &addr.to_unix_addr()?)?;
         Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
     }
-    fn connect_from_to_unix_addr(from: &UnixSocketAddr,  to: &UnixSocketAddr)
-    -> Result<Self, io::Error> {
+    fn connect_from_to_unix_addr<F, T>(from: F,  to: T) -> Result<Self, io::Error>
+    where F: ToUnixSocketAddr, T: ToUnixSocketAddr {
         let socket = Socket::new(SOCK_STREAM, false)?;
-        bind_to(socket.as_raw_fd(), from)?;
-        connect_to(socket.as_raw_fd(), to)?;
+        bind_to(socket.as_raw_fd(), &from.to_unix_addr()?)?;
+        connect_to(socket.as_raw_fd(), &to.to_unix_addr()?)?;
         Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
     }
 }
 
 #[cfg(feature="mio-uds")]
 impl UnixStreamExt for mio_uds::UnixStream {
-    fn connect_to_unix_addr(addr: &UnixSocketAddr) -> Result<Self, io::Error> {
+    fn connect_to_unix_addr<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error> {
         let socket = Socket::new(SOCK_STREAM, true)?;
-        connect_to(socket.as_raw_fd(), addr)?;
+        connect_to(socket.as_raw_fd(), &addr.to_unix_addr()?)?;
         Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
     }
-    fn connect_from_to_unix_addr(from: &UnixSocketAddr,  to: &UnixSocketAddr)
-    -> Result<Self, io::Error> {
+    fn connect_from_to_unix_addr<F, T>(from: F,  to: T) -> Result<Self, io::Error>
+    where F: ToUnixSocketAddr, T: ToUnixSocketAddr {
         let socket = Socket::new(SOCK_STREAM, true)?;
-        bind_to(socket.as_raw_fd(), from)?;
-        connect_to(socket.as_raw_fd(), to)?;
+        bind_to(socket.as_raw_fd(), &from.to_unix_addr()?)?;
+        connect_to(socket.as_raw_fd(), &to.to_unix_addr()?)?;
+        Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
+    }
+    fn connect_to_unix_addr_timeout<A: ToUnixSocketAddr>(addr: A,  timeout: Duration)
+    -> Result<Self, io::Error> {
+        let socket = Socket::connected_with_timeout(true, &addr.to_unix_addr()?, timeout)?;
         Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
     }
 }
 
 #[cfg(feature="mio_07")]
 impl UnixStreamExt for mio_07::net::UnixStream {
-    fn connect_to_unix_addr(addr: &UnixSocketAddr) -> Result<Self, io::Error> {
+    fn connect_to_unix_addr<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error> {
         let socket = Socket::new(SOCK_STREAM, true)?;
-        connect_to(socket.as_raw_fd(), addr)?;
+        connect_to(socket.as_raw_fd(), &addr.to_unix_addr()?)?;
         Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
     }
-    fn connect_from_to_unix_addr(from: &UnixSocketAddr,  to: &UnixSocketAddr)
-    -> Result<Self, io::Error> {
+    fn connect_from_to_unix_addr<F, T>(from: F,  to: T) -> Result<Self, io::Error>
+    where F: ToUnixSocketAddr, T: ToUnixSocketAddr {
         let socket = Socket::new(SOCK_STREAM, true)?;
-        bind_to(socket.as_raw_fd(), from)?;
-        connect_to(socket.as_raw_fd(), to)?;
+        bind_to(socket.as_raw_fd(), &from.to_unix_addr()?)?;
+        connect_to(socket.as_raw_fd(), &to.to_unix_addr()?)?;
+        Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
+    }
+    fn connect_to_unix_addr_timeout<A: ToUnixSocketAddr>(addr: A,  timeout: Duration)
+    -> Result<Self, io::Error> {
+        let socket = Socket::connected_with_timeout(true, &addr.to_unix_addr()?, timeout)?;
         Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
     }
 }
@@ -88,7 +314,63 @@ pub trait UnixListenerExt: AsRawFd + FromRawFd + Sized {
     type Conn: FromRawFd;
 
     /// Create a socket bound to a `UnixSocketAddr` and start listening on it.
-    fn bind_unix_addr(on: &UnixSocketAddr) -> Result<Self, io::Error>;
+    fn bind_unix_addr<A: ToUnixSocketAddr>(on: A) -> Result<Self, io::Error>;
+    /// Create a socket bound to a `UnixSocketAddr` and start listening on it
+    /// with a custom backlog of connections waiting to be accepted.
+    ///
+    /// The OS might limit the backlog further,
+    /// such as to `/proc/sys/net/core/somaxconn` on Linux.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(any(target_os="linux", target_os="android"), doc="```")]
+    #[cfg_attr(not(any(target_os="linux", target_os="android")), doc="```no_run")]
+    /// use std::os::unix::net::{UnixListener, UnixStream};
+    /// use uds::{UnixListenerExt, UnixStreamExt};
+    ///
+    /// let listener = UnixListener::bind_unix_addr_with_backlog("@uds backlog example", 128)
+    ///     .unwrap();
+    /// let _client = UnixStream::connect_to_unix_addr("@uds backlog example").unwrap();
+    /// let (_server_side, _) = listener.accept_unix_addr().unwrap();
+    /// ```
+    fn bind_unix_addr_with_backlog<A: ToUnixSocketAddr>(on: A,  backlog: u32)
+    -> Result<Self, io::Error>;
+    /// Create a socket bound to a path and start listening on it, replacing
+    /// the socket file left behind by a server that is no longer running.
+    ///
+    /// If binding fails because the path exists, this tries to connect to it.
+    /// The file is only removed if that is refused and it is a socket file,
+    /// otherwise an error with kind `AddrInUse` is returned if another server
+    /// is listening on the path, or `AlreadyExists` if it isn't a socket.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::ErrorKind;
+    /// use std::os::unix::net::UnixListener;
+    /// use uds::UnixListenerExt;
+    ///
+    /// # let _ = std::fs::remove_file("/tmp/uds takeover example.sock");
+    /// let crashed = UnixListener::bind_unix_addr("/tmp/uds takeover example.sock").unwrap();
+    /// drop(crashed); // leaves the file
+    /// let server = UnixListener::bind_unix_addr_taking_over("/tmp/uds takeover example.sock")
+    ///     .unwrap();
+    /// let err = UnixListener::bind_unix_addr_taking_over("/tmp/uds takeover example.sock")
+    ///     .unwrap_err();
+    /// assert_eq!(err.kind(), ErrorKind::AddrInUse);
+    /// # drop(server);
+    /// # std::fs::remove_file("/tmp/uds takeover example.sock").unwrap();
+    /// ```
+    fn bind_unix_addr_taking_over<A: ToUnixSocketAddr>(on: A) -> Result<Self, io::Error> {
+        let addr = on.to_unix_addr()?;
+        match Self::bind_unix_addr(addr) {
+            Err(e) if e.kind() == io::ErrorKind::AddrInUse => {
+                remove_stale_socket(&addr, e, SOCK_STREAM)?
+            }
+            result => return result,
+        }
+        Self::bind_unix_addr(addr)
+    }
 
     /// Get the address this socket is listening on.
     fn local_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
@@ -98,18 +380,53 @@ pub trait UnixListenerExt: AsRawFd + FromRawFd + Sized {
     /// Accept a connection and return the client's address as
     /// an `uds::UnixSocketAddr`.
     fn accept_unix_addr(&self) -> Result<(Self::Conn, UnixSocketAddr), io::Error>;
+
+    /// Accept a connection as a nonblocking socket, and return the client's
+    /// address as an `uds::UnixSocketAddr`.
+    ///
+    /// The connection is made nonblocking and close-on-exec atomically
+    /// with `accept4()` where available, for event loops built on std types.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(any(target_os="linux", target_os="android"), doc="```")]
+    #[cfg_attr(not(any(target_os="linux", target_os="android")), doc="```no_run")]
+    /// use std::io::{ErrorKind, Read};
+    /// use std::os::unix::net::{UnixListener, UnixStream};
+    /// use uds::{UnixListenerExt, UnixStreamExt, UnixSocketAddr};
+    ///
+    /// let listener = UnixListener::bind_unix_addr("@uds nonblocking accept").unwrap();
+    /// let _client = UnixStream::connect_from_to_unix_addr("@uds client", "@uds nonblocking accept")
+    ///     .unwrap();
+    /// let (mut conn, addr) = listener.accept_unix_addr_nonblocking().unwrap();
+    /// assert_eq!(addr, UnixSocketAddr::new("@uds client").unwrap());
+    /// assert_eq!(conn.read(&mut[0; 10]).unwrap_err().kind(), ErrorKind::WouldBlock);
+    /// ```
+    fn accept_unix_addr_nonblocking(&self) -> Result<(Self::Conn, UnixSocketAddr), io::Error> {
+        let (socket, addr) = Socket::accept_from(self.as_raw_fd(), true)?;
+        let conn = unsafe { Self::Conn::from_raw_fd(socket.into_raw_fd()) };
+        Ok((conn, addr))
+    }
 }
 
 impl UnixListenerExt for UnixListener {
     type Conn = UnixStream;
 
-    fn bind_unix_addr(on: &UnixSocketAddr) -> Result<Self, io::Error> {
+    fn bind_unix_addr<A: ToUnixSocketAddr>(on: A) -> Result<Self, io::Error> {
         let socket = Socket::new(SOCK_STREAM, false)?;
-        bind_to(socket.as_raw_fd(), on)?;
+        bind_to(socket.as_raw_fd(), &on.to_unix_addr()?)?;
         socket.start_listening()?;
         Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
     }
 
+    fn bind_unix_addr_with_backlog<A: ToUnixSocketAddr>(on: A,  backlog: u32)
+    -> Result<Self, io::Error> {
+        let socket = Socket::new(SOCK_STREAM, false)?;
+        bind_to(socket.as_raw_fd(), &on.to_unix_addr()?)?;
+        socket.start_listening_with_backlog(backlog)?;
+        Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
+    }
+
     fn accept_unix_addr(&self) -> Result<(Self::Conn, UnixSocketAddr), io::Error> {
         let (socket, addr) = Socket::accept_from(self.as_raw_fd(), false)?;
         let conn = unsafe { Self::Conn::from_raw_fd(socket.into_raw_fd()) };
@@ -121,13 +438,21 @@ impl UnixListenerExt for UnixListener {
 impl UnixListenerExt for mio_uds::UnixListener {
     type Conn = mio_uds::UnixStream;
 
-    fn bind_unix_addr(on: &UnixSocketAddr) -> Result<Self, io::Error> {
+    fn bind_unix_addr<A: ToUnixSocketAddr>(on: A) -> Result<Self, io::Error> {
         let socket = Socket::new(SOCK_STREAM, true)?;
-        bind_to(socket.as_raw_fd(), on)?;
+        bind_to(socket.as_raw_fd(), &on.to_unix_addr()?)?;
         socket.start_listening()?;
         Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
     }
 
+    fn bind_unix_addr_with_backlog<A: ToUnixSocketAddr>(on: A,  backlog: u32)
+    -> Result<Self, io::Error> {
+        let socket = Socket::new(SOCK_STREAM, true)?;
+        bind_to(socket.as_raw_fd(), &on.to_unix_addr()?)?;
+        socket.start_listening_with_backlog(backlog)?;
+        Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
+    }
+
     fn accept_unix_addr(&self) -> Result<(Self::Conn, UnixSocketAddr), io::Error> {
         let (socket, addr) = Socket::accept_from(self.as_raw_fd(), true)?;
         let conn = unsafe { Self::Conn::from_raw_fd(socket.into_raw_fd()) };
@@ -139,13 +464,21 @@ impl UnixListenerExt for mio_uds::UnixListener {
 impl UnixListenerExt for mio_07::net::UnixListener {
     type Conn = mio_07::net::UnixStream;
 
-    fn bind_unix_addr(on: &UnixSocketAddr) -> Result<Self, io::Error> {
+    fn bind_unix_addr<A: ToUnixSocketAddr>(on: A) -> Result<Self, io::Error> {
         let socket = Socket::new(SOCK_STREAM, true)?;
-        bind_to(socket.as_raw_fd(), on)?;
+        bind_to(socket.as_raw_fd(), &on.to_unix_addr()?)?;
         socket.start_listening()?;
         Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
     }
 
+    fn bind_unix_addr_with_backlog<A: ToUnixSocketAddr>(on: A,  backlog: u32)
+    -> Result<Self, io::Error> {
+        let socket = Socket::new(SOCK_STREAM, true)?;
+        bind_to(socket.as_raw_fd(), &on.to_unix_addr()?)?;
+        socket.start_listening_with_backlog(backlog)?;
+        Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
+    }
+
     fn accept_unix_addr(&self) -> Result<(Self::Conn, UnixSocketAddr), io::Error> {
         let (socket, addr) = Socket::accept_from(self.as_raw_fd(), true)?;
         let conn = unsafe { Self::Conn::from_raw_fd(socket.into_raw_fd()) };
@@ -163,20 +496,402 @@ pub trait UnixDatagramExt: AsRawFd + FromRawFd + Sized {
         peer_addr(self.as_raw_fd())
     }
 
-    fn bind_to_unix_addr(&self,  addr: &UnixSocketAddr) -> Result<(), io::Error> {
-        bind_to(self.as_raw_fd(), addr)
+    /// Create a socket bound to an address.
+    ///
+    /// Like all methods that take a `ToUnixSocketAddr`, this accepts strings
+    /// in the same format as [`UnixSocketAddr::new()`](struct.UnixSocketAddr.html#method.new),
+    /// where a leading `@` means an abstract address.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(any(target_os="linux", target_os="android"), doc="```")]
+    #[cfg_attr(not(any(target_os="linux", target_os="android")), doc="```no_run")]
+    /// use std::os::unix::net::UnixDatagram;
+    /// use uds::UnixDatagramExt;
+    ///
+    /// let server = UnixDatagram::bind_unix_addr("@uds datagram example").unwrap();
+    /// let client = UnixDatagram::unbound().unwrap();
+    /// client.send_to_unix_addr(b"hello", "@uds datagram example").unwrap();
+    /// assert_eq!(server.recv(&mut[0; 10]).unwrap(), 5);
+    /// ```
+    ///
+    /// The provided implementation creates a blocking socket,
+    /// so implementations for nonblocking types should override it.
+    fn bind_unix_addr<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error> {
+        let socket = Socket::bound(SOCK_DGRAM, false, &addr.to_unix_addr()?)?;
+        Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
     }
-    fn connect_to_unix_addr(&self,  addr: &UnixSocketAddr) -> Result<(), io::Error> {
-        connect_to(self.as_raw_fd(), addr)
+    fn bind_to_unix_addr<A: ToUnixSocketAddr>(&self,  addr: A) -> Result<(), io::Error> {
+        bind_to(self.as_raw_fd(), &addr.to_unix_addr()?)
+    }
+    /// Create a pair of connected sockets with the options of a datagram
+    /// [`SocketBuilder`](struct.SocketBuilder.html) applied to both,
+    /// such as for a private channel between a process and a forked child.
+    ///
+    /// `options` must be from [`SocketBuilder::datagram()`](struct.SocketBuilder.html#method.datagram),
+    /// and its nonblocking option must match `Self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::os::unix::net::UnixDatagram;
+    /// use uds::{UnixDatagramExt, SocketBuilder};
+    ///
+    /// let options = SocketBuilder::datagram()
+    ///     .cloexec(false)
+    ///     .send_buffer_size(16*1024);
+    /// let (parent, child) = UnixDatagram::pair_with(&options).unwrap();
+    /// child.send(b"ready").unwrap();
+    /// assert_eq!(parent.recv(&mut[0; 10]).unwrap(), 5);
+    /// ```
+    fn pair_with(options: &SocketBuilder) -> Result<(Self, Self), io::Error> {
+        options.pair_of_type(SOCK_DGRAM)
+    }
+    /// Connect the socket to an address, so that `send()` sends to it,
+    /// and only datagrams from it are received.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::os::unix::net::UnixDatagram;
+    /// use uds::{UnixDatagramExt, UnixSocketAddr};
+    ///
+    /// let addr = UnixSocketAddr::new_unique().unwrap();
+    /// let server = UnixDatagram::bind_unix_addr(addr).unwrap();
+    /// let client = UnixDatagram::unbound().unwrap();
+    /// client.connect_to_unix_addr(addr).unwrap();
+    /// client.try_send(b"connected").unwrap();
+    /// let mut buf = [0; 5];
+    /// assert_eq!(server.try_recv(&mut buf).unwrap(), (5, true));
+    /// assert_eq!(&buf, b"conne");
+    /// # if let Some(path) = addr.as_pathname() { let _ = std::fs::remove_file(path); }
+    /// ```
+    fn connect_to_unix_addr<A: ToUnixSocketAddr>(&self,  addr: A) -> Result<(), io::Error> {
+        connect_to(self.as_raw_fd(), &addr.to_unix_addr()?)
+    }
+    /// Send a datagram to the connected address without blocking,
+    /// even if the socket is in blocking mode.
+    ///
+    /// Returns an error of kind `WouldBlock` if the receiver's buffer is full.
+    /// This uses `MSG_DONTWAIT`, so the socket doesn't need to be switched
+    /// between blocking and nonblocking mode.
+    fn try_send(&self,  datagram: &[u8]) -> Result<usize, io::Error> {
+        send_ancillary(self.as_raw_fd(), None, MSG_DONTWAIT, &[IoSlice::new(datagram)], &[], None)
+    }
+    /// Receive a datagram without blocking, even if the socket is in
+    /// blocking mode.
+    ///
+    /// Returns an error of kind `WouldBlock` if no datagram is available.
+    /// The returned `bool` indicates whether the datagram was truncated due
+    /// to too short buffer.
+    fn try_recv(&self,  buf: &mut[u8]) -> Result<(usize, bool), io::Error> {
+        recv_ancillary(self.as_raw_fd(), None, MSG_DONTWAIT, &mut[IoSliceMut::new(buf)], &mut[])
+            .map(|(len, ancillary)| (len, ancillary.message_truncated()) )
+    }
+    /// Receive a datagram, and get its full length even if it
+    /// didn't fit in the buffer.
+    ///
+    /// If the returned length is greater than the length of `buf`, the
+    /// datagram was truncated and the rest of it is lost.
+    ///
+    /// This uses `MSG_TRUNC`, which only Linux and Android support for
+    /// `recv()`.
+    #[cfg(any(target_os="linux", target_os="android"))]
+    fn recv_full_size(&self,  buf: &mut[u8]) -> Result<usize, io::Error> {
+        recv_ancillary(self.as_raw_fd(), None, MSG_TRUNC, &mut[IoSliceMut::new(buf)], &mut[])
+            .map(|(len, _)| len )
+    }
+    /// Get the number of bytes that can be received without blocking.
+    /// (`FIONREAD`)
+    ///
+    /// This is the size of the next datagram on Linux,
+    /// but might be the total size of all queued datagrams on other
+    /// operating systems.
+    fn bytes_available(&self) -> Result<usize, io::Error> {
+        bytes_available(self.as_raw_fd())
+    }
+    /// Send multiple datagrams to an address with a single system call.
+    /// (`sendmmsg()`)
+    ///
+    /// Returns the number of datagrams sent, which can be fewer than given
+    /// if the socket is nonblocking or the send buffer fills up.
+    /// Linux sends at most 1024 datagrams per call.
+    #[cfg(any(target_os="linux", target_os="android"))]
+    fn send_multiple_to<A: ToUnixSocketAddr>(&self,  datagrams: &[&[u8]],  addr: A)
+    -> Result<usize, io::Error> {
+        let addr = addr.to_unix_addr()?;
+        send_multiple_to(self.as_raw_fd(), &addr, datagrams)
+    }
+    /// Receive up to one datagram per buffer with a single system call.
+    /// (`recvmmsg()`)
+    ///
+    /// Only waits for the first datagram, and returns the length, whether it
+    /// was truncated and the address it was sent from for each datagram
+    /// received.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::os::unix::net::UnixDatagram;
+    /// use uds::{UnixDatagramExt, UnixSocketAddr};
+    ///
+    /// let addr = UnixSocketAddr::new_unique().unwrap();
+    /// let collector = UnixDatagram::bind_unix_addr(addr).unwrap();
+    /// let reporter = UnixDatagram::unbound().unwrap();
+    /// assert_eq!(reporter.send_multiple_to(&[b"cpu:5", b"mem:60"], addr).unwrap(), 2);
+    ///
+    /// let mut storage = [[0u8; 16]; 4];
+    /// let mut buffers = storage.iter_mut().map(|b| &mut b[..] ).collect::<Vec<_>>();
+    /// let received = collector.recv_multiple_from(&mut buffers).unwrap();
+    /// assert_eq!(received.len(), 2);
+    /// assert_eq!(&buffers[1][..received[1].0], b"mem:60");
+    /// # if let Some(path) = addr.as_pathname() { let _ = std::fs::remove_file(path); }
+    /// ```
+    #[cfg(any(target_os="linux", target_os="android"))]
+    fn recv_multiple_from(&self,  buffers: &mut[&mut[u8]])
+    -> Result<Vec<(usize, bool, UnixSocketAddr)>, io::Error> {
+        recv_multiple_from(self.as_raw_fd(), buffers)
+    }
+    /// Receive a datagram and the address it was sent from.
+    ///
+    /// Unlike `recv_from()` of std and mio, this supports abstract addresses.
+    /// File descriptors sent with the datagram are not received.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::os::unix::net::UnixDatagram;
+    /// use uds::{UnixDatagramExt, UnixSocketAddr};
+    ///
+    /// let addr = UnixSocketAddr::new_unique().unwrap();
+    /// let receiver = UnixDatagram::bind_unix_addr(addr).unwrap();
+    /// let sender = UnixDatagram::unbound().unwrap();
+    /// sender.send_to_unix_addr(b"hi", addr).unwrap();
+    /// let mut buf = [0; 10];
+    /// let (len, from) = receiver.recv_from_unix_addr(&mut buf).unwrap();
+    /// assert_eq!(&buf[..len], b"hi");
+    /// assert!(from.is_unnamed());
+    /// # if let Some(path) = addr.as_pathname() { let _ = std::fs::remove_file(path); }
+    /// ```
+    fn recv_from_unix_addr(&self,  buf: &mut[u8]) -> Result<(usize, UnixSocketAddr), io::Error> {
+        let mut addr = UnixSocketAddr::default();
+        let mut buffers = [IoSliceMut::new(buf)];
+        recv_ancillary(self.as_raw_fd(), Some(&mut addr), 0, &mut buffers, &mut[])
+            .map(|(len, _)| (len, addr) )
+    }
+    /// Read the next datagram and the address it was sent from,
+    /// without removing it from the queue. (`MSG_PEEK`)
+    ///
+    /// File descriptors sent with the datagram are not received.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::os::unix::net::UnixDatagram;
+    /// use uds::{UnixDatagramExt, UnixSocketAddr};
+    ///
+    /// let addr = UnixSocketAddr::new_unique().unwrap();
+    /// let dispatcher = UnixDatagram::unbound().unwrap();
+    /// dispatcher.bind_to_unix_addr(addr).unwrap();
+    /// let client = UnixDatagram::unbound().unwrap();
+    /// client.send_fds_to(b"job:42", &[], addr).unwrap();
+    /// let mut header = [0; 4];
+    /// let (len, _from) = dispatcher.peek_from_unix_addr(&mut header).unwrap();
+    /// assert_eq!(&header[..len], b"job:");
+    /// assert_eq!(dispatcher.recv(&mut[0; 10]).unwrap(), 6);
+    /// # if let Some(path) = addr.as_pathname() { let _ = std::fs::remove_file(path); }
+    /// ```
+    fn peek_from_unix_addr(&self,  buf: &mut[u8]) -> Result<(usize, UnixSocketAddr), io::Error> {
+        let mut addr = UnixSocketAddr::default();
+        let mut buffers = [IoSliceMut::new(buf)];
+        recv_ancillary(self.as_raw_fd(), Some(&mut addr), MSG_PEEK, &mut buffers, &mut[])
+            .map(|(len, _)| (len, addr) )
     }
 
-    fn send_fds_to(&self,  datagram: &[u8],  fds: &[RawFd],  addr: &UnixSocketAddr)
+    /// Send a datagram to an address,
+    /// which doesn't require the socket to be connected.
+    fn send_to_unix_addr<A: ToUnixSocketAddr>(&self,  datagram: &[u8],  addr: A)
+    -> Result<usize, io::Error> {
+        let addr = addr.to_unix_addr()?;
+        send_ancillary(self.as_raw_fd(), Some(&addr), 0, &[IoSlice::new(datagram)], &[], None)
+    }
+    /// Send a datagram with file descriptors to an address,
+    /// which doesn't require the socket to be connected.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(any(target_os="illumos", target_os="solaris")), doc="```")]
+    #[cfg_attr(any(target_os="illumos", target_os="solaris"), doc="```no_run")]
+    /// use std::os::unix::net::UnixDatagram;
+    /// use std::os::unix::io::AsRawFd;
+    /// use uds::{UnixDatagramExt, UnixSocketAddr};
+    ///
+    /// let addr = UnixSocketAddr::new_unique().unwrap();
+    /// let receiver = UnixDatagram::unbound().unwrap();
+    /// receiver.bind_to_unix_addr(addr).unwrap();
+    /// let sender = UnixDatagram::unbound().unwrap();
+    /// sender.send_fds_to(b"fd", &[sender.as_raw_fd()], addr).unwrap();
+    /// let mut fd_buf = [-1; 2];
+    /// let (len, fds, _) = receiver.recv_fds_from(&mut[0; 10], &mut fd_buf).unwrap();
+    /// assert_eq!((len, fds), (2, 1));
+    /// # unsafe { libc::close(fd_buf[0]) };
+    /// # if let Some(path) = addr.as_pathname() { let _ = std::fs::remove_file(path); }
+    /// ```
+    fn send_fds_to<A: ToUnixSocketAddr>(&self,  datagram: &[u8],  fds: &[RawFd],  addr: A)
     -> Result<usize, io::Error> {
-        send_ancillary(self.as_raw_fd(), Some(addr), 0, &[IoSlice::new(datagram)], fds, None)
+        let addr = addr.to_unix_addr()?;
+        send_ancillary(self.as_raw_fd(), Some(&addr), 0, &[IoSlice::new(datagram)], fds, None)
     }
     fn send_fds(&self,  datagram: &[u8],  fds: &[RawFd]) -> Result<usize, io::Error> {
         send_ancillary(self.as_raw_fd(), None, 0, &[IoSlice::new(datagram)], fds, None)
     }
+    /// Send a datagram with explicit credentials (`SCM_CREDENTIALS`)
+    /// to the connected address.
+    ///
+    /// Credentials other than those of this process require privileges,
+    /// see [`SendCredentials::Custom`](enum.SendCredentials.html#variant.Custom).
+    #[cfg(any(target_os="linux", target_os="android"))]
+    fn send_with_credentials(&self,  datagram: &[u8],  creds: SendCredentials)
+    -> Result<usize, io::Error> {
+        send_ancillary(self.as_raw_fd(), None, 0, &[IoSlice::new(datagram)], &[], Some(creds))
+    }
+    /// Send a datagram with file descriptors from values of any type that
+    /// implements `AsFd`.
+    fn send_as_fds<I>(&self,  datagram: &[u8],  fds: I) -> Result<usize, io::Error>
+    where I: IntoIterator, I::Item: AsFd {
+        send_as_fds(self.as_raw_fd(), None, 0, &[IoSlice::new(datagram)], fds)
+    }
+    /// Enable or disable receiving the credentials of the sender with every
+    /// datagram (`SO_PASSCRED` on Linux and Android, `LOCAL_CREDS` on
+    /// FreeBSD and NetBSD).
+    ///
+    /// The kernel attaches the credentials of the sending process to
+    /// every datagram when this is enabled, even if the sender doesn't send
+    /// them explicitly.
+    /// Linux provides the pid, uid and gid, while FreeBSD and NetBSD
+    /// provide the real and effective uid and gid and the groups,
+    /// and NetBSD also the pid.
+    #[cfg(any(target_os="linux", target_os="android"))]
+    fn set_receive_credentials(&self,  receive: bool) -> Result<(), io::Error> {
+        set_passcred(self.as_raw_fd(), receive)
+    }
+    #[cfg(any(target_os="freebsd", target_os="netbsd"))]
+    fn set_receive_credentials(&self,  receive: bool) -> Result<(), io::Error> {
+        set_local_creds(self.as_raw_fd(), receive)
+    }
+    /// Receive a datagram, the address it was sent from, and the credentials
+    /// of the process that sent it.
+    ///
+    /// The credentials are `None` unless receiving credentials has been
+    /// enabled with `set_receive_credentials()`.
+    /// Any file descriptors sent with the datagram are closed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::os::unix::net::UnixDatagram;
+    /// use uds::UnixDatagramExt;
+    ///
+    /// let (a, b) = UnixDatagram::pair().unwrap();
+    /// b.set_receive_credentials(true).unwrap();
+    /// a.send(b"who am I?").unwrap();
+    /// let mut buf = [0; 20];
+    /// let (len, _, creds) = b.recv_from_with_credentials(&mut buf).unwrap();
+    /// assert_eq!(&buf[..len], b"who am I?");
+    /// assert_eq!(creds.unwrap().real_or_sent_uid(), unsafe { libc::getuid() });
+    /// ```
+    #[cfg(any(target_os="linux", target_os="android", target_os="freebsd", target_os="netbsd"))]
+    fn recv_from_with_credentials(&self,  buf: &mut[u8])
+    -> Result<(usize, UnixSocketAddr, Option<ReceivedCredentials>), io::Error> {
+        let mut addr = UnixSocketAddr::default();
+        recv_with_credentials(self.as_raw_fd(), Some(&mut addr), buf)
+            .map(|(len, _, creds)| (len, addr, creds) )
+    }
+    /// Enable or disable receiving a timestamp of when the kernel received
+    /// every datagram.
+    ///
+    /// This uses `SO_TIMESTAMPNS` on Linux and Android, and `SO_TIMESTAMP`
+    /// (which has microsecond resolution) elsewhere.
+    #[cfg(not(any(target_os="illumos", target_os="solaris")))]
+    fn set_receive_timestamps(&self,  receive: bool) -> Result<(), io::Error> {
+        set_timestamps(self.as_raw_fd(), receive)
+    }
+    /// Receive a datagram, the address it was sent from, and the timestamp of
+    /// when the kernel received it.
+    ///
+    /// The timestamp is `None` unless enabled with `set_receive_timestamps()`.
+    /// Any file descriptors sent with the datagram are closed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::os::unix::net::UnixDatagram;
+    /// use uds::UnixDatagramExt;
+    ///
+    /// let (a, b) = UnixDatagram::pair().unwrap();
+    /// b.set_receive_timestamps(true).unwrap();
+    /// a.send(b"when?").unwrap();
+    /// let (len, _, timestamp) = b.recv_from_with_timestamp(&mut[0; 10]).unwrap();
+    /// assert_eq!(len, 5);
+    /// assert!(timestamp.is_some());
+    /// ```
+    #[cfg(not(any(target_os="illumos", target_os="solaris")))]
+    fn recv_from_with_timestamp(&self,  buf: &mut[u8])
+    -> Result<(usize, UnixSocketAddr, Option<SystemTime>), io::Error> {
+        let mut addr = UnixSocketAddr::default();
+        recv_with_timestamp(self.as_raw_fd(), Some(&mut addr), buf)
+            .map(|(len, _, timestamp)| (len, addr, timestamp) )
+    }
+    /// Receive a datagram together with the address it was sent from,
+    /// up to `max_fds` file descriptors and any credentials or timestamp
+    /// attached to it.
+    ///
+    /// Credentials and timestamps are only received if enabled with
+    /// `set_receive_credentials()` and `set_receive_timestamps()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::os::unix::net::UnixDatagram;
+    /// use std::os::unix::io::AsRawFd;
+    /// use uds::UnixDatagramExt;
+    ///
+    /// let (a, b) = UnixDatagram::pair().unwrap();
+    /// a.send_fds(b"fd", &[a.as_raw_fd()]).unwrap();
+    /// let received = b.recv_full(&mut[0; 10], 1).unwrap();
+    /// assert_eq!(received.len(), 2);
+    /// assert!(received.from().is_unnamed());
+    /// assert_eq!(received.fds().len(), 1);
+    /// ```
+    fn recv_full(&self,  buf: &mut[u8],  max_fds: usize) -> Result<ReceivedMessage, io::Error> {
+        recv_full(self.as_raw_fd(), buf, max_fds)
+    }
+    /// Receive a datagram, and close the file descriptors sent with it that
+    /// `policy` doesn't accept.
+    ///
+    /// See [`UnixSeqpacketConn::recv_with_fd_policy()`](struct.UnixSeqpacketConn.html#method.recv_with_fd_policy)
+    /// for details.
+    fn recv_with_fd_policy(&self,  buf: &mut[u8],  policy: FdPolicy)
+    -> Result<ReceivedMessage, io::Error> {
+        recv_with_fd_policy(self.as_raw_fd(), buf, policy)
+    }
+    /// Send a big payload in a sealed memfd to the connected address.
+    ///
+    /// See [`UnixSeqpacketConn::send_memfd()`](struct.UnixSeqpacketConn.html#method.send_memfd)
+    /// for details.
+    #[cfg(all(feature="memfd", any(target_os="linux", target_os="android")))]
+    fn send_memfd(&self,  payload: &[u8]) -> Result<(), io::Error> {
+        send_memfd(self.as_raw_fd(), 0, payload)
+    }
+    /// Receive a payload sent with `send_memfd()` and map it into memory.
+    ///
+    /// See [`UnixSeqpacketConn::recv_memfd()`](struct.UnixSeqpacketConn.html#method.recv_memfd)
+    /// for details.
+    #[cfg(all(feature="memfd", any(target_os="linux", target_os="android")))]
+    fn recv_memfd(&self) -> Result<MemfdPayload, io::Error> {
+        recv_memfd(self.as_raw_fd())
+    }
+    /// Receive a datagram with file descriptors, and the address it was sent from.
     fn recv_fds_from(&self,  buf: &mut[u8],  fd_buf: &mut[RawFd])
     -> Result<(usize, usize, UnixSocketAddr), io::Error> {
         let mut addr = UnixSocketAddr::default();
@@ -187,6 +902,28 @@ pub trait UnixDatagramExt: AsRawFd + FromRawFd + Sized {
         recv_fds(self.as_raw_fd(), None, &mut[IoSliceMut::new(buf)], fd_buf)
             .map(|(bytes, _, fds)| (bytes, fds) )
     }
+    /// Receive bytes and up to `max_fds` file descriptors,
+    /// which are closed when dropped.
+    fn recv_owned_fds(&self,  buf: &mut[u8],  max_fds: usize)
+    -> Result<(usize, Vec<OwnedFd>), io::Error> {
+        recv_owned_fds(self.as_raw_fd(), None, &mut[IoSliceMut::new(buf)], max_fds, true)
+            .map(|(bytes, _, fds)| (bytes, fds) )
+    }
+    /// Like `recv_owned_fds()`, but fails with an error of kind
+    /// `InvalidData` and closes all received file descriptors if more than
+    /// `max_fds` were sent.
+    fn recv_fds_limited(&self,  buf: &mut[u8],  max_fds: usize)
+    -> Result<(usize, Vec<OwnedFd>), io::Error> {
+        recv_fds_limited(self.as_raw_fd(), None, &mut[IoSliceMut::new(buf)], max_fds)
+            .map(|(bytes, _, fds)| (bytes, fds) )
+    }
+    /// Like `recv_owned_fds()`, but without making the received
+    /// file descriptors close-on-exec.
+    fn recv_inheritable_fds(&self,  buf: &mut[u8],  max_fds: usize)
+    -> Result<(usize, Vec<OwnedFd>), io::Error> {
+        recv_owned_fds(self.as_raw_fd(), None, &mut[IoSliceMut::new(buf)], max_fds, false)
+            .map(|(bytes, _, fds)| (bytes, fds) )
+    }
 
     /// Get the credentials of the process that created the socket pair this socket is one end of.
     ///
@@ -215,7 +952,149 @@ pub trait UnixDatagramExt: AsRawFd + FromRawFd + Sized {
 impl UnixDatagramExt for UnixDatagram {}
 
 #[cfg(feature="mio-uds")]
-impl UnixDatagramExt for mio_uds::UnixDatagram {}
+impl UnixDatagramExt for mio_uds::UnixDatagram {
+    fn bind_unix_addr<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error> {
+        let socket = Socket::bound(SOCK_DGRAM, true, &addr.to_unix_addr()?)?;
+        Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
+    }
+}
 
 #[cfg(feature="mio_07")]
-impl UnixDatagramExt for mio_07::net::UnixDatagram {}
+impl UnixDatagramExt for mio_07::net::UnixDatagram {
+    fn bind_unix_addr<A: ToUnixSocketAddr>(addr: A) -> Result<Self, io::Error> {
+        let socket = Socket::bound(SOCK_DGRAM, true, &addr.to_unix_addr()?)?;
+        Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
+    }
+}
+
+/// Methods shared by blocking and nonblocking seqpacket connections,
+/// for code that should work with either.
+///
+/// # Examples
+///
+#[cfg_attr(not(target_vendor="apple"), doc="```")]
+#[cfg_attr(target_vendor="apple", doc="```no_run")]
+/// use uds::{UnixSeqpacketConnExt, UnixSeqpacketConn};
+///
+/// fn echo<C: UnixSeqpacketConnExt>(conn: &C) -> std::io::Result<()> {
+///     let mut buf = [0; 100];
+///     let (len, _) = UnixSeqpacketConnExt::recv(conn, &mut buf)?;
+///     UnixSeqpacketConnExt::send(conn, &buf[..len])?;
+///     Ok(())
+/// }
+///
+/// let (a, b) = UnixSeqpacketConn::pair().unwrap();
+/// a.send(b"echo").unwrap();
+/// echo(&b).unwrap();
+/// assert_eq!(a.recv(&mut[0; 10]).unwrap(), (4, false));
+/// ```
+pub trait UnixSeqpacketConnExt: AsRawFd {
+    /// Get the address of this side of the connection.
+    fn local_unix_addr(&self) -> Result<UnixSocketAddr, io::Error>;
+    /// Get the address of the other side of the connection.
+    fn peer_unix_addr(&self) -> Result<UnixSocketAddr, io::Error>;
+    /// Get information about the process of the peer when the connection
+    /// was established.
+    fn initial_peer_credentials(&self) -> Result<ConnCredentials, io::Error>;
+    /// Get the supplementary groups of the peer process when the connection
+    /// was established.
+    fn initial_peer_groups(&self) -> Result<Vec<u32>, io::Error>;
+    /// Get the security label of the peer process when the connection was
+    /// established (`SO_PEERSEC`), without any trailing NUL.
+    #[cfg(any(target_os="linux", target_os="android"))]
+    fn peer_security_label(&self) -> Result<Vec<u8>, io::Error>;
+    /// Get a pidfd referring to the peer process when the connection was
+    /// established (`SO_PEERPIDFD`, Linux 6.5 and later).
+    #[cfg(any(target_os="linux", target_os="android"))]
+    fn peer_pidfd(&self) -> Result<OwnedFd, io::Error>;
+    /// Send a packet to the peer.
+    fn send(&self,  packet: &[u8]) -> Result<usize, io::Error>;
+    /// Receive a packet from the peer.
+    ///
+    /// The returned `bool` indicates whether the packet was truncated due to
+    /// too short buffer.
+    fn recv(&self,  buffer: &mut[u8]) -> Result<(usize, bool), io::Error>;
+    /// Send a packet assembled from multiple byte slices.
+    fn send_vectored(&self,  slices: &[IoSlice]) -> Result<usize, io::Error>;
+    /// Read a packet into multiple buffers.
+    ///
+    /// The returned `bool` indicates whether the packet was truncated due to
+    /// too short buffers.
+    fn recv_vectored(&self,  buffers: &mut[IoSliceMut]) -> Result<(usize, bool), io::Error>;
+    /// Send a packet with associated file descriptors.
+    fn send_fds(&self,  bytes: &[u8],  fds: &[RawFd]) -> Result<usize, io::Error>;
+    /// Receive a packet and associated file descriptors.
+    ///
+    /// Returns the number of bytes received, whether the packet was
+    /// truncated and the number of file descriptors received.
+    fn recv_fds(&self,  byte_buffer: &mut[u8],  fd_buffer: &mut[RawFd])
+    -> Result<(usize, bool, usize), io::Error>;
+    /// Shut down the read, write, or both halves of this connection.
+    fn shutdown(&self,  how: Shutdown) -> Result<(), io::Error>;
+    /// Get and clear the value of the `SO_ERROR` option.
+    fn take_error(&self) -> Result<Option<io::Error>, io::Error>;
+    /// Get the number of bytes waiting to be received (`FIONREAD`).
+    fn bytes_available(&self) -> Result<usize, io::Error>;
+    /// Get the largest packet that can be sent.
+    fn max_send_size(&self) -> Result<usize, io::Error>;
+}
+
+/// Implement `UnixSeqpacketConnExt` by delegating to the inherent methods.
+macro_rules! impl_seqpacket_conn_ext {($type:ty) => {
+    impl UnixSeqpacketConnExt for $type {
+        fn local_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+            <$type>::local_unix_addr(self)
+        }
+        fn peer_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+            <$type>::peer_unix_addr(self)
+        }
+        fn initial_peer_credentials(&self) -> Result<ConnCredentials, io::Error> {
+            <$type>::initial_peer_credentials(self)
+        }
+        fn initial_peer_groups(&self) -> Result<Vec<u32>, io::Error> {
+            <$type>::initial_peer_groups(self)
+        }
+        #[cfg(any(target_os="linux", target_os="android"))]
+        fn peer_security_label(&self) -> Result<Vec<u8>, io::Error> {
+            <$type>::peer_security_label(self)
+        }
+        #[cfg(any(target_os="linux", target_os="android"))]
+        fn peer_pidfd(&self) -> Result<OwnedFd, io::Error> {
+            <$type>::peer_pidfd(self)
+        }
+        fn send(&self,  packet: &[u8]) -> Result<usize, io::Error> {
+            <$type>::send(self, packet)
+        }
+        fn recv(&self,  buffer: &mut[u8]) -> Result<(usize, bool), io::Error> {
+            <$type>::recv(self, buffer)
+        }
+        fn send_vectored(&self,  slices: &[IoSlice]) -> Result<usize, io::Error> {
+            <$type>::send_vectored(self, slices)
+        }
+        fn recv_vectored(&self,  buffers: &mut[IoSliceMut]) -> Result<(usize, bool), io::Error> {
+            <$type>::recv_vectored(self, buffers)
+        }
+        fn send_fds(&self,  bytes: &[u8],  fds: &[RawFd]) -> Result<usize, io::Error> {
+            <$type>::send_fds(self, bytes, fds)
+        }
+        fn recv_fds(&self,  byte_buffer: &mut[u8],  fd_buffer: &mut[RawFd])
+        -> Result<(usize, bool, usize), io::Error> {
+            <$type>::recv_fds(self, byte_buffer, fd_buffer)
+        }
+        fn shutdown(&self,  how: Shutdown) -> Result<(), io::Error> {
+            <$type>::shutdown(self, how)
+        }
+        fn take_error(&self) -> Result<Option<io::Error>, io::Error> {
+            <$type>::take_error(self)
+        }
+        fn bytes_available(&self) -> Result<usize, io::Error> {
+            <$type>::bytes_available(self)
+        }
+        fn max_send_size(&self) -> Result<usize, io::Error> {
+            <$type>::max_send_size(self)
+        }
+    }
+}}
+
+impl_seqpacket_conn_ext!{UnixSeqpacketConn}
+impl_seqpacket_conn_ext!{NonblockingUnixSeqpacketConn}
@@ -0,0 +1,265 @@
+use std::io::{self, ErrorKind};
+use std::os::unix::io::{AsFd, AsRawFd, FromRawFd, OwnedFd};
+
+use io_uring::{IoUring, opcode, squeue, types};
+use libc::{sockaddr, SOCK_CLOEXEC, ECANCELED, EBUSY};
+
+use crate::addr::{UnixSocketAddr, ToUnixSocketAddr};
+use crate::helpers::MSG_NOSIGNAL;
+
+/// Used for the multishot accept, to not collide with batch indexes.
+const ACCEPT_USER_DATA: u64 = u64::MAX;
+const CANCEL_USER_DATA: u64 = u64::MAX - 1;
+
+/// Batched sends, receives and accepts through io_uring,
+/// where one system call submits many operations.
+///
+/// The operations work on any unix domain socket, such as this crate's
+/// seqpacket types and std's datagram sockets, blocking or not:
+/// Each method waits for all its operations to complete before returning,
+/// and io_uring waits for sockets to become ready by itself.
+///
+/// The sends or receives of a batch are linked so that they happen in order,
+/// and if one fails the remaining ones are canceled with `ECANCELED`.
+/// Batches bigger than the submission queue are split.
+///
+/// Requires the `io-uring` feature and Linux 5.6,
+/// or newer for some of the methods.
+///
+/// # Examples
+///
+/// ```
+/// use uds::{UnixSeqpacketConn, UringBatch};
+///
+/// let (a, b) = UnixSeqpacketConn::pair().unwrap();
+/// let mut uring = UringBatch::new(32).unwrap();
+/// let sent = uring.send_all(&a, &[b"one", b"two", b"three"]).unwrap();
+/// assert_eq!(sent.into_iter().map(Result::unwrap).collect::<Vec<_>>(), [3, 3, 5]);
+///
+/// let mut storage = [[0u8; 8]; 3];
+/// let mut buffers = storage.iter_mut().map(|b| &mut b[..] ).collect::<Vec<_>>();
+/// let received = uring.recv_all(&b, &mut buffers).unwrap();
+/// assert_eq!(*received[2].as_ref().unwrap(), 5);
+/// assert_eq!(&buffers[2][..5], b"three");
+/// ```
+pub struct UringBatch {
+    ring: IoUring,
+}
+
+impl UringBatch {
+    /// Create an io_uring with room for `entries` queued operations.
+    pub fn new(entries: u32) -> Result<Self, io::Error> {
+        Ok(UringBatch { ring: IoUring::new(entries)? })
+    }
+
+    /// Submit `entries`, linked together in chunks that fit in the submission
+    /// queue, and wait for all of them to complete.
+    ///
+    /// If waiting fails, the entries that haven't completed are canceled,
+    /// and this waits for them to complete before returning the error,
+    /// so that the kernel is done with any memory they point to.
+    ///
+    /// # Safety
+    ///
+    /// Any memory the entries point to must be valid until this returns.
+    unsafe fn run(&mut self,  entries: Vec<squeue::Entry>) -> Result<Vec<i32>, io::Error> {
+        let mut results = vec![0; entries.len()];
+        let capacity = self.ring.submission().capacity();
+        for (chunk_n, chunk) in entries.chunks(capacity).enumerate() {
+            let offset = chunk_n * capacity;
+            {
+                let mut submission = self.ring.submission();
+                for (i, entry) in chunk.iter().enumerate() {
+                    let mut entry = entry.clone().user_data((offset + i) as u64);
+                    if i + 1 != chunk.len() {
+                        entry = entry.flags(squeue::Flags::IO_LINK);
+                    }
+                    // cannot fail as the queue is empty and the chunk fits
+                    submission.push(&entry).expect("submission queue is full");
+                }
+            }
+            let mut completed = vec![false; chunk.len()];
+            let mut remaining = chunk.len();
+            let mut error = None;
+            // index of the next entry to cancel after an error
+            let mut to_cancel = 0;
+            while remaining > 0 {
+                if error.is_some() {
+                    let mut submission = self.ring.submission();
+                    while to_cancel < chunk.len() && !submission.is_full() {
+                        if !completed[to_cancel] {
+                            let cancel = opcode::AsyncCancel::new((offset + to_cancel) as u64)
+                                .build()
+                                .user_data(CANCEL_USER_DATA);
+                            submission.push(&cancel).expect("submission queue is full");
+                        }
+                        to_cancel += 1;
+                    }
+                }
+                // don't wait while there are cancels left to submit,
+                // as the entries might never complete by themselves
+                let wait = if to_cancel < chunk.len() && error.is_some() {0} else {remaining};
+                match self.ring.submit_and_wait(wait) {
+                    Ok(_) => {}
+                    Err(ref e) if can_retry(e) => {}
+                    // the kernel might still use the memory, so returning
+                    // or unwinding would be unsound
+                    Err(_) if error.is_some() => std::process::abort(),
+                    Err(e) => error = Some(e),
+                }
+                for completion in self.ring.completion() {
+                    let user_data = completion.user_data();
+                    // also skips leftovers from accept_multiple()
+                    if user_data < offset as u64 || user_data >= (offset + chunk.len()) as u64 {
+                        continue;
+                    }
+                    let index = user_data as usize;
+                    results[index] = completion.result();
+                    completed[index - offset] = true;
+                    remaining -= 1;
+                }
+            }
+            if let Some(error) = error {
+                return Err(error);
+            }
+        }
+        Ok(results)
+    }
+
+    /// Send packets or datagrams on a connected socket.
+    ///
+    /// Returns the outcome of each send.
+    pub fn send_all<S: AsFd>(&mut self,  socket: &S,  packets: &[&[u8]])
+    -> Result<Vec<Result<usize, io::Error>>, io::Error> {
+        let fd = types::Fd(socket.as_fd().as_raw_fd());
+        let entries = packets.iter().map(|packet| {
+            Ok(opcode::Send::new(fd, packet.as_ptr(), buffer_len(packet.len())?)
+                .flags(MSG_NOSIGNAL)
+                .build())
+        }).collect::<Result<Vec<_>, io::Error>>()?;
+        let results = unsafe { self.run(entries)? };
+        Ok(results.into_iter().map(outcome).collect())
+    }
+
+    /// Send datagrams to an address, which doesn't require the socket to be
+    /// connected.
+    ///
+    /// Requires Linux 6.0.
+    pub fn send_all_to<S: AsFd,  A: ToUnixSocketAddr>
+    (&mut self,  socket: &S,  datagrams: &[&[u8]],  addr: A)
+    -> Result<Vec<Result<usize, io::Error>>, io::Error> {
+        let addr: UnixSocketAddr = addr.to_unix_addr()?;
+        let (raw_addr, addr_len) = addr.as_raw_general();
+        let fd = types::Fd(socket.as_fd().as_raw_fd());
+        let entries = datagrams.iter().map(|datagram| {
+            Ok(opcode::Send::new(fd, datagram.as_ptr(), buffer_len(datagram.len())?)
+                .flags(MSG_NOSIGNAL)
+                .dest_addr(raw_addr as *const sockaddr)
+                .dest_addr_len(addr_len)
+                .build())
+        }).collect::<Result<Vec<_>, io::Error>>()?;
+        let results = unsafe { self.run(entries)? };
+        Ok(results.into_iter().map(outcome).collect())
+    }
+
+    /// Receive one packet or datagram into each buffer, in order.
+    ///
+    /// This waits until all buffers have been filled, or the peer has closed
+    /// the connection, which makes the remaining receives complete with
+    /// length zero.
+    /// Returns the outcome of each receive, where packets that didn't fit
+    /// in their buffer are truncated.
+    pub fn recv_all<S: AsFd>(&mut self,  socket: &S,  buffers: &mut[&mut[u8]])
+    -> Result<Vec<Result<usize, io::Error>>, io::Error> {
+        let fd = types::Fd(socket.as_fd().as_raw_fd());
+        let entries = buffers.iter_mut().map(|buffer| {
+            Ok(opcode::Recv::new(fd, buffer.as_mut_ptr(), buffer_len(buffer.len())?)
+                .flags(MSG_NOSIGNAL)
+                .build())
+        }).collect::<Result<Vec<_>, io::Error>>()?;
+        let results = unsafe { self.run(entries)? };
+        Ok(results.into_iter().map(outcome).collect())
+    }
+
+    /// Accept `count` connections with a single multishot accept.
+    ///
+    /// The connections are blocking and close-on-exec.
+    /// Returns the outcome of each accept, and stops early if the
+    /// multishot accept ends due to an error.
+    /// More than `count` connections can be returned, if more were accepted
+    /// before the multishot accept was canceled.
+    ///
+    /// Requires Linux 5.19.
+    pub fn accept_multiple<S: AsFd>(&mut self,  listener: &S,  count: usize)
+    -> Result<Vec<Result<OwnedFd, io::Error>>, io::Error> {
+        let mut accepted = Vec::with_capacity(count);
+        if count == 0 {
+            return Ok(accepted);
+        }
+        let fd = types::Fd(listener.as_fd().as_raw_fd());
+        let accept = opcode::AcceptMulti::new(fd)
+            .flags(SOCK_CLOEXEC)
+            .build()
+            .user_data(ACCEPT_USER_DATA);
+        // cannot fail as the queue is always emptied by submitting
+        unsafe { self.ring.submission().push(&accept) }.expect("submission queue is full");
+        let mut active = true;
+        let mut canceling = false;
+        let mut cancel_completed = false;
+        while active || (canceling && !cancel_completed) {
+            if accepted.len() >= count && !canceling {
+                let cancel = opcode::AsyncCancel::new(ACCEPT_USER_DATA)
+                    .build()
+                    .user_data(CANCEL_USER_DATA);
+                unsafe { self.ring.submission().push(&cancel) }.expect("submission queue is full");
+                canceling = true;
+            }
+            match self.ring.submit_and_wait(1) {
+                Ok(_) => {}
+                Err(ref e) if can_retry(e) => {}
+                Err(e) => return Err(e),
+            }
+            for completion in self.ring.completion() {
+                if completion.user_data() == CANCEL_USER_DATA {
+                    cancel_completed = true;
+                    continue;
+                } else if completion.user_data() != ACCEPT_USER_DATA {
+                    continue;
+                }
+                let result = completion.result();
+                if result >= 0 {
+                    accepted.push(Ok(unsafe { OwnedFd::from_raw_fd(result) }));
+                } else if result != -ECANCELED && accepted.len() < count {
+                    accepted.push(Err(io::Error::from_raw_os_error(-result)));
+                }
+                if !io_uring::cqueue::more(completion.flags()) {
+                    active = false;
+                }
+            }
+        }
+        Ok(accepted)
+    }
+}
+
+/// Convert a buffer length to what io_uring takes.
+fn buffer_len(len: usize) -> Result<u32, io::Error> {
+    if len > u32::MAX as usize {
+        return Err(io::Error::new(ErrorKind::InvalidInput, "buffer is too big for io_uring"));
+    }
+    Ok(len as u32)
+}
+
+/// Check whether `submit_and_wait()` failed for a reason that goes away
+/// by trying again, after reaping completions.
+fn can_retry(error: &io::Error) -> bool {
+    error.kind() == ErrorKind::Interrupted || error.raw_os_error() == Some(EBUSY)
+}
+
+/// Convert a completion result to an `io::Result`.
+fn outcome(result: i32) -> Result<usize, io::Error> {
+    if result < 0 {
+        Err(io::Error::from_raw_os_error(-result))
+    } else {
+        Ok(result as usize)
+    }
+}
@@ -1,17 +1,21 @@
+#![allow(clippy::expect_fun_call, clippy::manual_repeat_n, clippy::manual_str_repeat)]
+
 extern crate uds;
 extern crate libc;
 
-use std::os::unix::net::{UnixListener, UnixStream};
+use std::os::unix::net::{UnixListener, UnixStream, UnixDatagram};
+use std::convert::TryFrom;
 use std::io::ErrorKind::*;
 use std::fs::remove_file;
+use std::os::unix::ffi::OsStrExt;
 
-use uds::{UnixSocketAddr, UnixSocketAddrRef};
-use uds::{UnixListenerExt, UnixStreamExt};
+use uds::{UnixSocketAddr, UnixSocketAddrRef, AddrNamespace};
+use uds::{UnixListenerExt, UnixStreamExt, UnixDatagramExt};
 
 #[cfg(any(target_os="linux", target_os="android"))]
 #[test]
 fn unspecified_creates_abstract() {
-    let listener = UnixListener::bind_unix_addr(&UnixSocketAddr::new_unspecified())
+    let listener = UnixListener::bind_unix_addr(UnixSocketAddr::new_unspecified())
         .expect("bind to unspecified (abstract) address");
     let listener_addr = listener.local_unix_addr().expect("get auto-bound address");
     assert!(listener_addr.is_abstract());
@@ -24,8 +28,8 @@ fn unspecified_creates_abstract() {
         _ => unreachable!()
     }
     let conn = UnixStream::connect_from_to_unix_addr(
-        &UnixSocketAddr::new_unspecified(),
-        &listener_addr
+        UnixSocketAddr::new_unspecified(),
+        listener_addr
     ).expect(&format!("connect from unspecified (abstract) addr to autobound addr {:?}", listener_addr));
     assert!(conn.local_unix_addr().unwrap().is_abstract());
 }
@@ -45,7 +49,7 @@ fn empty_abstract() {
             .expect("create empty abstract address");
         assert!(empty_addr.is_abstract());
         assert_eq!(empty_addr.as_ref(), UnixSocketAddrRef::Abstract(b""));
-        let listener = UnixListener::bind_unix_addr(&empty_addr)
+        let listener = UnixListener::bind_unix_addr(empty_addr)
             .expect("bind to empty abstract address");
         let retrieved_local_addr = listener.local_unix_addr()
             .expect("get local empty abstract addr");
@@ -65,13 +69,13 @@ fn max_abstract_name() {
         .expect("create abstract address with max length");
     assert_eq!(max_addr.as_ref(), UnixSocketAddrRef::Abstract(&vec![0; max]));
 
-    let listener = UnixListener::bind_unix_addr(&max_addr)
+    let listener = UnixListener::bind_unix_addr(max_addr)
         .expect("create socket with max abstract name length");
     let retrieved_local_addr = listener.local_unix_addr()
         .expect("get local max length abstract addr");
     assert_eq!(retrieved_local_addr, max_addr);
 
-    let conn = UnixStream::connect_to_unix_addr(&max_addr)
+    let conn = UnixStream::connect_to_unix_addr(max_addr)
         .expect("connect to max length abstract addr");
     let retrieved_peer_addr = conn.peer_unix_addr()
         .expect("get local max length abstract addr");
@@ -97,7 +101,7 @@ fn max_regular_path_addr() {
 
     let _ = remove_file(&max_regular_path);
 
-    let listener = UnixListener::bind_unix_addr(&max_regular_addr)
+    let listener = UnixListener::bind_unix_addr(max_regular_addr)
         .expect("create socket with max regular path length");
     let addr_from_os = listener.local_unix_addr().expect("get local max regular length path addr");
     assert_eq!(addr_from_os.as_ref(), UnixSocketAddrRef::Path(max_regular_path.as_ref()));
@@ -127,7 +131,7 @@ fn max_path_addr() {// std fails this!
 
     let _ = remove_file(&max_path);
 
-    let listener = UnixListener::bind_unix_addr(&max_addr)
+    let listener = UnixListener::bind_unix_addr(max_addr)
         .expect("create socket with max length path addr");
     let addr_from_os = listener.local_unix_addr()
         .expect("get local max length path addr");
@@ -163,3 +167,441 @@ fn too_long_path() {
         InvalidInput
     );
 }
+
+#[test]
+fn display_is_parseable() {
+    let addrs = [
+        UnixSocketAddr::new_unspecified(),
+        UnixSocketAddr::from_path("unnamed").unwrap(),
+        UnixSocketAddr::from_path("@at").unwrap(),
+        UnixSocketAddr::from_path("back\\slash").unwrap(),
+        UnixSocketAddr::from_path("new\nline\t\r\u{1b}").unwrap(),
+        UnixSocketAddr::from_path(std::ffi::OsStr::from_bytes(b"/not/\xffutf8")).unwrap(),
+        UnixSocketAddr::from_path("/ütf8/påth").unwrap(),
+    ];
+    for addr in &addrs {
+        let displayed = addr.to_string();
+        assert!(!displayed.contains(&['\n', '\t', '\r', '\u{1b}'][..]), "{:?} is escaped", displayed);
+        let parsed = displayed.parse::<UnixSocketAddr>()
+            .unwrap_or_else(|e| panic!("parse {:?}: {}", displayed, e));
+        assert_eq!(&parsed, addr, "{:?} round-trips", displayed);
+    }
+    assert_eq!(addrs[1].to_string(), "\\x75nnamed");
+    assert_eq!(addrs[2].to_string(), "\\@at");
+    assert_eq!(addrs[3].to_string(), "back\\\\slash");
+
+    if UnixSocketAddr::has_abstract_addresses() {
+        for name in &[&b""[..], b"@", b"\0\0", b"x\xff\xfe", b"unnamed", b"\\@"] {
+            let addr = UnixSocketAddr::from_abstract(name).unwrap();
+            let parsed = addr.to_string().parse::<UnixSocketAddr>().unwrap();
+            assert_eq!(parsed, addr, "{:?} round-trips", addr.to_string());
+        }
+        assert_eq!("\\0escaped".parse::<UnixSocketAddr>().unwrap().as_ref(),
+            UnixSocketAddrRef::Abstract(b"escaped")
+        );
+    }
+
+    for invalid in &["\\", "trailing\\", "\\q", "\\x", "\\x1", "\\xg0", "nul\\0"] {
+        assert_eq!(invalid.parse::<UnixSocketAddr>().unwrap_err().kind(), InvalidInput);
+    }
+}
+
+#[test]
+fn std_conversions() {
+    let unnamed = UnixSocketAddr::new_unspecified();
+    let std_unnamed = unnamed.to_std().expect("convert unnamed to std");
+    assert!(std_unnamed.is_unnamed());
+    assert_eq!(UnixSocketAddr::from(std_unnamed), unnamed);
+
+    let path = UnixSocketAddr::from_path("std conversion.socket").unwrap();
+    let std_path = path.to_std().expect("convert path to std");
+    assert_eq!(std_path.as_pathname(), Some("std conversion.socket".as_ref()));
+    assert_eq!(UnixSocketAddr::from(std_path), path);
+
+    let max_path = UnixSocketAddr::from_path(&"M".repeat(UnixSocketAddr::max_path_len()-1)).unwrap();
+    assert_eq!(UnixSocketAddr::from(max_path.to_std().unwrap()), max_path);
+
+    if UnixSocketAddr::has_abstract_addresses() {
+        let name = UnixSocketAddr::from_abstract(b"std\0conversion").unwrap();
+        let std_name = std::os::unix::net::SocketAddr::try_from(&name)
+            .expect("convert abstract to std");
+        let socket = UnixDatagram::bind_addr(&std_name).expect("bind to converted abstract address");
+        let local = socket.local_addr().unwrap();
+        assert_eq!(UnixSocketAddr::from(local), name);
+        #[allow(deprecated)]
+        let via_from_std = UnixSocketAddr::from_std(socket.local_addr().unwrap());
+        assert_eq!(via_from_std, Some(name));
+    }
+}
+
+#[test]
+fn const_constructors() {
+    const PATH: UnixSocketAddr = UnixSocketAddr::from_path_const("const.socket");
+    assert_eq!(PATH, UnixSocketAddr::from_path("const.socket").unwrap());
+    assert_eq!(PATH.as_raw().1, UnixSocketAddr::from_path("const.socket").unwrap().as_raw().1);
+    static UNNAMED: UnixSocketAddr = UnixSocketAddr::new_unspecified();
+    assert!(UNNAMED.is_unnamed());
+    #[cfg(any(target_os="linux", target_os="android"))] {
+        static ABSTRACT: UnixSocketAddr = UnixSocketAddr::from_abstract_const(b"const\0abstract");
+        assert_eq!(ABSTRACT, UnixSocketAddr::from_abstract(b"const\0abstract").unwrap());
+    }
+}
+
+#[test]
+fn unique_addresses() {
+    let a = UnixSocketAddr::new_unique().expect("create unique address");
+    let b = UnixSocketAddr::new_unique().expect("create another unique address");
+    assert_ne!(a, b);
+    if UnixSocketAddr::has_abstract_addresses() {
+        assert!(a.is_abstract());
+    } else {
+        assert!(a.is_absolute_path());
+        assert!(!a.as_pathname().unwrap().exists());
+    }
+    let listener = UnixListener::bind_unix_addr(a).expect("bind to unique address");
+    assert_eq!(listener.local_unix_addr().unwrap(), a);
+    if let Some(path) = a.as_pathname() {
+        let _ = remove_file(path);
+    }
+}
+
+#[test]
+fn canonicalize() {
+    let cwd = std::env::current_dir().unwrap();
+    let relative = UnixSocketAddr::from_path("canonicalize.socket").unwrap();
+    let absolute = relative.canonicalize().expect("canonicalize relative path");
+    assert_eq!(absolute.as_pathname(), Some(&*cwd.join("canonicalize.socket")));
+    assert_eq!(absolute.canonicalize().unwrap(), absolute);
+
+    let too_long = "L".repeat(UnixSocketAddr::max_path_len()-1);
+    let too_long = UnixSocketAddr::from_path(&too_long).unwrap();
+    assert_eq!(too_long.canonicalize().unwrap(), too_long);
+
+    let unnamed = UnixSocketAddr::new_unspecified();
+    assert_eq!(unnamed.canonicalize().unwrap(), unnamed);
+}
+
+#[test]
+fn namespace() {
+    let dir = std::env::temp_dir().join("uds-namespace-test");
+    let namespace = AddrNamespace::with_directory(&dir);
+    let addr = namespace.addr("server").expect("create path address in namespace");
+    assert_eq!(addr.as_pathname(), Some(&*dir.join("server")));
+    assert_eq!(namespace.logical_name(&addr), Some(&b"server"[..]));
+    assert_eq!(namespace.logical_name(&UnixSocketAddr::from_path("server").unwrap()), None);
+    assert_eq!(namespace.addr("").unwrap_err().kind(), InvalidInput);
+    assert_eq!(namespace.addr("sub/dir").unwrap_err().kind(), InvalidInput);
+    assert_eq!(namespace.remove("../uds-namespace-test").unwrap_err().kind(), InvalidInput);
+    assert_eq!(namespace.remove("/etc/passwd").unwrap_err().kind(), InvalidInput);
+    let too_long = "T".repeat(UnixSocketAddr::max_path_len());
+    assert_eq!(namespace.addr(&too_long).unwrap_err().kind(), InvalidInput);
+
+    if UnixSocketAddr::has_abstract_addresses() {
+        let namespace = AddrNamespace::new("uds-test");
+        let addr = namespace.addr("server").unwrap();
+        assert_eq!(addr.as_abstract_name(), Some(&b"uds-test/server"[..]));
+        assert_eq!(namespace.logical_name(&addr), Some(&b"server"[..]));
+        let other = AddrNamespace::with_abstract_prefix(b"other:").unwrap();
+        assert_eq!(other.logical_name(&addr), None);
+    } else {
+        assert!(AddrNamespace::with_abstract_prefix(b"uds-test").is_err());
+    }
+}
+
+#[test]
+fn redacted_display() {
+    let unnamed = UnixSocketAddr::new_unspecified();
+    assert_eq!(format!("{:#}", unnamed), "unnamed");
+    let path = UnixSocketAddr::from_path("/run/app.sock").unwrap();
+    assert_eq!(format!("{:#}", path), "/run/app.sock");
+    let path = UnixSocketAddr::from_path("/Users/bob/Library/app.sock").unwrap();
+    assert_eq!(format!("{:#}", path), "~/Library/app.sock");
+    let path = UnixSocketAddr::from_path("/home/not-a-home-dir").unwrap();
+    assert_eq!(format!("{:#}", path), "/home/not-a-home-dir");
+    if let Some(home) = std::env::var_os("HOME") {
+        let path = std::path::Path::new(&home).join("app.sock");
+        if let Ok(path) = UnixSocketAddr::from_path(&path) {
+            assert_eq!(format!("{:#}", path), "~/app.sock");
+        }
+    }
+
+    if UnixSocketAddr::has_abstract_addresses() {
+        let short = UnixSocketAddr::from_abstract(b"short").unwrap();
+        assert_eq!(format!("{:#}", short), "@short");
+        let a = UnixSocketAddr::from_abstract(b"secret-token-1234").unwrap();
+        let b = UnixSocketAddr::from_abstract(b"secret-token-5678").unwrap();
+        let (a, b) = (format!("{:#}", a), format!("{:#}", b));
+        assert!(a.starts_with("@secret...#"));
+        assert!(!a.contains("1234"));
+        assert_ne!(a, b);
+    }
+}
+
+#[test]
+fn as_bytes() {
+    let unnamed = UnixSocketAddr::new_unspecified();
+    assert_eq!(unnamed.as_bytes(), b"");
+    assert!(unnamed.len_bytes() < UnixSocketAddr::from_path("a").unwrap().len_bytes());
+
+    let max_len = "M".repeat(UnixSocketAddr::max_path_len());
+    let max_path = UnixSocketAddr::from_path(&max_len).unwrap();
+    assert_eq!(max_path.as_bytes(), max_len.as_bytes());
+    assert_eq!(max_path.len_bytes(), max_path.as_raw().1 as usize);
+    assert_eq!(max_path, *max_path.as_bytes());
+
+    let path = UnixSocketAddr::from_path("bytes.socket").unwrap();
+    assert_eq!(path.as_bytes(), b"bytes.socket");
+    assert_eq!(path.len_bytes(), max_path.len_bytes() - max_len.len() + b"bytes.socket\0".len());
+
+    if UnixSocketAddr::has_abstract_addresses() {
+        let name = UnixSocketAddr::from_abstract(b"\0").unwrap();
+        assert_eq!(name.as_bytes(), b"\0\0");
+        assert_eq!(name, *name.as_bytes());
+    }
+}
+
+#[test]
+fn from_url() {
+    let path = UnixSocketAddr::from_path("/run/app.sock").unwrap();
+    assert_eq!(UnixSocketAddr::from_url("unix:///run/app.sock").unwrap(), path);
+    assert_eq!(UnixSocketAddr::from_url("unix:/run/app.sock").unwrap(), path);
+    let relative = UnixSocketAddr::from_url("unix:run/app%20name.sock").unwrap();
+    assert_eq!(relative, UnixSocketAddr::from_path("run/app name.sock").unwrap());
+
+    assert_eq!(UnixSocketAddr::from_url("unix:").unwrap_err().kind(), InvalidInput);
+    assert_eq!(UnixSocketAddr::from_url("unix://").unwrap_err().kind(), InvalidInput);
+    assert_eq!(UnixSocketAddr::from_url("unix://host/run/app.sock").unwrap_err().kind(), InvalidInput);
+    assert_eq!(UnixSocketAddr::from_url("unix://localhost").unwrap_err().kind(), InvalidInput);
+    assert_eq!(UnixSocketAddr::from_url("unix:/a%2").unwrap_err().kind(), InvalidInput);
+    assert_eq!(UnixSocketAddr::from_url("unix:/a%00b").unwrap_err().kind(), InvalidInput);
+    assert_eq!(UnixSocketAddr::from_url("tcp://localhost").unwrap_err().kind(), InvalidInput);
+    assert_eq!(UnixSocketAddr::from_url("/run/app.sock").unwrap_err().kind(), InvalidInput);
+
+    let abstract_urls = ["unix-abstract://name", "unix-abstract:name", "unix:@name"];
+    for url in &abstract_urls {
+        if UnixSocketAddr::has_abstract_addresses() {
+            let addr = UnixSocketAddr::from_url(url).unwrap();
+            assert_eq!(addr.as_abstract_name(), Some(&b"name"[..]));
+        } else {
+            assert!(UnixSocketAddr::from_url(url).is_err());
+        }
+    }
+}
+
+#[test]
+fn to_unix_addr() {
+    use std::path::{Path, PathBuf};
+    use std::ffi::OsStr;
+    use uds::ToUnixSocketAddr;
+
+    let path = UnixSocketAddr::from_path("@path").unwrap();
+    assert_eq!(Path::new("@path").to_unix_addr().unwrap(), path);
+    assert_eq!(PathBuf::from("@path").to_unix_addr().unwrap(), path);
+    assert_eq!(OsStr::new("@path").to_unix_addr().unwrap(), path);
+    assert_eq!("./@path".to_unix_addr().unwrap().as_pathname(), Some(Path::new("./@path")));
+    assert_eq!(path.as_ref().to_unix_addr().unwrap(), path);
+    assert_eq!((&&path).to_unix_addr().unwrap(), path);
+    assert!("".to_unix_addr().unwrap().is_unnamed());
+    assert_eq!(String::from("@name").to_unix_addr().is_ok(), UnixSocketAddr::has_abstract_addresses());
+
+    let _ = remove_file("to_unix_addr.socket");
+    let listener = UnixListener::bind_unix_addr("to_unix_addr.socket").expect("bind to str");
+    let _conn = UnixStream::connect_to_unix_addr(Path::new("to_unix_addr.socket"))
+        .expect("connect to Path");
+    assert_eq!(listener.accept_unix_addr().unwrap().1, UnixSocketAddr::new_unspecified());
+    remove_file("to_unix_addr.socket").unwrap();
+}
+
+#[test]
+fn hex_abstract_names() {
+    if UnixSocketAddr::has_abstract_addresses() {
+        for name in &[&b""[..], b"\0", b"x", b"xyz", b"\xde\xad\xbe\xef", b"\\x41"] {
+            let addr = UnixSocketAddr::from_abstract(name).unwrap();
+            let hex = format!("{:x}", addr);
+            assert!(hex.starts_with("@x"));
+            assert_eq!(hex.parse::<UnixSocketAddr>().unwrap(), addr, "{:?} round-trips", hex);
+            let displayed = addr.to_string();
+            assert_eq!(displayed.parse::<UnixSocketAddr>().unwrap(), addr, "{:?} round-trips", displayed);
+        }
+        let xyz = UnixSocketAddr::from_abstract(b"xyz").unwrap();
+        assert_eq!(xyz.to_string(), "@\\x78yz");
+        assert_eq!(format!("{:x}", xyz), "@x78797a");
+        assert_eq!("@x4A4b".parse::<UnixSocketAddr>().unwrap().as_abstract_name(), Some(&b"JK"[..]));
+        for &not_hex in &["@xorg", "@x1", "@xyz", "@x+1", "@x 1", "@x11 "] {
+            let addr = not_hex.parse::<UnixSocketAddr>().unwrap();
+            assert_eq!(addr.as_abstract_name(), Some(&not_hex.as_bytes()[1..]), "{}", not_hex);
+        }
+    }
+    assert_eq!("@x\\q".parse::<UnixSocketAddr>().unwrap_err().kind(), InvalidInput);
+    let path = UnixSocketAddr::from_path("x").unwrap();
+    assert_eq!(format!("{:x}", path), "x");
+}
+
+#[test]
+fn peek_datagram_sender() {
+    let addr = UnixSocketAddr::new_unique().unwrap();
+    let receiver = UnixDatagram::unbound().unwrap();
+    receiver.bind_to_unix_addr(addr).expect("bind receiver");
+    let sender_addr = UnixSocketAddr::new_unique().unwrap();
+    let sender = UnixDatagram::unbound().unwrap();
+    sender.bind_to_unix_addr(sender_addr).expect("bind sender");
+    let unbound_sender = UnixDatagram::unbound().unwrap();
+
+    sender.send_fds_to(b"first", &[], addr).unwrap();
+    unbound_sender.send_fds_to(b"second", &[], addr).unwrap();
+    let mut buf = [0; 10];
+    for _ in 0..2 {
+        let (len, from) = receiver.peek_from_unix_addr(&mut buf).expect("peek");
+        assert_eq!((&buf[..len], from), (&b"first"[..], sender_addr));
+    }
+    assert_eq!(receiver.recv(&mut buf).unwrap(), 5);
+    let (len, from) = receiver.peek_from_unix_addr(&mut buf[..3]).expect("peek partially");
+    assert_eq!(&buf[..len], b"sec");
+    assert!(from.is_unnamed());
+    assert_eq!(receiver.recv(&mut buf).unwrap(), 6);
+
+    for addr in &[addr, sender_addr] {
+        if let Some(path) = addr.as_pathname() {
+            let _ = remove_file(path);
+        }
+    }
+}
+
+#[test]
+fn datagram_string_addresses() {
+    let addr = UnixSocketAddr::new_unique().unwrap().to_string();
+    let receiver = UnixDatagram::bind_unix_addr(&addr).expect("bind to string address");
+    let sender = UnixDatagram::unbound().unwrap();
+    assert_eq!(sender.send_to_unix_addr(b"str", addr.as_str()).expect("send to &str"), 3);
+    assert_eq!(sender.send_to_unix_addr(b"String", &addr).expect("send to String"), 6);
+    let mut buf = [0; 10];
+    assert_eq!(receiver.recv(&mut buf).unwrap(), 3);
+    assert_eq!(receiver.recv(&mut buf).unwrap(), 6);
+    if let Some(path) = receiver.local_unix_addr().unwrap().as_pathname() {
+        let _ = remove_file(path);
+    }
+}
+
+#[test]
+fn listen_with_backlog() {
+    for &backlog in &[1, u32::MAX] {
+        let addr = UnixSocketAddr::new_unique().unwrap();
+        let listener = UnixListener::bind_unix_addr_with_backlog(addr, backlog)
+            .expect("bind and listen with backlog");
+        assert_eq!(listener.local_unix_addr().unwrap(), addr);
+        let _client = UnixStream::connect_to_unix_addr(addr).expect("connect");
+        let (_conn, _) = listener.accept_unix_addr().expect("accept");
+        if let Some(path) = addr.as_pathname() {
+            let _ = remove_file(path);
+        }
+    }
+}
+
+#[cfg(any(target_os="linux", target_os="android"))]
+#[test]
+fn connect_timeout_when_backlog_is_full() {
+    use std::time::{Duration, Instant};
+    let addr = UnixSocketAddr::new_unique().unwrap();
+    let listener = UnixListener::bind_unix_addr_with_backlog(addr, 1).unwrap();
+    let timeout = Duration::from_millis(50);
+    let mut clients = Vec::new();
+    let error = loop {
+        assert!(clients.len() < 10, "backlog is not limited");
+        let started = Instant::now();
+        match UnixStream::connect_to_unix_addr_timeout(addr, timeout) {
+            Ok(client) => clients.push(client),
+            Err(e) => {
+                assert!(started.elapsed() >= timeout);
+                break e;
+            }
+        }
+    };
+    assert_eq!(error.kind(), TimedOut);
+    // the connected clients are blocking
+    assert!(!clients.is_empty());
+    clients[0].set_read_timeout(Some(timeout)).unwrap();
+    assert_eq!(std::io::Read::read(&mut &clients[0], &mut[0]).unwrap_err().kind(), WouldBlock);
+    let err = UnixStream::connect_to_unix_addr_timeout(addr, Duration::from_secs(0)).unwrap_err();
+    assert_eq!(err.kind(), InvalidInput);
+    drop(listener);
+    if let Some(path) = addr.as_pathname() {
+        let _ = remove_file(path);
+    }
+}
+
+#[test]
+fn introspect_any_socket() {
+    use std::os::unix::io::OwnedFd;
+    use uds::introspect;
+
+    let addr = UnixSocketAddr::new_unique().unwrap();
+    let listener = UnixListener::bind_unix_addr(addr).expect("bind stream listener");
+    assert_eq!(introspect::socket_type(&listener).unwrap(), libc::SOCK_STREAM);
+    assert!(introspect::is_listening(&listener).unwrap());
+    assert_eq!(introspect::local_unix_addr(&listener).unwrap(), addr);
+    assert!(introspect::peer_unix_addr(&listener).is_err());
+
+    let (a, _b) = UnixDatagram::pair().unwrap();
+    let raw: OwnedFd = a.into();
+    assert_eq!(introspect::socket_type(&raw).unwrap(), libc::SOCK_DGRAM);
+    assert!(introspect::peer_unix_addr(&raw).unwrap().is_unnamed());
+
+    let file = std::fs::File::open("README.md").unwrap();
+    assert!(introspect::local_unix_addr(&file).is_err());
+    assert!(introspect::socket_type(&file).is_err());
+    if let Some(path) = addr.as_pathname() {
+        let _ = remove_file(path);
+    }
+}
+
+#[test]
+fn take_over_stale_socket_path() {
+    let path = "stale socket.sock";
+    let _ = remove_file(path);
+    drop(UnixListener::bind_unix_addr(path).expect("create socket file"));
+    assert_eq!(UnixListener::bind_unix_addr(path).unwrap_err().kind(), AddrInUse);
+
+    let server = UnixListener::bind_unix_addr_taking_over(path).expect("replace stale socket");
+    let _client = UnixStream::connect_to_unix_addr(path).expect("connect to new server");
+    let err = UnixListener::bind_unix_addr_taking_over(path).expect_err("replace live server");
+    assert_eq!(err.kind(), AddrInUse);
+    let _client = UnixStream::connect_to_unix_addr(path).expect("connect after failed takeover");
+    drop(server);
+    remove_file(path).unwrap();
+
+    std::fs::write(path, b"not a socket").unwrap();
+    let err = UnixListener::bind_unix_addr_taking_over(path).expect_err("replace regular file");
+    assert_eq!(err.kind(), AlreadyExists);
+    assert_eq!(std::fs::read(path).unwrap(), b"not a socket");
+    remove_file(path).unwrap();
+}
+
+#[test]
+fn bound_path_guard() {
+    let path = "bound_path_guard.sock";
+    let _ = remove_file(path);
+    let (socket, guard) = uds::BoundPath::bind(path, UnixDatagram::bind_unix_addr)
+        .expect("bind datagram socket");
+    assert_eq!(guard.path(), Some(std::path::Path::new(path)));
+    drop(guard);
+    assert!(std::fs::symlink_metadata(path).is_err(), "file was removed");
+    drop(socket);
+
+    // binding to an existing file fails without removing it
+    let (listener, mut guard) = uds::BoundPath::bind(path, UnixListener::bind_unix_addr)
+        .expect("bind listener");
+    assert_eq!(uds::BoundPath::bind(path, UnixListener::bind_unix_addr).unwrap_err().kind(), AddrInUse);
+    assert!(std::fs::symlink_metadata(path).is_ok());
+    guard.disarm();
+    drop(guard);
+    assert!(std::fs::symlink_metadata(path).is_ok(), "disarmed guard doesn't remove the file");
+    drop(listener);
+
+    let guard = uds::BoundPath::new(&UnixSocketAddr::new(path).unwrap());
+    assert_eq!(guard.forget().as_deref(), Some(std::path::Path::new(path)));
+    assert!(std::fs::symlink_metadata(path).is_ok(), "forgotten guard doesn't remove the file");
+    remove_file(path).unwrap();
+
+    let unnamed = uds::BoundPath::new(&UnixSocketAddr::new_unspecified());
+    assert!(unnamed.path().is_none());
+}
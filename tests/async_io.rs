@@ -0,0 +1,134 @@
+#![cfg(not(target_vendor="apple"))]
+
+extern crate uds;
+extern crate async_io;
+
+use std::thread;
+use std::time::Duration;
+
+use async_io::{Async, block_on};
+
+use uds::nonblocking::UnixSeqpacketConn as NonblockingUnixSeqpacketConn;
+use uds::nonblocking::UnixSeqpacketListener as NonblockingUnixSeqpacketListener;
+use uds::UnixSocketAddr;
+
+#[test]
+fn seqpacket_with_async_io() {
+    block_on(async {
+        let addr = UnixSocketAddr::new_unique().unwrap();
+        let listener = NonblockingUnixSeqpacketListener::bind_unix_addr(addr).expect("bind");
+        let listener = Async::new(listener).expect("register listener");
+        let client = NonblockingUnixSeqpacketConn::connect_unix_addr(addr).expect("connect");
+        let client = Async::new(client).expect("register client");
+        let (server, _) = listener.read_with(|l| l.accept_unix_addr() ).await.expect("accept");
+        let server = Async::new(server).expect("register server");
+
+        assert_eq!(client.write_with(|conn| conn.send(b"async") ).await.expect("send"), 5);
+        let mut buf = [0; 10];
+        let received = server.read_with(|conn| conn.recv(&mut buf) ).await.expect("receive");
+        assert_eq!((&buf[..received.0], received.1), (&b"async"[..], false));
+
+        // wait for a packet that is sent later
+        let (a, b) = NonblockingUnixSeqpacketConn::pair().expect("create pair");
+        let sender = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            a.send(b"later").expect("send from thread");
+        });
+        let b = Async::new(b).expect("register receiver");
+        let received = b.read_with(|conn| conn.recv(&mut buf) ).await.expect("wait for packet");
+        assert_eq!(&buf[..received.0], b"later");
+        sender.join().unwrap();
+
+        if let Some(path) = addr.as_pathname() {
+            let _ = std::fs::remove_file(path);
+        }
+    });
+}
+
+#[cfg(feature="async-io")]
+#[test]
+fn seqpacket_types_are_io_safe() {
+    fn assert_io_safe<T: async_io::IoSafe>() {}
+    assert_io_safe::<NonblockingUnixSeqpacketConn>();
+    assert_io_safe::<NonblockingUnixSeqpacketListener>();
+}
+
+#[cfg(feature="async-io")]
+#[test]
+fn stream_of_incoming_connections() {
+    use futures_lite::StreamExt;
+    use uds::AsyncSeqpacketListenerExt;
+
+    let addr = UnixSocketAddr::new_unique().unwrap();
+    let listener = NonblockingUnixSeqpacketListener::bind_unix_addr(addr).expect("bind");
+    let listener = Async::new(listener).expect("register listener");
+    let client = thread::spawn(move || {
+        let mut conns = Vec::new();
+        for n in 0..3u8 {
+            thread::sleep(Duration::from_millis(5));
+            let conn = uds::UnixSeqpacketConn::connect_unix_addr(addr).expect("connect");
+            conn.send(&[n]).expect("send");
+            conns.push(conn);
+        }
+        conns
+    });
+    let received = block_on(listener.incoming().take(3).then(|conn| async move {
+        let conn = conn.expect("accept");
+        let mut buf = [0; 1];
+        conn.read_with(|conn| conn.recv(&mut buf) ).await.expect("receive");
+        buf[0]
+    }).collect::<Vec<u8>>());
+    assert_eq!(received, [0, 1, 2]);
+    client.join().unwrap();
+    if let Some(path) = addr.as_pathname() {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+#[cfg(feature="async-io")]
+#[test]
+fn packets_stream_and_sink() {
+    use std::future::poll_fn;
+    use std::pin::Pin;
+    use futures_lite::StreamExt;
+    use uds::AsyncPackets;
+
+    let (a, b) = NonblockingUnixSeqpacketConn::pair().expect("create pair");
+    let mut sender = AsyncPackets::new(Async::new(a).expect("register sender"));
+    let b = Async::new(b).expect("register receiver");
+    // more than fits in the send buffer, so that sending has to wait
+    let packets = (0..200u8).map(|n| vec![n; 10_000] ).collect::<Vec<_>>();
+    let expected = packets.clone();
+    let receiver = thread::spawn(move || {
+        block_on(AsyncPackets::from(b).try_collect::<_, _, Vec<Vec<u8>>>())
+    });
+    block_on(async {
+        for packet in packets {
+            sender.send_packet(packet).await.expect("send packet");
+        }
+        poll_fn(|cx| futures_sink::Sink::poll_close(Pin::new(&mut sender), cx) )
+            .await
+            .expect("close sink");
+    });
+    let received = receiver.join().unwrap().expect("receive packets");
+    assert_eq!(received.len(), expected.len());
+    assert!(received == expected);
+}
+
+#[cfg(feature="async-io")]
+#[test]
+fn packets_stream_doesnt_affect_fd_passing() {
+    use std::os::unix::io::AsRawFd;
+    use futures_lite::StreamExt;
+    use uds::AsyncPackets;
+
+    let (a, b) = NonblockingUnixSeqpacketConn::pair().expect("create pair");
+    let mut b = AsyncPackets::new(Async::new(b).expect("register receiver"));
+    a.send(b"").expect("send empty packet");
+    let received = block_on(b.next()).expect("get packet").expect("receive packet");
+    assert_eq!(received, b"");
+    a.send_fds(b"fd", &[a.as_raw_fd()]).expect("send fd");
+    let (len, truncated, fds) = b.get_ref().get_ref().recv_owned_fds(&mut[0; 10], 1)
+        .expect("receive fd");
+    assert_eq!((len, truncated, fds.len()), (2, false, 1));
+}
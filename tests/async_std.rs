@@ -0,0 +1,72 @@
+#![cfg(all(feature="async-std", not(target_vendor="apple")))]
+
+extern crate uds;
+extern crate async_std;
+
+use std::os::unix::io::AsRawFd;
+
+use async_std::stream::StreamExt;
+use async_std::task;
+
+use uds::{AsyncStdSeqpacketListener, AsyncStdSeqpacketConn, UnixSocketAddr};
+use uds::{UnixSeqpacketListener, UnixSeqpacketConn};
+
+#[test]
+fn seqpacket_with_async_std() {
+    task::block_on(async {
+        let addr = UnixSocketAddr::new_unique().unwrap();
+        let listener = AsyncStdSeqpacketListener::bind_unix_addr(addr).expect("bind");
+        assert_eq!(listener.local_unix_addr().expect("get listener address"), addr);
+
+        // accept in another task, which must wait for the connect
+        let server = task::spawn(async move {
+            let server = listener.incoming().next().await.unwrap().expect("accept");
+            let mut buf = [0; 10];
+            let mut fd_buf = [-1; 2];
+            let (len, _, fds) = server.recv_fds(&mut buf, &mut fd_buf).await.expect("receive fd");
+            for &fd in &fd_buf[..fds] {
+                unsafe { libc::close(fd) };
+            }
+            server.send(&buf[..len]).await.expect("send reply");
+            fds
+        });
+        let client = AsyncStdSeqpacketConn::connect_unix_addr(addr).await.expect("connect");
+        assert_eq!(client.peer_unix_addr().expect("get peer address"), addr);
+        client.send_fds(b"fd", &[client.get_ref().as_raw_fd()]).await.expect("send fd");
+        let mut buf = [0; 10];
+        assert_eq!(client.recv(&mut buf).await.expect("receive reply"), (2, false));
+        assert_eq!(&buf[..2], b"fd");
+        assert_eq!(server.await, 1);
+
+        // end of connection
+        assert_eq!(client.recv(&mut buf).await.expect("receive end of connection"), (0, false));
+        if let Some(path) = addr.as_pathname() {
+            let _ = std::fs::remove_file(path);
+        }
+    });
+}
+
+#[test]
+fn async_std_std_conversions() {
+    let addr = UnixSocketAddr::new_unique().unwrap();
+    let listener = UnixSeqpacketListener::bind_unix_addr(addr).expect("bind");
+    let client = UnixSeqpacketConn::connect_unix_addr(addr).expect("connect");
+    task::block_on(async {
+        let listener = AsyncStdSeqpacketListener::from_std(listener).expect("register listener");
+        assert!(listener.get_ref().is_nonblocking().unwrap());
+        let (server, _) = listener.accept_unix_addr().await.expect("accept");
+        let listener = listener.into_std().expect("deregister listener");
+        assert!(!listener.is_nonblocking().unwrap());
+
+        let client = AsyncStdSeqpacketConn::from_std(client).expect("register connection");
+        assert!(client.get_ref().is_nonblocking().unwrap());
+        client.send(b"async").await.expect("send");
+        let client = client.into_std().expect("deregister connection");
+        assert!(!client.is_nonblocking().unwrap());
+        let mut buf = [0; 10];
+        assert_eq!(server.recv(&mut buf).await.expect("receive"), (5, false));
+    });
+    if let Some(path) = addr.as_pathname() {
+        let _ = std::fs::remove_file(path);
+    }
+}
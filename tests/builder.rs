@@ -0,0 +1,107 @@
+extern crate uds;
+extern crate libc;
+
+use std::fs;
+use std::io::{ErrorKind, Read, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net::{UnixDatagram, UnixListener, UnixStream};
+
+use uds::SocketBuilder;
+use uds::nonblocking::UnixSeqpacketConn as NonblockingUnixSeqpacketConn;
+
+fn is_cloexec(fd: libc::c_int) -> bool {
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFD) };
+    assert_ne!(flags, -1, "get fd flags");
+    flags & libc::FD_CLOEXEC != 0
+}
+
+#[test]
+fn build_stream_listener_with_mode() {
+    let path = "builder_stream.sock";
+    let _ = fs::remove_file(path);
+    let listener: UnixListener = SocketBuilder::stream()
+        .mode(0o640)
+        .owner(None, None)
+        .backlog(1)
+        .listen(path)
+        .expect("listen");
+    assert_eq!(fs::metadata(path).unwrap().permissions().mode() & 0o777, 0o640);
+    assert!(is_cloexec(listener.as_raw_fd()));
+
+    let mut client: UnixStream = SocketBuilder::stream()
+        .cloexec(false)
+        .connect(path)
+        .expect("connect");
+    assert!(!is_cloexec(client.as_raw_fd()));
+    let (mut server, _) = listener.accept().expect("accept");
+    client.write_all(b"built").unwrap();
+    let mut buf = [0; 5];
+    server.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"built");
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn build_datagram_pair_and_bind() {
+    let (a, b): (UnixDatagram, UnixDatagram) = SocketBuilder::datagram()
+        .nonblocking(true)
+        .send_buffer_size(32*1024)
+        .recv_buffer_size(32*1024)
+        .pair()
+        .expect("create pair");
+    assert_eq!(b.recv(&mut[0; 10]).unwrap_err().kind(), ErrorKind::WouldBlock);
+    a.send(b"pair").unwrap();
+    assert_eq!(b.recv(&mut[0; 10]).unwrap(), 4);
+
+
+    let path = "builder_bound.sock";
+    let _ = fs::remove_file(path);
+    let server: UnixDatagram = SocketBuilder::datagram().mode(0o600).bind(path).expect("bind");
+    assert_eq!(fs::metadata(path).unwrap().permissions().mode() & 0o777, 0o600);
+    let client: UnixDatagram = SocketBuilder::datagram().connect(path).expect("connect");
+    client.send(b"bound").unwrap();
+    assert_eq!(server.recv(&mut[0; 10]).unwrap(), 5);
+    fs::remove_file(path).unwrap();
+}
+
+#[cfg(not(target_vendor="apple"))]
+#[test]
+fn build_nonblocking_seqpacket_pair() {
+    let (a, b): (NonblockingUnixSeqpacketConn, _) = SocketBuilder::seqpacket()
+        .nonblocking(true)
+        .pair()
+        .expect("create pair");
+    assert!(a.is_nonblocking().unwrap());
+    a.send(b"packet").unwrap();
+    assert_eq!(b.recv(&mut[0; 10]).unwrap(), (6, false));
+}
+
+#[test]
+fn failing_listen_removes_socket_file() {
+    let path = "builder_datagram.sock";
+    let _ = fs::remove_file(path);
+    let error = SocketBuilder::datagram().listen::<_, UnixDatagram>(path).unwrap_err();
+    assert_ne!(error.kind(), ErrorKind::AddrInUse);
+    assert!(fs::symlink_metadata(path).is_err(), "socket file was removed");
+}
+
+#[test]
+fn take_over_stale_datagram_socket() {
+    let path = "builder_stale.sock";
+    let _ = fs::remove_file(path);
+    drop(UnixDatagram::bind(path).unwrap());
+    let error = SocketBuilder::datagram().bind::<_, UnixDatagram>(path).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::AddrInUse);
+    let server: UnixDatagram = SocketBuilder::datagram()
+        .take_over_stale(true)
+        .bind(path)
+        .expect("replace stale socket");
+    let error = SocketBuilder::datagram()
+        .take_over_stale(true)
+        .bind::<_, UnixDatagram>(path)
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::AddrInUse);
+    drop(server);
+    fs::remove_file(path).unwrap();
+}
@@ -1,3 +1,6 @@
+// panicking with the tester's output predates format string checks
+#![allow(non_fmt_panics)]
+
 extern crate uds;
 
 use std::ffi::OsStr;
@@ -38,7 +41,7 @@ fn stream_listener() {
     let path = "stream_listener_cloexec";
     let addr = UnixSocketAddr::from_path(path).unwrap();
     let _ = remove_file(path);
-    let listener = UnixListener::bind_unix_addr(&addr).expect("bind()");
+    let listener = UnixListener::bind_unix_addr(addr).expect("bind()");
     remove_file(path).expect("remove socket file");
     assert!(is_cloexec(listener.as_raw_fd()));
 }
@@ -55,13 +58,30 @@ fn stream_accepted() {
     assert!(is_cloexec(conn.as_raw_fd()));
 }
 
+#[test]
+fn stream_accepted_nonblocking() {
+    let addr = UnixSocketAddr::new_unique().unwrap();
+    let listener = UnixListener::bind_unix_addr(addr).expect("bind()");
+    let _client = UnixStream::connect_to_unix_addr(addr).expect("connect()");
+    let (conn, _) = listener.accept_unix_addr_nonblocking().expect("accept()");
+    if let Some(path) = addr.as_pathname() {
+        let _ = remove_file(path);
+    }
+    assert!(is_cloexec(conn.as_raw_fd()));
+    let flags = unsafe { libc::fcntl(conn.as_raw_fd(), libc::F_GETFL) };
+    assert_ne!(flags & libc::O_NONBLOCK, 0);
+    // the listener is not made nonblocking
+    let flags = unsafe { libc::fcntl(listener.as_raw_fd(), libc::F_GETFL) };
+    assert_eq!(flags & libc::O_NONBLOCK, 0);
+}
+
 #[test]
 fn stream_connected() {
     let path = "stream_connected_cloexec";
     let addr = UnixSocketAddr::from_path(path).unwrap();
     let _ = remove_file(path);
     let _listener = UnixListener::bind(path).unwrap();
-    let result = UnixStream::connect_to_unix_addr(&addr);
+    let result = UnixStream::connect_to_unix_addr(addr);
     remove_file(path).expect("remove socket file");
     let conn = result.expect("connect()");
     assert!(is_cloexec(conn.as_raw_fd()));
@@ -77,7 +97,7 @@ fn stream_connected_from() {
     let _ = remove_file(listen_path);
     let _ = remove_file(connect_from_path);
     let _listener = UnixListener::bind(listen_path).unwrap();
-    let result = UnixStream::connect_from_to_unix_addr(&connect_from_addr, &listen_addr);
+    let result = UnixStream::connect_from_to_unix_addr(connect_from_addr, listen_addr);
     remove_file(listen_path).expect("remove listening socket file");
     remove_file(connect_from_path).expect("remove connect from socket file");
     let conn = result.expect("connect()");
@@ -96,6 +116,30 @@ fn received() {
     assert!(is_cloexec(fd_buf[0]));
 }
 
+#[cfg(not(any(target_os="illumos", target_os="solaris")))]
+#[test]
+fn received_inheritable() {
+    let (foo, bar) = UnixStream::pair().expect("create unix stream pair");
+    foo.send_fds(b"inherit this", &[foo.as_raw_fd()]).expect("send fd");
+    let (_, fds) = bar.recv_inheritable_fds(&mut[0; 20], 2).expect("receive fd");
+    assert_eq!(fds.len(), 1);
+    assert!(!is_cloexec(fds[0].as_raw_fd()));
+}
+
+#[cfg(not(target_vendor="apple"))]
+#[test]
+fn seqpacket_accepted_inheritable() {
+    let addr = UnixSocketAddr::new_unique().unwrap();
+    let listener = uds::UnixSeqpacketListener::bind_unix_addr(addr).expect("bind()");
+    let _client = uds::UnixSeqpacketConn::connect_unix_addr(addr).expect("connect()");
+    let (conn, _) = listener.accept_inheritable().expect("accept()");
+    if let Some(path) = addr.as_pathname() {
+        let _ = remove_file(path);
+    }
+    assert!(!is_cloexec(conn.as_raw_fd()));
+    assert!(is_cloexec(listener.as_raw_fd()));
+}
+
 #[test] /// tests that cloexec_tester detects a fd without cloexec
 fn raw_not_cloexec() {
     unsafe {
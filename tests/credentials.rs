@@ -1,4 +1,6 @@
 #![allow(unused)] // when not applicable, tests should still compile
+// the id types are u32 on some OSes and not on others
+#![allow(clippy::unnecessary_cast, clippy::match_ref_pats)]
 
 use std::os::unix::net::{UnixStream, UnixDatagram};
 use std::io::{self, ErrorKind::*};
@@ -11,7 +13,10 @@ extern crate libc;
 use libc::{getpid, geteuid, getegid, getgid, getgroups};
 
 #[cfg_attr(
-    not(any(target_os="linux", target_os="android", target_os="freebsd", target_vendor="apple")),
+    not(any(
+        target_os="linux", target_os="android", target_os="freebsd", target_vendor="apple",
+        target_os="openbsd", target_os="netbsd",
+    )),
     test
 )]
 fn peer_credentials_not_supported() {
@@ -90,6 +95,32 @@ fn peer_credentials_of_stream_conn() {
     assert_eq!(a.initial_peer_credentials().unwrap(), creds); // consistent
 }
 
+#[cfg_attr(any(target_os="openbsd", target_os="netbsd"), test)]
+fn peer_credentials_from_getpeereid() {
+    let (a, _b) = UnixStream::pair().expect("create unix stream pair");
+    let creds = a.initial_peer_credentials().expect("get credentials of peer");
+    assert_eq!(creds.pid(), None);
+    assert_eq!(creds.euid(), unsafe { geteuid() } as u32);
+    assert_eq!(creds.egid(), Some(unsafe { getegid() } as u32));
+    assert_eq!(creds.groups(), &[unsafe { getegid() } as u32]);
+}
+
+#[cfg_attr(any(target_os="linux", target_os="android", target_vendor="apple"), test)]
+fn peer_pid_of_stream_conn() {
+    let (a, b) = UnixStream::pair().unwrap();
+    let pid = unsafe { getpid() } as u32;
+    assert_eq!(a.initial_peer_pid().map(u32::from).unwrap(), pid);
+    assert_eq!(b.initial_peer_pid().map(u32::from).unwrap(), pid);
+    #[cfg(target_vendor="apple")]
+    assert_eq!(a.initial_peer_effective_pid().map(u32::from).unwrap(), pid);
+}
+
+#[cfg_attr(not(any(target_os="linux", target_os="android", target_vendor="apple")), test)]
+fn peer_pid_not_available() {
+    let (a, _b) = UnixStream::pair().unwrap();
+    assert!(a.initial_peer_pid().is_err());
+}
+
 #[cfg_attr(any(target_os="linux", target_os="android", target_os="freebsd"), test)]
 fn peer_credentials_of_seqpacket_conn() {
     let (a, b) = UnixSeqpacketConn::pair().expect("create unix seqpacket pair");
@@ -98,6 +129,25 @@ fn peer_credentials_of_seqpacket_conn() {
     assert_eq!(b.initial_peer_credentials().unwrap(), creds);
 }
 
+#[cfg_attr(any(target_os="linux", target_os="android", target_os="freebsd"), test)]
+fn peer_credentials_of_nonblocking_seqpacket_conn() {
+    let addr = uds::UnixSocketAddr::new_unique().unwrap();
+    let listener = uds::nonblocking::UnixSeqpacketListener::bind_unix_addr(addr)
+        .expect("create nonblocking seqpacket listener");
+    let client = uds::nonblocking::UnixSeqpacketConn::connect_unix_addr(addr)
+        .expect("connect nonblocking");
+    let (server_side, _) = listener.accept_unix_addr().expect("accept connection");
+    let creds = server_side.initial_peer_credentials().expect("get credentials of client");
+    assert_credentials_matches_current_process(&creds, "nonblocking seqpacket conn");
+    assert_eq!(client.initial_peer_credentials().unwrap(), creds);
+    let _client = uds::nonblocking::UnixSeqpacketConn::connect_unix_addr(addr).unwrap();
+    let (_, _, accepted_creds) = listener.accept_with_credentials().expect("accept with credentials");
+    assert_eq!(accepted_creds, creds);
+    if let Some(path) = addr.as_pathname() {
+        let _ = remove_file(path);
+    }
+}
+
 #[cfg_attr(
     any(target_os="linux", target_os="android", target_os="freebsd", target_vendor="apple"),
     test
@@ -157,3 +207,138 @@ fn no_peer_credentials_of_regularly_connected_datagram_socket() {
     remove_file(a_pathname).expect("delete socket file");
     remove_file(b_pathname).expect("delete socket file");
 }
+
+#[cfg_attr(any(target_os="linux", target_os="android"), test)]
+fn credentials_of_each_seqpacket_packet() {
+    let (a, b) = UnixSeqpacketConn::pair().expect("create unix seqpacket pair");
+    let mut buf = [0; 10];
+    a.send(b"unmarked").unwrap();
+    let (len, truncated, creds) = b.recv_with_credentials(&mut buf).unwrap();
+    assert_eq!((len, truncated, creds), (8, false, None));
+
+    b.set_receive_credentials(true).expect("enable SO_PASSCRED");
+    a.send(b"marked").unwrap();
+    let (len, _, creds) = b.recv_with_credentials(&mut buf).unwrap();
+    assert_eq!(len, 6);
+    let creds = creds.expect("receive credentials");
+    assert_eq!(creds.pid(), Some(unsafe { getpid() } as u32));
+    assert_eq!(creds.effective_or_sent_uid(), unsafe { geteuid() } as u32);
+
+    b.set_receive_credentials(false).unwrap();
+    a.send(b"unmarked").unwrap();
+    assert_eq!(b.recv_with_credentials(&mut buf).unwrap().2, None);
+}
+
+#[cfg_attr(any(target_os="linux", target_os="android"), test)]
+fn send_explicit_credentials() {
+    use uds::SendCredentials;
+    let (a, b) = UnixSeqpacketConn::pair().expect("create unix seqpacket pair");
+    b.set_receive_credentials(true).expect("enable SO_PASSCRED");
+    let pid = unsafe { getpid() } as u32;
+    let (uid, gid) = unsafe { (libc::getuid() as u32, getgid() as u32) };
+    a.send_with_credentials(b"real", SendCredentials::Custom{ pid, uid, gid })
+        .expect("send own credentials");
+    let (len, _, creds) = b.recv_with_credentials(&mut[0; 10]).unwrap();
+    assert_eq!(len, 4);
+    let creds = creds.expect("receive credentials");
+    assert_eq!(creds.pid(), Some(pid));
+    assert_eq!(creds.effective_or_sent_uid(), uid);
+    // the receiver doesn't get them without SO_PASSCRED, but sending succeeds
+    let (c, d) = UnixDatagram::pair().expect("create unix datagram pair");
+    c.send_with_credentials(b"ignored", SendCredentials::Effective).expect("send credentials");
+    assert_eq!(d.recv(&mut[0; 10]).unwrap(), 7);
+}
+
+#[cfg_attr(any(target_os="linux", target_os="android", target_os="freebsd", target_os="netbsd"), test)]
+fn credentials_of_each_datagram() {
+    let addr = uds::UnixSocketAddr::new_unique().unwrap();
+    let receiver = UnixDatagram::unbound().expect("create receiver");
+    receiver.bind_to_unix_addr(addr).expect("bind receiver");
+    let sender_addr = uds::UnixSocketAddr::new_unique().unwrap();
+    let sender = UnixDatagram::unbound().expect("create sender");
+    sender.bind_to_unix_addr(sender_addr).expect("bind sender");
+
+    let mut buf = [0; 10];
+    sender.send_fds_to(b"disabled", &[], addr).unwrap();
+    let (len, from, creds) = receiver.recv_from_with_credentials(&mut buf).unwrap();
+    assert_eq!((&buf[..len], from), (&b"disabled"[..], sender_addr));
+    assert_eq!(creds, None);
+    receiver.set_receive_credentials(true).expect("enable SO_PASSCRED");
+    sender.send_fds_to(b"after", &[], addr).unwrap();
+    let (len, from, creds) = receiver.recv_from_with_credentials(&mut buf).unwrap();
+    assert_eq!((&buf[..len], from), (&b"after"[..], sender_addr));
+    let creds = creds.expect("receive credentials");
+    if cfg!(target_os="freebsd") {
+        assert_eq!(creds.pid(), None);
+        assert!(creds.groups().contains(&(unsafe { getegid() } as u32)));
+    } else {
+        assert_eq!(creds.pid(), Some(unsafe { getpid() } as u32));
+    }
+    assert_eq!(creds.effective_or_sent_uid(), unsafe { geteuid() } as u32);
+    assert_eq!(creds.effective_or_sent_gid(), Some(unsafe { getegid() } as u32));
+
+    for addr in &[addr, sender_addr] {
+        if let Some(path) = addr.as_pathname() {
+            let _ = remove_file(path);
+        }
+    }
+}
+
+#[cfg_attr(any(target_os="linux", target_os="android", target_os="freebsd", target_vendor="apple"), test)]
+fn peer_groups_of_stream() {
+    let (a, _b) = UnixStream::pair().unwrap();
+    let groups = a.initial_peer_groups().expect("get peer groups");
+    let mut own: Vec<libc::gid_t> = vec![0; 512];
+    let count = unsafe { getgroups(own.len() as i32, own.as_mut_ptr()) };
+    own.truncate(count as usize);
+    if cfg!(any(target_os="linux", target_os="android")) {
+        assert_eq!(groups, own);
+    } else {
+        assert!(groups.len() <= 16);
+        assert!(groups.iter().all(|gid| own.contains(gid) || *gid == unsafe { getegid() } as u32 ));
+    }
+}
+
+#[cfg_attr(any(target_os="linux", target_os="android"), test)]
+fn security_labels() {
+    use std::io::IoSliceMut;
+    use uds::{AncillaryBuf, AncillaryMessage};
+    let (a, b) = UnixSeqpacketConn::pair().expect("create unix seqpacket pair");
+    let peer_label = match b.peer_security_label() {
+        Ok(label) => label,
+        // no security module with labels
+        Err(ref e) if e.raw_os_error() == Some(libc::ENOPROTOOPT) => return,
+        Err(e) => panic!("get peer security label: {}", e),
+    };
+    assert!(!peer_label.ends_with(&[0]));
+
+    b.set_receive_security_label(true).expect("enable SO_PASSSEC");
+    a.send(b"labeled").unwrap();
+    let mut ancillary_buf = AncillaryBuf::with_capacity(256);
+    let mut buf = [0; 10];
+    let (len, ancillary) = b.recv_vectored_ancillary(
+        &mut[IoSliceMut::new(&mut buf)],
+        &mut ancillary_buf
+    ).expect("receive with label");
+    assert_eq!(len, 7);
+    let labels = ancillary.filter(|message| {
+        matches!(message, AncillaryMessage::SecurityLabel(_))
+    }).count();
+    assert_eq!(labels, 1);
+}
+
+#[cfg_attr(any(target_os="linux", target_os="android"), test)]
+fn peer_pidfd_refers_to_peer() {
+    use std::os::unix::io::AsRawFd;
+    let (a, _b) = UnixStream::pair().expect("create unix stream pair");
+    let pidfd = match a.peer_pidfd() {
+        Ok(pidfd) => pidfd,
+        // older than Linux 6.5
+        Err(ref e) if e.raw_os_error() == Some(libc::ENOPROTOOPT) => return,
+        Err(e) => panic!("get peer pidfd: {}", e),
+    };
+    let fdinfo = format!("/proc/self/fdinfo/{}", pidfd.as_raw_fd());
+    let fdinfo = std::fs::read_to_string(fdinfo).expect("read fdinfo of pidfd");
+    let pid_line = format!("Pid:\t{}\n", unsafe { getpid() });
+    assert!(fdinfo.contains(&pid_line), "{:?} contains {:?}", fdinfo, pid_line);
+}
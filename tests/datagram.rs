@@ -0,0 +1,228 @@
+extern crate uds;
+extern crate libc;
+
+use std::io::ErrorKind::*;
+use std::os::unix::net::UnixDatagram;
+use std::fs::remove_file;
+
+use uds::{UnixDatagramExt, UnixSocketAddr, SocketBuilder, DatagramClient};
+
+#[test]
+fn connected_datagram_without_blocking() {
+    let addr = UnixSocketAddr::new_unique().unwrap();
+    let server = UnixDatagram::bind_unix_addr(addr).expect("bind server");
+    let client = UnixDatagram::unbound().unwrap();
+    assert!(client.try_send(b"unconnected").is_err());
+    client.connect_to_unix_addr(addr).expect("connect");
+    assert_eq!(client.peer_unix_addr().unwrap(), addr);
+
+    assert_eq!(server.try_recv(&mut[0; 10]).unwrap_err().kind(), WouldBlock);
+    assert_eq!(client.try_send(b"first").expect("send without blocking"), 5);
+    assert_eq!(client.try_send(b"second datagram").expect("send without blocking"), 15);
+    let mut buf = [0; 10];
+    assert_eq!(server.try_recv(&mut buf).unwrap(), (5, false));
+    assert_eq!(server.try_recv(&mut buf).unwrap(), (10, true));
+    assert_eq!(&buf, b"second dat");
+    assert_eq!(server.try_recv(&mut buf).unwrap_err().kind(), WouldBlock);
+
+    // fill the receive queue
+    let sent = (0..10_000)
+        .map(|_| client.try_send(&[0; 1024]) )
+        .find(Result::is_err)
+        .expect("receive queue is unbounded");
+    assert_eq!(sent.unwrap_err().kind(), WouldBlock);
+    if let Some(path) = addr.as_pathname() {
+        let _ = remove_file(path);
+    }
+}
+
+#[cfg(any(target_os="linux", target_os="android"))]
+#[test]
+fn datagram_full_size() {
+    let (a, b) = UnixDatagram::pair().unwrap();
+    a.send(b"twelve bytes").unwrap();
+    let mut buf = [0; 6];
+    assert_eq!(b.recv_full_size(&mut buf).unwrap(), 12);
+    assert_eq!(&buf, b"twelve");
+}
+
+#[cfg(feature="mio_07")]
+#[test]
+fn mio_datagram_addresses_and_fds() {
+    use std::os::unix::io::AsRawFd;
+
+    let addr = UnixSocketAddr::new_unique().unwrap();
+    let server = mio_07::net::UnixDatagram::bind_unix_addr(addr).expect("bind mio datagram");
+    assert_eq!(server.local_unix_addr().unwrap(), addr);
+    let client_addr = UnixSocketAddr::new_unique().unwrap();
+    let client = mio_07::net::UnixDatagram::bind_unix_addr(client_addr).expect("bind mio datagram");
+
+    let mut buf = [0; 10];
+    assert_eq!(server.recv_from_unix_addr(&mut buf).unwrap_err().kind(), WouldBlock);
+    client.send_to_unix_addr(b"plain", addr).expect("send to address");
+    let (len, from) = server.recv_from_unix_addr(&mut buf).expect("receive with address");
+    assert_eq!((&buf[..len], from), (&b"plain"[..], client_addr));
+
+    client.send_fds_to(b"fd", &[client.as_raw_fd()], addr).expect("send fd");
+    let (len, fds) = server.recv_owned_fds(&mut buf, 2).expect("receive fd");
+    assert_eq!((&buf[..len], fds.len()), (&b"fd"[..], 1));
+
+    for addr in &[addr, client_addr] {
+        if let Some(path) = addr.as_pathname() {
+            let _ = remove_file(path);
+        }
+    }
+}
+
+#[cfg(any(target_os="linux", target_os="android"))]
+#[test]
+fn datagram_batches() {
+    let addr = UnixSocketAddr::new_unique().unwrap();
+    let collector = UnixDatagram::bind_unix_addr(addr).expect("bind collector");
+    collector.set_nonblocking(true).unwrap();
+    let reporter_addr = UnixSocketAddr::new_unique().unwrap();
+    let reporter = UnixDatagram::bind_unix_addr(reporter_addr).expect("bind reporter");
+
+    assert_eq!(reporter.send_multiple_to(&[], addr).expect("send nothing"), 0);
+    let mut storage = [[0u8; 4]; 3];
+    let mut buffers = storage.iter_mut().map(|b| &mut b[..] ).collect::<Vec<_>>();
+    let err = collector.recv_multiple_from(&mut buffers).expect_err("receive nothing");
+    assert_eq!(err.kind(), WouldBlock);
+
+    let datagrams: [&[u8]; 4] = [b"a", b"", b"too long", b"left"];
+    assert_eq!(reporter.send_multiple_to(&datagrams, addr).expect("send batch"), 4);
+    let received = collector.recv_multiple_from(&mut buffers).expect("receive batch");
+    assert_eq!(received.len(), 3);
+    assert_eq!(received[0], (1, false, reporter_addr));
+    assert_eq!(received[1], (0, false, reporter_addr));
+    assert_eq!(received[2], (4, true, reporter_addr));
+    assert_eq!(&buffers[2][..], b"too ");
+    let received = collector.recv_multiple_from(&mut buffers).expect("receive rest");
+    assert_eq!(received.len(), 1);
+    assert_eq!(&buffers[0][..received[0].0], b"left");
+
+    for addr in &[addr, reporter_addr] {
+        if let Some(path) = addr.as_pathname() {
+            let _ = remove_file(path);
+        }
+    }
+}
+
+#[test]
+fn crate_datagram_types() {
+    use std::os::unix::io::AsRawFd;
+
+    let addr = UnixSocketAddr::new_unique().unwrap();
+    let server = uds::UnixDatagram::bind(addr).expect("bind");
+    let client = uds::UnixDatagram::unbound().expect("create unbound socket");
+    client.send_to(b"to", addr).expect("send to address");
+    let mut buf = [0; 10];
+    let (len, from) = server.recv_from(&mut buf).expect("receive");
+    assert_eq!((&buf[..len], from.is_unnamed()), (&b"to"[..], true));
+
+    client.send_fds_to(b"fd", &[client.as_raw_fd()], addr).expect("send fd");
+    let mut fd_buf = [-1; 2];
+    let (len, fds, _) = server.recv_fds_from(&mut buf, &mut fd_buf).expect("receive fd");
+    assert_eq!((len, fds), (2, 1));
+    unsafe { libc::close(fd_buf[0]) };
+
+    let (a, b) = uds::nonblocking::UnixDatagram::pair().expect("create nonblocking pair");
+    assert_eq!(b.recv(&mut buf).unwrap_err().kind(), WouldBlock);
+    a.send(b"pair").unwrap();
+    assert_eq!(b.recv(&mut buf).unwrap(), 4);
+    let b = b.into_blocking().expect("make blocking");
+    b.set_read_timeout(Some(std::time::Duration::from_millis(1))).unwrap();
+    assert!(b.recv(&mut buf).is_err());
+    if let Some(path) = addr.as_pathname() {
+        let _ = remove_file(path);
+    }
+}
+
+#[test]
+fn pair_with_options() {
+    use std::os::unix::io::AsRawFd;
+
+    let options = SocketBuilder::datagram().nonblocking(true).cloexec(false).recv_buffer_size(8192);
+    let (a, b) = UnixDatagram::pair_with(&options).expect("create pair");
+    for socket in [&a, &b] {
+        let flags = unsafe { libc::fcntl(socket.as_raw_fd(), libc::F_GETFD) };
+        assert_eq!(flags & libc::FD_CLOEXEC, 0);
+    }
+    assert_eq!(b.recv(&mut[0; 10]).unwrap_err().kind(), WouldBlock);
+    a.send(b"child").unwrap();
+    assert_eq!(b.recv(&mut[0; 10]).unwrap(), 5);
+
+    let error = UnixDatagram::pair_with(&SocketBuilder::stream()).unwrap_err();
+    assert_eq!(error.kind(), InvalidInput);
+}
+
+#[test]
+fn datagram_client_retries() {
+    use std::time::Duration;
+
+    let addr = UnixSocketAddr::new_unique().unwrap();
+    let server = UnixDatagram::bind_unix_addr(addr).unwrap();
+    let client = DatagramClient::new(addr).unwrap()
+        .timeout(Duration::from_millis(100))
+        .retries(1);
+    assert_eq!(client.server_addr(), addr);
+
+    // nobody answers
+    let mut reply = [0; 10];
+    assert_eq!(client.request(b"lost", &mut reply).unwrap_err().kind(), TimedOut);
+    let mut buf = [0; 10];
+    for _ in 0..2 {
+        let (len, from) = server.recv_from_unix_addr(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"lost");
+        assert_eq!(from, client.local_unix_addr().unwrap());
+    }
+    // the late reply is discarded
+    server.send_to_unix_addr(b"late", client.local_unix_addr().unwrap()).unwrap();
+
+    let reply_addr = client.local_unix_addr().unwrap();
+    let handle = std::thread::spawn(move || {
+        // ignore the first attempt
+        server.recv_from_unix_addr(&mut buf).unwrap();
+        let (len, _) = server.recv_from_unix_addr(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"again");
+        server.send_to_unix_addr(b"too long reply", reply_addr).unwrap();
+        server
+    });
+    let client = client.timeout(Duration::from_secs(1));
+    assert_eq!(client.request(b"again", &mut reply).unwrap(), (10, true));
+    assert_eq!(&reply, b"too long r");
+    let server = handle.join().unwrap();
+    assert_eq!(client.get_ref().read_timeout().unwrap(), None);
+
+    // a timeout too long for a deadline waits forever
+    let handle = std::thread::spawn(move || {
+        let (len, from) = server.recv_from_unix_addr(&mut buf).unwrap();
+        server.send_to_unix_addr(&buf[..len], from).unwrap();
+        server
+    });
+    let client = client.timeout(Duration::MAX);
+    assert_eq!(client.request(b"echo", &mut reply).unwrap(), (4, false));
+    let server = handle.join().unwrap();
+
+    drop(server);
+    if let Some(path) = addr.as_pathname() {
+        remove_file(path).unwrap();
+    }
+    assert!(client.request(b"gone", &mut reply).is_err());
+}
+
+#[test]
+fn datagram_bytes_available() {
+    let (a, b) = UnixDatagram::pair().unwrap();
+    assert_eq!(b.bytes_available().unwrap(), 0);
+    a.send(b"first").unwrap();
+    a.send(b"second").unwrap();
+    if cfg!(any(target_os="linux", target_os="android")) {
+        assert_eq!(b.bytes_available().unwrap(), 5);
+    } else {
+        assert!(b.bytes_available().unwrap() >= 5);
+    }
+    b.recv(&mut[0; 10]).unwrap();
+    b.recv(&mut[0; 10]).unwrap();
+    assert_eq!(b.bytes_available().unwrap(), 0);
+}
@@ -1,4 +1,6 @@
 #![cfg_attr(any(target_os="illumos", target_os="solaris"), allow(unused))]
+// writes and reads are small enough to never be partial on socket pairs
+#![allow(clippy::unused_io_amount)]
 
 extern crate uds;
 
@@ -306,3 +308,244 @@ fn errors_on_solarish() {
         .expect_err("receive with fd capacity");
     assert!(format!("{}", err).contains("available"));
 }
+
+#[cfg_attr(any(target_os="linux", target_os="android"), test)]
+fn seqpacket_iterate_all_ancillary_messages() {
+    use std::io::IoSliceMut;
+    use uds::{UnixSeqpacketConn, SendCredentials, AncillaryBuf, AncillaryMessage};
+
+    let (a, b) = UnixSeqpacketConn::pair().expect("create seqpacket pair");
+    b.set_receive_credentials(true).unwrap();
+    a.send_ancillary()
+        .bytes(b"both")
+        .fds(&[a.as_raw_fd(), b.as_raw_fd()])
+        .credentials(SendCredentials::Effective)
+        .send()
+        .expect("send fds and credentials");
+
+    let mut ancillary_buf = AncillaryBuf::with_capacity(200);
+    let mut buf = [0; 10];
+    let (len, ancillary) = b.recv_vectored_ancillary(
+        &mut[IoSliceMut::new(&mut buf)],
+        &mut ancillary_buf,
+    ).expect("receive with ancillary buffer");
+    assert_eq!(len, 4);
+    assert!(!ancillary.ancillary_truncated());
+    let (mut num_fds, mut got_credentials) = (0, false);
+    for message in ancillary {
+        match message {
+            AncillaryMessage::Fds(fds) => {
+                num_fds += fds.len();
+                for &fd in fds {
+                    drop(unsafe { UnixSeqpacketConn::from_raw_fd(fd) });
+                }
+            }
+            AncillaryMessage::Credentials(creds) => {
+                assert_eq!(creds.pid(), Some(std::process::id()));
+                got_credentials = true;
+            }
+            other => panic!("unexpected ancillary message {:?}", other),
+        }
+    }
+    assert_eq!((num_fds, got_credentials), (2, true));
+}
+
+#[cfg_attr(not(any(target_os="illumos", target_os="solaris")), test)]
+fn stream_receive_owned_fds() {
+    let (a, b) = UnixStream::pair().expect("create stream socket pair");
+    let (c, mut d) = UnixStream::pair().expect("create another stream socket pair");
+    a.send_fds(b"three", &[c.as_raw_fd(), c.as_raw_fd(), c.as_raw_fd()]).expect("send fds");
+    let (bytes, fds) = b.recv_owned_fds(&mut[0; 10], 2).expect("receive owned fds");
+    drop(c);
+    assert_eq!((bytes, fds.len()), (5, 2));
+    d.set_nonblocking(true).unwrap();
+    assert_eq!(d.read(&mut[0; 10]).unwrap_err().kind(), WouldBlock);
+    // the excess fd has been closed, so dropping the received ones closes c
+    drop(fds);
+    assert_eq!(d.read(&mut[0; 10]).unwrap(), 0);
+}
+
+#[cfg_attr(not(any(target_os="illumos", target_os="solaris")), test)]
+fn datagram_send_as_fds() {
+    use std::os::unix::io::{AsFd, OwnedFd};
+    let (a, b) = UnixDatagram::pair().expect("create datagram socket pair");
+    let owned = OwnedFd::from(a.try_clone().unwrap());
+    // owned fds are kept open until sent
+    a.send_as_fds(b"owned", vec![owned]).expect("send owned fd");
+    a.send_as_fds(b"borrowed", [a.as_fd(), b.as_fd()]).expect("send borrowed fds");
+    let (bytes, fds) = b.recv_owned_fds(&mut[0; 10], 3).expect("receive first");
+    assert_eq!((bytes, fds.len()), (5, 1));
+    let (bytes, fds) = b.recv_owned_fds(&mut[0; 10], 3).expect("receive second");
+    assert_eq!((bytes, fds.len()), (8, 2));
+}
+
+#[cfg_attr(not(any(target_os="illumos", target_os="solaris")), test)]
+fn datagram_reject_too_many_fds() {
+    let (a, b) = UnixDatagram::pair().expect("create datagram socket pair");
+    let (c, mut d) = UnixStream::pair().expect("create stream socket pair");
+    a.send_fds(b"too many", &[c.as_raw_fd(); 5]).expect("send fds");
+    a.send_fds(b"ok", &[c.as_raw_fd()]).expect("send fd");
+    drop(c);
+    let error = b.recv_fds_limited(&mut[0; 10], 2).expect_err("reject too many fds");
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    let (bytes, fds) = b.recv_fds_limited(&mut[0; 10], 2).expect("receive within limit");
+    assert_eq!((bytes, fds.len()), (2, 1));
+    // all fds from the rejected datagram have been closed
+    drop(fds);
+    assert_eq!(d.read(&mut[0; 10]).unwrap(), 0);
+}
+
+#[cfg_attr(any(target_os="linux", target_os="android"), test)]
+fn limited_fds_with_credentials_enabled() {
+    let (a, b) = UnixDatagram::pair().expect("create datagram socket pair");
+    b.set_receive_credentials(true).expect("enable credentials");
+    a.send_fds(b"one", &[a.as_raw_fd()]).expect("send fd");
+    let (bytes, fds) = b.recv_fds_limited(&mut[0; 10], 1).expect("receive within limit");
+    assert_eq!((bytes, fds.len()), (3, 1));
+    a.send_fds(b"two", &[a.as_raw_fd(); 2]).expect("send fds");
+    let error = b.recv_fds_limited(&mut[0; 10], 1).expect_err("reject too many fds");
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[cfg_attr(not(any(target_os="illumos", target_os="solaris")), test)]
+fn unconnected_datagram_send_fds_to() {
+    let addr = uds::UnixSocketAddr::new_unique().unwrap();
+    let receiver = UnixDatagram::unbound().expect("create receiver");
+    receiver.bind_to_unix_addr(addr).expect("bind receiver");
+    let sender_addr = uds::UnixSocketAddr::new_unique().unwrap();
+    let sender = UnixDatagram::unbound().expect("create sender");
+    sender.bind_to_unix_addr(sender_addr).expect("bind sender");
+    sender.send_fds_to(b"one", &[sender.as_raw_fd()], addr).expect("send fd to address");
+    let mut fd_buf = [-1; 3];
+    let (bytes, fds, from) = receiver.recv_fds_from(&mut[0; 10], &mut fd_buf).expect("receive fd");
+    assert_eq!((bytes, fds, from), (3, 1, sender_addr));
+    unsafe { libc::close(fd_buf[0]) };
+    for addr in &[addr, sender_addr] {
+        if let Some(path) = addr.as_pathname() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+#[cfg_attr(any(target_os="linux", target_os="android"), test)]
+fn exactly_sized_ancillary_buffer() {
+    use std::io::IoSliceMut;
+    use uds::{UnixSeqpacketConn, SendCredentials, AncillaryBuf};
+
+    let (a, b) = UnixSeqpacketConn::pair().expect("create seqpacket pair");
+    b.set_receive_credentials(true).unwrap();
+    let creds_space = AncillaryBuf::space_for_credentials();
+    let enough = AncillaryBuf::space_for_fds(3) + creds_space;
+    let too_little = AncillaryBuf::space_for_fds(2) + creds_space;
+    for &(capacity, truncated) in &[(enough, false), (too_little, true)] {
+        a.send_ancillary()
+            .bytes(b"sized")
+            .fds(&[a.as_raw_fd(), b.as_raw_fd(), a.as_raw_fd()])
+            .credentials(SendCredentials::Effective)
+            .send()
+            .expect("send fds and credentials");
+        let mut ancillary_buf = AncillaryBuf::with_capacity(capacity);
+        let (_, ancillary) = b.recv_vectored_ancillary(
+            &mut[IoSliceMut::new(&mut[0; 10])],
+            &mut ancillary_buf,
+        ).expect("receive with ancillary buffer");
+        assert_eq!(ancillary.ancillary_truncated(), truncated);
+        if !truncated {
+            assert_eq!(ancillary.ancillary_len(), capacity);
+        }
+    }
+}
+
+#[cfg_attr(any(target_os="linux", target_os="android"), test)]
+fn datagram_recv_everything() {
+    let addr = uds::UnixSocketAddr::new_unique().unwrap();
+    let receiver = UnixDatagram::unbound().expect("create receiver");
+    receiver.bind_to_unix_addr(addr).expect("bind receiver");
+    receiver.set_receive_credentials(true).expect("enable credentials");
+    receiver.set_receive_timestamps(true).expect("enable timestamps");
+    let sender_addr = uds::UnixSocketAddr::new_unique().unwrap();
+    let sender = UnixDatagram::unbound().expect("create sender");
+    sender.bind_to_unix_addr(sender_addr).expect("bind sender");
+
+    sender.send_fds_to(b"all of it", &[sender.as_raw_fd(), receiver.as_raw_fd()], addr)
+        .expect("send fds to address");
+    let mut buf = [0; 5];
+    let received = receiver.recv_full(&mut buf, 2).expect("receive everything");
+    assert_eq!(&buf, b"all o");
+    assert!(received.info().is_truncated());
+    assert!(!received.info().is_control_truncated());
+    assert_eq!(received.from(), sender_addr);
+    assert_eq!(received.credentials().and_then(|creds| creds.pid() ), Some(std::process::id()));
+    assert!(received.timestamp().is_some());
+    assert_eq!(received.into_fds().len(), 2);
+
+    for addr in &[addr, sender_addr] {
+        if let Some(path) = addr.as_pathname() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+#[cfg_attr(not(any(target_os="illumos", target_os="solaris")), test)]
+fn datagram_fd_policies() {
+    use uds::FdPolicy;
+    let (a, b) = UnixDatagram::pair().expect("create datagram pair");
+    let fds = [a.as_raw_fd(), b.as_raw_fd(), a.as_raw_fd()];
+    let policies = [
+        (FdPolicy::RefuseFds, 0, 3),
+        (FdPolicy::AcceptUpTo(1), 1, 2),
+        (FdPolicy::AcceptUpTo(5), 3, 0),
+        (FdPolicy::AcceptAll, 3, 0),
+    ];
+    for &(policy, accepted, refused) in &policies {
+        a.send_fds(b"fds", &fds).expect("send fds");
+        let received = b.recv_with_fd_policy(&mut[0; 10], policy).expect("receive with policy");
+        assert_eq!(received.len(), 3);
+        assert_eq!((received.fds().len(), received.refused_fds()), (accepted, refused));
+        assert!(!received.info().is_control_truncated());
+    }
+    a.send(b"none").unwrap();
+    let received = b.recv_with_fd_policy(&mut[0; 10], FdPolicy::RefuseFds).unwrap();
+    assert_eq!((received.len(), received.refused_fds()), (4, 0));
+}
+
+#[cfg_attr(not(any(target_vendor="apple", target_os="illumos", target_os="solaris")), test)]
+fn seqpacket_socket_fd_policy() {
+    use uds::{UnixSeqpacketConn, FdPolicy, FdPolicySocket};
+    let (a, b) = UnixSeqpacketConn::pair().expect("create seqpacket pair");
+    let mut b = FdPolicySocket::new(b, FdPolicy::AcceptUpTo(1));
+    assert_eq!(b.policy(), FdPolicy::AcceptUpTo(1));
+    let fds = [a.as_raw_fd(), a.as_raw_fd()];
+    a.send_fds(b"one", &fds).expect("send fds");
+    let received = b.recv_message(&mut[0; 10]).expect("receive with accept one");
+    assert_eq!((received.fds().len(), received.refused_fds()), (1, 1));
+    b.set_policy(FdPolicy::RefuseFds);
+    a.send_fds(b"none", &fds).expect("send fds");
+    let received = b.recv_message(&mut[0; 10]).expect("receive with refuse");
+    assert_eq!((received.fds().len(), received.refused_fds()), (0, 2));
+    // other methods are available through Deref
+    a.send(b"plain").unwrap();
+    assert_eq!(b.recv(&mut[0; 10]).unwrap(), (5, false));
+    let _conn: UnixSeqpacketConn = b.into_inner();
+}
+
+#[cfg_attr(not(any(target_os="illumos", target_os="solaris")), test)]
+fn stream_vectored_fds() {
+    use std::io::{IoSlice, IoSliceMut};
+    let (a, b) = UnixStream::pair().expect("create stream socket pair");
+    let slices = [IoSlice::new(b"one"), IoSlice::new(b""), IoSlice::new(b"two")];
+    let sent = a.send_vectored_with_fds(&slices, &[a.as_raw_fd(), b.as_raw_fd()])
+        .expect("send vectored with fds");
+    assert_eq!(sent, 6);
+    let (mut first, mut second) = ([0; 4], [0; 4]);
+    let mut fd_buf = [-1; 3];
+    let (bytes, fds) = b.recv_vectored_with_fds(
+        &mut[IoSliceMut::new(&mut first), IoSliceMut::new(&mut second)],
+        &mut fd_buf,
+    ).expect("receive vectored with fds");
+    assert_eq!((bytes, fds), (6, 2));
+    assert_eq!((&first, &second[..2]), (b"onet", &b"wo"[..]));
+    for &fd in &fd_buf[..fds] {
+        unsafe { libc::close(fd) };
+    }
+}
@@ -0,0 +1,42 @@
+#![cfg(all(feature="memfd", any(target_os="linux", target_os="android")))]
+
+extern crate uds;
+
+use std::io::ErrorKind::*;
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net::UnixDatagram;
+
+use uds::{UnixSeqpacketConn, UnixDatagramExt};
+
+#[test]
+fn seqpacket_memfd_payloads() {
+    let (a, b) = UnixSeqpacketConn::pair().expect("create seqpacket pair");
+    let blob = (0..4_000_000u32).map(|n| n as u8 ).collect::<Vec<u8>>();
+    assert!(blob.len() > a.max_send_size().unwrap());
+    a.send_memfd(&blob).expect("send big payload");
+    a.send_memfd(&[]).expect("send empty payload");
+    assert_eq!(&b.recv_memfd().expect("receive big payload")[..], &blob[..]);
+    assert!(b.recv_memfd().expect("receive empty payload").is_empty());
+    drop(a);
+    assert_eq!(b.recv_memfd().unwrap_err().kind(), UnexpectedEof);
+}
+
+#[test]
+fn datagram_memfd_payload() {
+    let (a, b) = UnixDatagram::pair().expect("create datagram pair");
+    a.send_memfd(b"small").expect("send payload");
+    assert_eq!(&*b.recv_memfd().expect("receive payload"), b"small");
+}
+
+#[test]
+fn reject_invalid_memfd_payloads() {
+    let (a, b) = UnixSeqpacketConn::pair().expect("create seqpacket pair");
+    a.send(b"no fd").unwrap();
+    assert_eq!(b.recv_memfd().unwrap_err().kind(), InvalidData);
+
+    let file = std::fs::File::open("README.md").unwrap();
+    let len = file.metadata().unwrap().len().to_le_bytes();
+    a.send_fds(&len, &[file.as_raw_fd()]).unwrap();
+    let err = b.recv_memfd().expect_err("receive unsealed file");
+    assert_eq!(err.kind(), InvalidData);
+}
@@ -0,0 +1,80 @@
+extern crate uds;
+
+use std::io::Write;
+use std::net::Shutdown;
+use std::os::unix::net::{UnixStream, UnixListener};
+
+use uds::{PortableSeqpacketConn, PortableSeqpacketListener};
+
+#[test]
+fn native_where_supported() {
+    let (a, b) = PortableSeqpacketConn::pair().unwrap();
+    assert_eq!(a.is_emulated(), cfg!(target_vendor="apple"));
+    a.send(b"").unwrap();
+    a.send(b"after empty").unwrap();
+    assert_eq!(b.recv(&mut[0; 20]).unwrap(), (0, false));
+    assert_eq!(b.recv(&mut[0; 20]).unwrap(), (11, false));
+}
+
+#[test]
+fn emulated_packets() {
+    let (a, b) = UnixStream::pair().unwrap();
+    let (a, b) = (PortableSeqpacketConn::from_stream(a).unwrap(), PortableSeqpacketConn::from_stream(b).unwrap());
+    assert!(a.is_emulated());
+    a.send(b"truncated packet").unwrap();
+    a.send(&[7; 100_000]).unwrap();
+    a.send(b"").unwrap();
+    let mut buf = [0; 9];
+    assert_eq!(b.recv(&mut buf).unwrap(), (9, true));
+    assert_eq!(&buf, b"truncated");
+    let mut big = vec![0; 200_000];
+    assert_eq!(b.recv(&mut big).unwrap(), (100_000, false));
+    assert!(big[..100_000].iter().all(|&byte| byte == 7 ));
+    assert_eq!(b.recv(&mut buf).unwrap(), (0, false));
+    a.shutdown(Shutdown::Write).unwrap();
+    assert_eq!(b.recv(&mut buf).unwrap(), (0, false));
+}
+
+#[test]
+fn emulated_eof_inside_packet() {
+    let (a, b) = UnixStream::pair().unwrap();
+    (&a).write_all(&[0, 0, 0, 10, b'x']).unwrap();
+    drop(a);
+    let b = PortableSeqpacketConn::from_stream(b).unwrap();
+    assert_eq!(b.recv(&mut[0; 20]).unwrap_err().kind(), std::io::ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn emulated_listener() {
+    let path = "portable emulated.socket";
+    let _ = std::fs::remove_file(path);
+    let listener = PortableSeqpacketListener::from_stream_listener(UnixListener::bind(path).unwrap());
+    assert!(listener.is_emulated());
+    let client = PortableSeqpacketConn::from_stream(UnixStream::connect(path).unwrap()).unwrap();
+    let (server, _) = listener.accept_unix_addr().unwrap();
+    assert!(server.is_emulated());
+    server.send(b"hello").unwrap();
+    assert_eq!(client.recv(&mut[0; 10]).unwrap(), (5, false));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn emulated_streams_must_block() {
+    use std::time::Duration;
+    let (a, b) = UnixStream::pair().unwrap();
+    a.set_read_timeout(Some(Duration::from_secs(1))).unwrap();
+    let error = PortableSeqpacketConn::from_stream(a).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    drop(b);
+
+    // sending more than fits in the socket buffer waits instead of failing
+    let (a, b) = UnixStream::pair().unwrap();
+    a.set_nonblocking(true).unwrap();
+    let a = PortableSeqpacketConn::from_stream(a).unwrap();
+    let receiver = std::thread::spawn(move || {
+        let b = PortableSeqpacketConn::from_stream(b).unwrap();
+        b.recv(&mut vec![0; 1_000_000]).unwrap()
+    });
+    assert_eq!(a.send(&vec![1; 1_000_000]).unwrap(), 1_000_000);
+    assert_eq!(receiver.join().unwrap(), (1_000_000, false));
+}
@@ -4,10 +4,11 @@ extern crate uds;
 
 use std::io::ErrorKind::*;
 use std::io::{IoSlice, IoSliceMut};
-use std::os::unix::io::AsRawFd;
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd};
 
 use uds::nonblocking::UnixSeqpacketConn as NonblockingUnixSeqpacketConn;
-use uds::{UnixSeqpacketConn, UnixSeqpacketListener};
+use uds::{UnixSeqpacketConn, UnixSeqpacketListener, UnixSocketAddr, RecvPacket};
+use uds::nonblocking::UnixSeqpacketListener as NonblockingUnixSeqpacketListener;
 
 #[test]
 fn seqpacket_is_supported() {
@@ -172,3 +173,638 @@ fn vectored() {
     let mut buffers = [IoSliceMut::new(&mut array_1)];
     assert_eq!(b.recv_vectored(&mut buffers).unwrap_err().kind(), WouldBlock);
 }
+
+#[test]
+fn shutdown_write_then_drain() {
+    use std::net::Shutdown;
+    let (server, client) = UnixSeqpacketConn::pair().expect("create seqpacket pair");
+    client.send(b"one").unwrap();
+    client.send(b"two").unwrap();
+    server.shutdown(Shutdown::Write).expect("shut down write half");
+    assert_eq!(server.send(b"no").unwrap_err().kind(), BrokenPipe);
+    let mut buf = [0; 10];
+    assert_eq!(server.recv(&mut buf).unwrap(), (3, false));
+    assert_eq!(server.recv(&mut buf).unwrap(), (3, false));
+    assert_eq!(client.recv(&mut buf).unwrap(), (0, false));
+
+    let (a, b) = NonblockingUnixSeqpacketConn::pair().unwrap();
+    a.shutdown(Shutdown::Both).unwrap();
+    assert_eq!(b.recv(&mut buf).unwrap(), (0, false));
+    assert_eq!(a.recv(&mut buf).unwrap(), (0, false));
+}
+
+#[cfg(any(target_os="linux", target_os="android"))]
+#[test]
+fn recv_full_size() {
+    let (a, b) = NonblockingUnixSeqpacketConn::pair().unwrap();
+    a.send(&[7; 100]).unwrap();
+    a.send(b"").unwrap();
+    a.send(b"fits").unwrap();
+    let mut buf = [0; 10];
+    assert_eq!(b.recv_full_size(&mut buf).unwrap(), 100);
+    assert_eq!(buf, [7; 10]);
+    assert_eq!(b.recv_full_size(&mut buf).unwrap(), 0);
+    assert_eq!(b.recv_full_size(&mut buf).unwrap(), 4);
+    assert_eq!(&buf[..4], b"fits");
+    assert_eq!(b.recv_full_size(&mut buf).unwrap_err().kind(), WouldBlock);
+}
+
+#[test]
+fn take_error() {
+    let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    assert!(a.take_error().expect("get SO_ERROR").is_none());
+    drop(b);
+    let _ = a.send(b"nobody there");
+    assert!(a.take_error().expect("get SO_ERROR again").is_none());
+
+    let (a, _b) = NonblockingUnixSeqpacketConn::pair().unwrap();
+    assert!(a.take_error().unwrap().is_none());
+}
+
+#[test]
+fn accept_without_addr() {
+    let addr = UnixSocketAddr::new_unique().unwrap();
+    let listener = UnixSeqpacketListener::bind_unix_addr(addr).unwrap();
+    let client = UnixSeqpacketConn::connect_unix_addr(addr).unwrap();
+    let server = listener.accept().expect("accept without address");
+    client.send(b"hi").unwrap();
+    assert_eq!(server.recv(&mut[0; 10]).unwrap(), (2, false));
+
+    if let Some(path) = addr.as_pathname() {
+        let _ = std::fs::remove_file(path);
+    }
+    let addr = UnixSocketAddr::new_unique().unwrap();
+    let listener = NonblockingUnixSeqpacketListener::bind(addr).unwrap();
+    assert_eq!(listener.accept().unwrap_err().kind(), WouldBlock);
+    if let Some(path) = addr.as_pathname() {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+#[test]
+fn nonblocking_pair_is_nonblocking() {
+    let (a, b) = NonblockingUnixSeqpacketConn::pair().expect("create nonblocking pair");
+    for conn in &[&a, &b] {
+        let flags = unsafe { libc::fcntl(conn.as_raw_fd(), libc::F_GETFL) };
+        assert_ne!(flags & libc::O_NONBLOCK, 0, "O_NONBLOCK is set");
+        let fd_flags = unsafe { libc::fcntl(conn.as_raw_fd(), libc::F_GETFD) };
+        assert_ne!(fd_flags & libc::FD_CLOEXEC, 0, "FD_CLOEXEC is set");
+        assert_eq!(conn.recv(&mut[0; 10]).unwrap_err().kind(), WouldBlock);
+    }
+}
+
+#[test]
+fn nonblocking_connect() {
+    let addr = UnixSocketAddr::new_unique().unwrap();
+    let listener = UnixSeqpacketListener::bind_unix_addr(addr).unwrap();
+    let conn = NonblockingUnixSeqpacketConn::connect_unix_addr(addr).expect("connect nonblockingly");
+    assert!(conn.take_error().unwrap().is_none());
+    let (server, _) = listener.accept_unix_addr().unwrap();
+    server.send(b"connected").unwrap();
+    assert_eq!(conn.recv(&mut[0; 20]).unwrap(), (9, false));
+
+    #[cfg(any(target_os="linux", target_os="android"))] {
+        // fill up the backlog
+        let mut clients = Vec::new();
+        let error = loop {
+            match NonblockingUnixSeqpacketConn::connect_unix_addr(addr) {
+                Ok(client) if clients.len() < 1000 => clients.push(client),
+                Ok(_) => panic!("backlog is never full"),
+                Err(e) => break e,
+            }
+        };
+        assert_eq!(error.kind(), WouldBlock);
+    }
+    if let Some(path) = addr.as_pathname() {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+#[test]
+fn try_send_and_recv_on_blocking() {
+    let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    let mut buf = [0; 10];
+    assert_eq!(b.try_recv(&mut buf).unwrap_err().kind(), WouldBlock);
+    let mut sent = 0;
+    let error = loop {
+        match a.try_send(&[1; 1024]) {
+            Ok(_) if sent < 100_000 => sent += 1,
+            Ok(_) => panic!("send buffer is never full"),
+            Err(e) => break e,
+        }
+    };
+    assert_eq!(error.kind(), WouldBlock);
+    assert_eq!(b.try_recv_vectored(&mut[IoSliceMut::new(&mut buf)]).unwrap(), (10, true));
+    // still blocking
+    assert_eq!(b.recv(&mut buf).unwrap(), (10, true));
+}
+
+#[test]
+fn send_ancillary_builder() {
+    let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    let fds = [a.as_raw_fd(), b.as_raw_fd()];
+    a.send_ancillary()
+        .iovec(&[IoSlice::new(b"fd"), IoSlice::new(b"s + creds")])
+        .fds(&fds)
+        .credentials(uds::SendCredentials::Effective)
+        .send()
+        .expect("send fds and credentials together");
+    let mut buf = [0; 20];
+    let mut received = [-1; 3];
+    let (len, truncated, n) = b.recv_fds(&mut buf, &mut received).unwrap();
+    assert_eq!((&buf[..len], truncated, n), (&b"fds + creds"[..], false, 2));
+    for &fd in &received[..2] {
+        unsafe { libc::close(fd) };
+    }
+
+    a.send_ancillary().bytes(b"plain").send().unwrap();
+    assert_eq!(b.recv(&mut buf).unwrap(), (5, false));
+    assert_eq!(&buf[..5], b"plain");
+}
+
+#[test]
+fn empty_packet_is_not_end_of_connection() {
+    let (a, b) = NonblockingUnixSeqpacketConn::pair().unwrap();
+    let mut buf = [0; 10];
+    assert_eq!(b.recv_packet(&mut buf).unwrap_err().kind(), WouldBlock);
+    a.send(b"").unwrap();
+    a.send(b"not empty").unwrap();
+    a.send(b"").unwrap();
+    a.shutdown(std::net::Shutdown::Write).unwrap();
+    let empty = RecvPacket::Packet { len: 0, truncated: false };
+    assert_eq!(b.recv_packet(&mut buf).unwrap(), empty);
+    assert_eq!(b.recv_packet(&mut buf[..3]).unwrap(), RecvPacket::Packet { len: 3, truncated: true });
+    assert_eq!(b.recv_packet(&mut buf).unwrap(), empty);
+    assert_eq!(b.recv_packet(&mut buf).unwrap(), RecvPacket::EndOfConnection);
+    assert_eq!(b.recv_packet(&mut buf).unwrap(), RecvPacket::EndOfConnection);
+}
+
+#[test]
+fn empty_packet_then_data_before_close() {
+    let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    let mut buf = [0; 10];
+    a.send(b"").unwrap();
+    a.send(b"data").unwrap();
+    a.send(b"").unwrap();
+    drop(a);
+    let empty = RecvPacket::Packet { len: 0, truncated: false };
+    assert_eq!(b.recv_packet(&mut buf).unwrap(), empty);
+    assert_eq!(b.recv_packet(&mut buf).unwrap(), RecvPacket::Packet { len: 4, truncated: false });
+    assert_eq!(&buf[..4], b"data");
+    assert_eq!(b.recv_packet(&mut buf).unwrap(), empty);
+    assert_eq!(b.recv_packet(&mut buf).unwrap(), RecvPacket::EndOfConnection);
+}
+
+#[test]
+fn recv_packet_doesnt_affect_fd_passing() {
+    let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    a.send(b"").unwrap();
+    assert_eq!(b.recv_packet(&mut[0; 10]).unwrap(), RecvPacket::Packet { len: 0, truncated: false });
+    a.send_fds(b"fd", &[a.as_raw_fd()]).unwrap();
+    let (len, truncated, fds) = b.recv_owned_fds(&mut[0; 10], 1).unwrap();
+    assert_eq!((len, truncated, fds.len()), (2, false, 1));
+}
+
+#[test]
+fn packets_iterator_after_peer_closed() {
+    let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    a.send(b"first").unwrap();
+    a.send(b"").unwrap();
+    a.send(b"last").unwrap();
+    a.send(b"").unwrap();
+    drop(a);
+    let packets = b.packets().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(packets, vec![b"first".to_vec(), Vec::new(), b"last".to_vec(), Vec::new()]);
+}
+
+#[test]
+fn recv_info() {
+    let (a, b) = NonblockingUnixSeqpacketConn::pair().unwrap();
+    a.send(b"fits").unwrap();
+    a.send(b"").unwrap();
+    let mut buf = [0; 10];
+    let info = b.recv_vectored_info(&mut[IoSliceMut::new(&mut buf)]).unwrap();
+    assert_eq!((info.len(), info.is_truncated(), info.is_control_truncated()), (4, false, false));
+    let info = b.recv_vectored_info(&mut[IoSliceMut::new(&mut buf)]).unwrap();
+    assert!(info.is_empty());
+    assert!(!info.is_truncated());
+    assert_eq!(b.recv_vectored_info(&mut[]).unwrap_err().kind(), WouldBlock);
+}
+
+#[test]
+fn packets_iterator() {
+    let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    let big = vec![7u8; 100_000];
+    a.send(b"first").unwrap();
+    a.send(b"").unwrap();
+    a.send(&big).unwrap();
+    a.shutdown(std::net::Shutdown::Write).unwrap();
+    let mut packets = b.packets();
+    assert_eq!(packets.next().unwrap().unwrap(), b"first");
+    assert_eq!(packets.next().unwrap().unwrap(), b"");
+    assert_eq!(packets.next().unwrap().unwrap(), big);
+    assert!(packets.next().is_none());
+}
+
+#[test]
+fn packets_iterator_doesnt_affect_fd_passing() {
+    let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    a.send(b"").unwrap();
+    assert_eq!(b.packets().next().unwrap().unwrap(), b"");
+    a.send_fds(b"fd", &[a.as_raw_fd()]).unwrap();
+    let (len, truncated, fds) = b.recv_fds_limited(&mut[0; 10], 1).unwrap();
+    assert_eq!((len, truncated, fds.len()), (2, false, 1));
+}
+
+#[test]
+fn generic_over_blocking_and_nonblocking() {
+    fn forward<C: uds::UnixSeqpacketConnExt>(from: &C,  to: &C) {
+        let mut buf = [0; 20];
+        let (len, truncated) = from.recv_vectored(&mut[IoSliceMut::new(&mut buf)]).unwrap();
+        assert!(!truncated);
+        uds::UnixSeqpacketConnExt::send(to, &buf[..len]).unwrap();
+    }
+
+    let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    let (c, d) = UnixSeqpacketConn::pair().unwrap();
+    a.send(b"blocking").unwrap();
+    forward(&b, &c);
+    assert_eq!(d.recv(&mut[0; 20]).unwrap(), (8, false));
+
+    let (a, b) = NonblockingUnixSeqpacketConn::pair().unwrap();
+    let (c, d) = NonblockingUnixSeqpacketConn::pair().unwrap();
+    a.send(b"nonblocking").unwrap();
+    forward(&b, &c);
+    assert_eq!(d.recv(&mut[0; 20]).unwrap(), (11, false));
+}
+
+#[test]
+fn nonblocking_bind_with_cleanup() {
+    let path = "nonblocking seqpacket cleanup.socket";
+    let _ = std::fs::remove_file(path);
+    let listener = NonblockingUnixSeqpacketListener::bind_with_cleanup(path).unwrap();
+    assert_eq!(listener.accept_unix_addr().unwrap_err().kind(), WouldBlock);
+    // doesn't replace existing files
+    assert!(NonblockingUnixSeqpacketListener::bind_with_cleanup(path).is_err());
+    assert!(std::path::Path::new(path).exists());
+    drop(listener);
+    assert!(!std::path::Path::new(path).exists());
+}
+
+#[test]
+fn bind_taking_over_stale_socket() {
+    let path = "seqpacket takeover.socket";
+    let _ = std::fs::remove_file(path);
+    drop(UnixSeqpacketListener::bind(path).unwrap());
+    let listener = NonblockingUnixSeqpacketListener::bind_unix_addr_taking_over(path)
+        .expect("replace stale socket");
+    let _conn = UnixSeqpacketConn::connect(path).expect("connect to new listener");
+    let err = UnixSeqpacketListener::bind_unix_addr_taking_over(path).unwrap_err();
+    assert_eq!(err.kind(), AddrInUse);
+    drop(listener);
+    std::fs::remove_file(path).unwrap();
+
+    // a stream listener refuses seqpacket connections, but isn't stale
+    let _stream_listener = std::os::unix::net::UnixListener::bind(path).unwrap();
+    let err = UnixSeqpacketListener::bind_unix_addr_taking_over(path).unwrap_err();
+    assert_eq!(err.kind(), AddrInUse);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn bytes_available() {
+    let (a, b) = NonblockingUnixSeqpacketConn::pair().unwrap();
+    assert_eq!(b.bytes_available().unwrap(), 0);
+    a.send(b"first").unwrap();
+    assert!(b.bytes_available().unwrap() >= 5);
+    a.send(b"second").unwrap();
+    #[cfg(any(target_os="linux", target_os="android"))]
+    assert_eq!(b.bytes_available().unwrap(), 11);
+    b.recv(&mut[0; 10]).unwrap();
+    b.recv(&mut[0; 10]).unwrap();
+    assert_eq!(b.bytes_available().unwrap(), 0);
+}
+
+#[test]
+fn nonblocking_recv_to_vec() {
+    let (a, b) = NonblockingUnixSeqpacketConn::pair().unwrap();
+    let mut packet = vec![9; 100];
+    assert_eq!(b.recv_to_vec(&mut packet).unwrap_err().kind(), WouldBlock);
+    a.send(&[2; 700]).unwrap();
+    a.send(b"").unwrap();
+    assert_eq!(b.recv_to_vec(&mut packet).unwrap(), 700);
+    assert_eq!(packet, vec![2; 700]);
+    assert_eq!(b.recv_to_vec(&mut packet).unwrap(), 0);
+    assert!(packet.is_empty());
+}
+
+#[test]
+fn max_send_size() {
+    let (a, b) = NonblockingUnixSeqpacketConn::pair().unwrap();
+    let max = a.max_send_size().unwrap();
+    assert!(max > 0);
+    let packet = vec![5; max + 1];
+    assert_eq!(a.send(&packet).unwrap_err().kind(), InvalidInput);
+    assert_eq!(a.send_vectored(&[IoSlice::new(&packet)]).unwrap_err().kind(), InvalidInput);
+    #[cfg(any(target_os="linux", target_os="android"))] {
+        assert_eq!(a.send(&packet[..max]).unwrap(), max);
+        assert_eq!(b.recv(&mut vec![0; max]).unwrap(), (max, false));
+    }
+    let _ = b;
+}
+
+#[test]
+fn fragmenting() {
+    let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    let sender = uds::FragmentingSeqpacketConn::with_fragment_size(a, 10).unwrap();
+    let receiver = uds::FragmentingSeqpacketConn::new(b).unwrap();
+    for len in &[0, 1, 8, 9, 10, 18, 19, 100] {
+        let message = (0..*len).map(|n| n as u8 ).collect::<Vec<u8>>();
+        sender.send(&message).unwrap();
+        assert_eq!(receiver.recv().unwrap(), Some(message));
+    }
+
+    // a multi-megabyte message with the default fragment size
+    let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    let sender = uds::FragmentingSeqpacketConn::new(a).unwrap();
+    let receiver = uds::FragmentingSeqpacketConn::new(b).unwrap();
+    let big = vec![b'b'; 3_000_000];
+    let thread = std::thread::spawn(move || receiver.recv() );
+    sender.send(&big).unwrap();
+    assert_eq!(thread.join().unwrap().unwrap(), Some(big));
+
+    // end of connection in the middle of a message
+    let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    let receiver = uds::FragmentingSeqpacketConn::new(b).unwrap();
+    a.send(&[1, b'x']).unwrap();
+    drop(a);
+    assert_eq!(receiver.recv().unwrap_err().kind(), UnexpectedEof);
+}
+
+#[test]
+fn is_nonblocking() {
+    let addr = UnixSocketAddr::new_unique().unwrap();
+    let listener = UnixSeqpacketListener::bind_unix_addr(addr).unwrap();
+    assert!(!listener.is_nonblocking().unwrap());
+    let nonblocking_listener = NonblockingUnixSeqpacketListener::bind_unix_addr(
+        UnixSocketAddr::new_unique().unwrap()
+    ).unwrap();
+    assert!(nonblocking_listener.is_nonblocking().unwrap());
+    let conn = UnixSeqpacketConn::connect_unix_addr(addr).unwrap();
+    assert!(!conn.is_nonblocking().unwrap());
+    let (accepted, _) = listener.accept_nonblocking_unix_addr().unwrap();
+    assert!(accepted.is_nonblocking().unwrap());
+    for addr in [addr, nonblocking_listener.local_unix_addr().unwrap()] {
+        if let Some(path) = addr.as_pathname() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+#[test]
+fn deadlines() {
+    use std::time::{Duration, Instant};
+    let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    let start = Instant::now();
+    let deadline = start + Duration::from_millis(50);
+    assert_eq!(b.recv_deadline(&mut[0; 10], deadline).unwrap_err().kind(), TimedOut);
+    assert!(Instant::now() >= deadline);
+    // fill the send buffer
+    let packet = [0; 1000];
+    while a.try_send(&packet).is_ok() {}
+    let deadline = Instant::now() + Duration::from_millis(20);
+    assert_eq!(a.send_deadline(&packet, deadline).unwrap_err().kind(), TimedOut);
+    // Linux only reports the socket as writable when most of the buffer is free
+    while b.try_recv(&mut[0; 1000]).is_ok() {}
+    let deadline = Instant::now() + Duration::from_secs(10);
+    assert_eq!(a.send_deadline(&packet, deadline).unwrap(), 1000);
+}
+
+#[test]
+fn peer_liveness() {
+    let (a, b) = NonblockingUnixSeqpacketConn::pair().unwrap();
+    assert!(a.is_peer_alive().unwrap());
+    a.send(b"last words").unwrap();
+    a.shutdown(std::net::Shutdown::Write).unwrap();
+    assert!(b.is_peer_alive().unwrap());
+    drop(a);
+    assert!(!b.is_peer_alive().unwrap());
+    // queued packets can still be received
+    assert_eq!(b.recv(&mut[0; 20]).unwrap(), (10, false));
+}
+
+#[test]
+fn byte_stream() {
+    use std::io::{Read, Write};
+    let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    let mut writer = uds::SeqpacketByteStream::with_max_packet_size(a, 4).unwrap();
+    writer.write_all(b"hello world").unwrap();
+    writer.flush().unwrap();
+    let a = writer.into_inner().unwrap();
+    a.send(b"").unwrap(); // empty packets are skipped
+    a.send(b"!").unwrap();
+    let mut reader = uds::SeqpacketByteStream::new(b).unwrap();
+    let mut buf = [0; 3];
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hel");
+    #[cfg(any(target_os="linux", target_os="android"))]
+    assert_eq!(reader.get_ref().bytes_available().unwrap(), 8); // "o wo", "rld" and "!"
+    drop(a);
+    let mut rest = Vec::new();
+    reader.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, b"lo world!");
+}
+
+#[test]
+fn byte_stream_doesnt_affect_fd_passing() {
+    use std::io::Read;
+    let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    a.send(b"byte").unwrap();
+    let mut reader = uds::SeqpacketByteStream::new(b).unwrap();
+    let mut buf = [0; 4];
+    reader.read_exact(&mut buf).unwrap();
+    a.send_fds(b"fd", &[a.as_raw_fd()]).unwrap();
+    let (len, truncated, fds) = reader.get_ref().recv_owned_fds(&mut[0; 10], 1).unwrap();
+    assert_eq!((len, truncated, fds.len()), (2, false, 1));
+}
+
+#[test]
+fn pass_sockets_with_type() {
+    use uds::PassedSocket;
+    let (supervisor, worker) = UnixSeqpacketConn::pair().unwrap();
+    let addr = UnixSocketAddr::new_unique().unwrap();
+    let listener = UnixSeqpacketListener::bind_unix_addr(addr).expect("bind");
+    supervisor.send_socket(&PassedSocket::from(listener)).expect("send listener");
+    let listener = match worker.recv_socket().expect("receive listener") {
+        PassedSocket::SeqpacketListener(listener) => listener,
+        other => panic!("received {:?} instead of seqpacket listener", other),
+    };
+    let _client = UnixSeqpacketConn::connect_unix_addr(addr).expect("connect to passed listener");
+    listener.accept_unix_addr().expect("accept with passed listener");
+    if let Some(path) = addr.as_pathname() {
+        let _ = std::fs::remove_file(path);
+    }
+
+    // a tag that doesn't match the socket is rejected
+    let (stream, _) = std::os::unix::net::UnixStream::pair().unwrap();
+    supervisor.send_fds(&[1], &[stream.as_raw_fd()]).unwrap();
+    assert_eq!(worker.recv_socket().unwrap_err().kind(), InvalidData);
+    supervisor.send(&[2]).unwrap();
+    assert_eq!(worker.recv_socket().unwrap_err().kind(), InvalidData);
+    supervisor.send(&[]).unwrap();
+    assert_eq!(worker.recv_socket().unwrap_err().kind(), InvalidData);
+    drop(supervisor);
+    assert_eq!(worker.recv_socket().unwrap_err().kind(), UnexpectedEof);
+}
+
+#[test]
+fn passed_sockets_keep_their_mode() {
+    use uds::PassedSocket;
+    let (supervisor, worker) = UnixSeqpacketConn::pair().unwrap();
+    #[cfg(any(target_os="linux", target_os="android"))]
+    worker.set_receive_credentials(true).unwrap();
+    let (a, _b) = NonblockingUnixSeqpacketConn::pair().unwrap();
+    let a = unsafe { UnixSeqpacketConn::from_raw_fd(a.into_raw_fd()) };
+    supervisor.send_socket(&PassedSocket::from(a)).expect("send nonblocking socket");
+    let a = match worker.recv_socket().expect("receive socket") {
+        PassedSocket::SeqpacketConn(conn) => conn,
+        other => panic!("received {:?} instead of seqpacket connection", other),
+    };
+    assert_eq!(a.recv(&mut[0; 10]).unwrap_err().kind(), WouldBlock);
+}
+
+#[test]
+fn peek_ancillary_capacity() {
+    use uds::{AncillaryBuf, AncillaryMessage};
+    let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    a.send_fds(b"many", &[a.as_raw_fd(); 200]).unwrap();
+    let capacity = b.peek_ancillary_capacity().expect("peek ancillary capacity");
+    assert!(capacity > AncillaryBuf::MAX_STACK_CAPACITY);
+    let mut ancillary_buf = AncillaryBuf::with_capacity(capacity);
+    let mut buf = [0; 10];
+    let (len, ancillary) = b.recv_vectored_ancillary(
+        &mut[IoSliceMut::new(&mut buf)],
+        &mut ancillary_buf,
+    ).expect("receive with big enough buffer");
+    assert_eq!(len, 4);
+    assert!(!ancillary.ancillary_truncated());
+    assert_eq!(ancillary.ancillary_len(), capacity);
+    let received = ancillary.map(|message| match message {
+        AncillaryMessage::Fds(fds) => {
+            fds.iter().for_each(|&fd| unsafe { libc::close(fd); } );
+            fds.len()
+        }
+        _ => 0,
+    }).sum::<usize>();
+    assert_eq!(received, 200);
+}
+
+#[test]
+fn receive_timestamps() {
+    use std::time::{Duration, SystemTime};
+    let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    a.send(b"untimed").unwrap();
+    assert_eq!(b.recv_with_timestamp(&mut[0; 10]).unwrap(), (7, false, None));
+
+    b.set_receive_timestamps(true).expect("enable timestamps");
+    let before = SystemTime::now();
+    a.send(b"timed").unwrap();
+    let after = SystemTime::now();
+    let (len, truncated, timestamp) = b.recv_with_timestamp(&mut[0; 10]).unwrap();
+    assert_eq!((len, truncated), (5, false));
+    let timestamp = timestamp.expect("receive timestamp");
+    // the clock used might have lower resolution
+    assert!(timestamp + Duration::from_millis(10) >= before);
+    assert!(timestamp <= after + Duration::from_millis(10));
+}
+
+#[cfg(any(target_os="linux", target_os="android"))]
+#[test]
+fn unknown_control_messages() {
+    use uds::{AncillaryBuf, AncillaryMessage};
+    // SO_PASSPIDFD and SCM_PIDFD from Linux 6.5, which the crate doesn't parse
+    #[cfg(not(any(target_arch="sparc", target_arch="sparc64")))]
+    const SO_PASSPIDFD: libc::c_int = 76;
+    #[cfg(any(target_arch="sparc", target_arch="sparc64"))]
+    const SO_PASSPIDFD: libc::c_int = 0x55;
+    const SCM_PIDFD: libc::c_int = 0x04;
+
+    let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    let enable: libc::c_int = 1;
+    let size = std::mem::size_of::<libc::c_int>() as libc::socklen_t;
+    let ptr = &enable as *const libc::c_int as *const libc::c_void;
+    let fd = b.as_raw_fd();
+    if unsafe { libc::setsockopt(fd, libc::SOL_SOCKET, SO_PASSPIDFD, ptr, size) } == -1 {
+        return; // older kernel
+    }
+    a.send(b"pidfd").unwrap();
+    let mut ancillary_buf = AncillaryBuf::with_capacity(64);
+    let mut buf = [0; 10];
+    let (_, ancillary) = b.recv_vectored_ancillary(
+        &mut[IoSliceMut::new(&mut buf)],
+        &mut ancillary_buf,
+    ).unwrap();
+    let mut pidfds = 0;
+    for message in ancillary {
+        if let AncillaryMessage::Unknown { level: libc::SOL_SOCKET, ty: SCM_PIDFD, data } = message {
+            assert_eq!(data.len(), std::mem::size_of::<libc::c_int>());
+            let pidfd = unsafe { std::ptr::read_unaligned(data.as_ptr() as *const libc::c_int) };
+            assert!(pidfd >= 0);
+            unsafe { libc::close(pidfd) };
+            pidfds += 1;
+        }
+    }
+    assert_eq!(pidfds, 1);
+}
+
+#[test]
+fn wait_for_readiness_without_event_loop() {
+    use std::time::Duration;
+
+    let timeout = Some(Duration::from_millis(20));
+    let addr = UnixSocketAddr::new_unique().unwrap();
+    let listener = NonblockingUnixSeqpacketListener::bind_unix_addr(addr).expect("bind");
+    assert_eq!(listener.wait_readable(timeout).unwrap_err().kind(), TimedOut);
+    let client = NonblockingUnixSeqpacketConn::connect_unix_addr(addr).expect("connect");
+    listener.wait_readable(timeout).expect("wait for connection");
+    let (server, _) = listener.accept_unix_addr().expect("accept");
+
+    // fill the send buffer
+    while client.send(&[0; 1024]).is_ok() {}
+    assert_eq!(client.wait_writable(timeout).unwrap_err().kind(), TimedOut);
+    server.wait_readable(None).expect("wait for packet");
+    while server.recv(&mut[0; 1024]).is_ok() {}
+    client.wait_writable(timeout).expect("wait for room");
+
+    drop(client);
+    server.wait_readable(timeout).expect("wait for disconnect");
+    if let Some(path) = addr.as_pathname() {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+#[test]
+fn drive_nonblocking_connect() {
+    use std::time::Duration;
+
+    let addr = UnixSocketAddr::new_unique().unwrap();
+    let err = NonblockingUnixSeqpacketConn::start_connect(addr).expect_err("connect to nothing");
+    assert!(err.kind() == NotFound || err.kind() == ConnectionRefused);
+
+    let listener = NonblockingUnixSeqpacketListener::bind_unix_addr(addr).expect("bind");
+    let (conn, connected) = NonblockingUnixSeqpacketConn::start_connect(addr).expect("connect");
+    if !connected {
+        conn.finish_connect(Some(Duration::from_secs(1))).expect("finish connecting");
+    }
+    assert!(conn.poll_connected().expect("check connected"));
+    conn.finish_connect(Some(Duration::from_secs(1))).expect("already connected");
+    let (server, _) = listener.accept_unix_addr().expect("accept");
+    conn.send(b"connected").expect("send");
+    assert_eq!(server.recv(&mut[0; 10]).expect("receive"), (9, false));
+
+    let (a, _b) = NonblockingUnixSeqpacketConn::pair().unwrap();
+    assert!(a.poll_connected().expect("check pair"));
+    if let Some(path) = addr.as_pathname() {
+        let _ = std::fs::remove_file(path);
+    }
+}
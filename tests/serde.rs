@@ -0,0 +1,40 @@
+#![cfg(feature="serde")]
+
+extern crate uds;
+extern crate serde_json;
+
+use uds::UnixSocketAddr;
+
+#[test]
+fn path_and_unnamed() {
+    let unnamed = UnixSocketAddr::new_unspecified();
+    assert_eq!(serde_json::to_string(&unnamed).unwrap(), r#""unnamed""#);
+    assert_eq!(serde_json::from_str::<UnixSocketAddr>(r#""unnamed""#).unwrap(), unnamed);
+    assert_eq!(serde_json::from_str::<UnixSocketAddr>(r#""""#).unwrap(), unnamed);
+
+    let path = UnixSocketAddr::from_path("/run/app.sock").unwrap();
+    assert_eq!(serde_json::to_string(&path).unwrap(), r#""/run/app.sock""#);
+    assert_eq!(serde_json::from_str::<UnixSocketAddr>(r#""/run/app.sock""#).unwrap(), path);
+
+    let at_path = UnixSocketAddr::from_path("@relative").unwrap();
+    let json = serde_json::to_string(&at_path).unwrap();
+    assert_eq!(serde_json::from_str::<UnixSocketAddr>(&json).unwrap(), at_path);
+}
+
+#[test]
+fn abstract_names() {
+    let json = r#""@binary\\xff\\0name""#;
+    match serde_json::from_str::<UnixSocketAddr>(json) {
+        Ok(addr) => {
+            assert!(addr.is_abstract());
+            assert_eq!(serde_json::to_string(&addr).unwrap(), json);
+        }
+        Err(_) => assert!(!UnixSocketAddr::has_abstract_addresses()),
+    }
+}
+
+#[test]
+fn invalid() {
+    assert!(serde_json::from_str::<UnixSocketAddr>(r#""bad \\escape""#).is_err());
+    assert!(serde_json::from_str::<UnixSocketAddr>("42").is_err());
+}
@@ -0,0 +1,66 @@
+#![cfg(feature="socket2")]
+
+extern crate uds;
+extern crate socket2;
+
+use std::convert::TryFrom;
+use std::io::ErrorKind::*;
+
+use socket2::{Socket, SockAddr, Domain, Type};
+
+use uds::UnixSocketAddr;
+
+#[test]
+fn addr_conversions() {
+    let path = UnixSocketAddr::from_path("socket2 addr.socket").unwrap();
+    let sockaddr = SockAddr::from(path);
+    assert!(sockaddr.is_unix());
+    assert_eq!(sockaddr.len(), path.as_raw().1);
+    assert_eq!(UnixSocketAddr::try_from(&sockaddr).unwrap(), path);
+
+    let unnamed = UnixSocketAddr::new_unspecified();
+    assert_eq!(UnixSocketAddr::try_from(SockAddr::from(unnamed)).unwrap(), unnamed);
+
+    if UnixSocketAddr::has_abstract_addresses() {
+        let name = UnixSocketAddr::from_abstract(b"socket2\0conversion").unwrap();
+        assert_eq!(UnixSocketAddr::try_from(SockAddr::from(name)).unwrap(), name);
+    }
+
+    let ip = SockAddr::from("127.0.0.1:80".parse::<std::net::SocketAddr>().unwrap());
+    assert_eq!(UnixSocketAddr::try_from(&ip).unwrap_err().kind(), InvalidData);
+}
+
+#[cfg(not(target_vendor="apple"))]
+#[test]
+fn seqpacket_conversions() {
+    use uds::{UnixSeqpacketConn, UnixSeqpacketListener};
+    use uds::nonblocking::UnixSeqpacketConn as NonblockingUnixSeqpacketConn;
+
+    let addr = UnixSocketAddr::new_unique().unwrap();
+    let socket = Socket::new(Domain::UNIX, Type::from(libc::SOCK_SEQPACKET), None).unwrap();
+    socket.bind(&SockAddr::from(addr)).unwrap();
+    socket.listen(1).unwrap();
+    let listener = UnixSeqpacketListener::try_from(socket).expect("convert listener");
+    assert_eq!(listener.local_unix_addr().unwrap(), addr);
+
+    let socket = Socket::new(Domain::UNIX, Type::from(libc::SOCK_SEQPACKET), None).unwrap();
+    socket.connect(&SockAddr::from(addr)).unwrap();
+    let conn = NonblockingUnixSeqpacketConn::try_from(socket).expect("convert connection");
+    let (accepted, _) = listener.accept_unix_addr().unwrap();
+    assert_eq!(conn.recv(&mut[0; 10]).unwrap_err().kind(), WouldBlock);
+    accepted.send(b"socket2").unwrap();
+    let socket = Socket::from(accepted);
+    assert_eq!(socket.peer_addr().unwrap().len(), UnixSocketAddr::new_unspecified().as_raw().1);
+
+    let stream = Socket::new(Domain::UNIX, Type::STREAM, None).unwrap();
+    let (error, stream) = UnixSeqpacketConn::try_from(stream).unwrap_err();
+    assert_eq!(error.kind(), InvalidInput);
+    assert_eq!(stream.r#type().unwrap(), Type::STREAM);
+    let ip = Socket::new(Domain::IPV4, Type::from(libc::SOCK_SEQPACKET), None);
+    if let Ok(ip) = ip {
+        assert!(UnixSeqpacketConn::try_from(ip).is_err());
+    }
+    if let Some(path) = addr.as_pathname() {
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,93 @@
+extern crate uds;
+extern crate libc;
+
+#[cfg(any(target_os="linux", target_os="android"))]
+use std::fs::File;
+use std::io::{Read, Write};
+#[cfg(any(target_os="linux", target_os="android"))]
+use std::os::unix::io::FromRawFd;
+use std::os::unix::net::UnixStream;
+
+use uds::UnixStreamExt;
+
+#[cfg(any(target_os="linux", target_os="android"))]
+#[test]
+fn splice_between_streams() {
+    let mut fds = [-1; 2];
+    assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0, "create pipe");
+    let (pipe_read, pipe_write) = unsafe { (File::from_raw_fd(fds[0]), File::from_raw_fd(fds[1])) };
+    let (mut client, proxy_in) = UnixStream::pair().unwrap();
+    let (proxy_out, mut server) = UnixStream::pair().unwrap();
+
+    let message = vec![b'm'; 10_000];
+    client.write_all(&message).unwrap();
+    drop(client);
+    let mut total = 0;
+    loop {
+        let moved = proxy_in.splice_to(&pipe_write, 4096).expect("splice into pipe");
+        if moved == 0 {
+            break;
+        }
+        let mut remaining = moved;
+        while remaining > 0 {
+            remaining -= proxy_out.splice_from(&pipe_read, remaining).expect("splice from pipe");
+        }
+        total += moved;
+    }
+    assert_eq!(total, message.len());
+    drop(proxy_out);
+    let mut received = Vec::new();
+    server.read_to_end(&mut received).unwrap();
+    assert_eq!(received, message);
+}
+
+#[test]
+fn stream_bytes_available() {
+    let (mut a, mut b) = UnixStream::pair().unwrap();
+    a.write_all(b"one").unwrap();
+    a.write_all(b"two").unwrap();
+    assert_eq!(b.bytes_available().unwrap(), 6);
+    b.read_exact(&mut[0; 4]).unwrap();
+    assert_eq!(b.bytes_available().unwrap(), 2);
+}
+
+#[test]
+fn crate_stream_types() {
+    use std::io::ErrorKind;
+    use std::os::unix::io::AsRawFd;
+
+    let addr = uds::UnixSocketAddr::new_unique().unwrap();
+    let listener = uds::UnixListener::bind(addr).expect("bind");
+    let mut client = uds::UnixStream::connect(addr).expect("connect");
+    let (mut server, client_addr) = listener.accept().expect("accept");
+    assert!(client_addr.is_unnamed());
+    assert_eq!(client.peer_unix_addr().unwrap(), addr);
+    let creds = server.initial_peer_credentials();
+    if cfg!(any(target_os="linux", target_os="android", target_os="freebsd", target_vendor="apple")) {
+        assert_eq!(creds.expect("get credentials").euid(), unsafe { libc::geteuid() } as u32);
+    }
+
+    client.write_all(b"text").unwrap();
+    let mut buf = [0; 4];
+    server.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"text");
+    server.send_fds(b"fd", &[server.as_raw_fd()]).expect("send fd");
+    let (len, fds) = client.recv_owned_fds(&mut buf, 2).expect("receive fd");
+    assert_eq!((len, fds.len()), (2, 1));
+
+    let server = server.into_nonblocking().expect("make nonblocking");
+    assert_eq!((&server).read(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+    let client: UnixStream = client.into_std();
+    assert!(UnixStreamExt::peer_unix_addr(&client).is_ok());
+    drop(client);
+    assert_eq!((&server).read(&mut buf).expect("read end of stream"), 0);
+
+    let listener = listener.into_nonblocking().expect("make listener nonblocking");
+    assert_eq!(listener.accept().unwrap_err().kind(), ErrorKind::WouldBlock);
+    let _client = uds::nonblocking::UnixStream::connect(addr).expect("connect nonblocking");
+    let (conn, _) = listener.accept().expect("accept nonblocking");
+    assert_eq!((&conn).read(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+    if let Some(path) = addr.as_pathname() {
+        let _ = std::fs::remove_file(path);
+    }
+}
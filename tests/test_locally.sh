@@ -1,5 +1,5 @@
 #!/bin/sh
-MSRV="1.36.0"
+MSRV="1.70.0"
 
 set -e
 export RUST_BACKTRACE=1
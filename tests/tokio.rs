@@ -0,0 +1,295 @@
+#![cfg(feature="tokio")]
+
+extern crate uds;
+extern crate tokio;
+
+use std::future::poll_fn;
+use std::os::unix::io::AsRawFd;
+use std::task::Poll;
+
+use tokio::net::UnixStream;
+use tokio::runtime::Builder;
+
+use uds::{TokioUnixStreamExt, TokioSeqpacketConn, UnixSeqpacketConn};
+
+#[test]
+fn tokio_stream_fd_passing() {
+    let runtime = Builder::new_current_thread().enable_io().build().expect("create runtime");
+    runtime.block_on(async {
+        let (a, b) = UnixStream::pair().expect("create tokio stream pair");
+        assert!(a.local_unix_addr().unwrap().is_unnamed());
+        assert!(b.peer_unix_addr().unwrap().is_unnamed());
+        let creds = b.initial_peer_credentials();
+        if cfg!(any(target_os="linux", target_os="android", target_os="freebsd", target_vendor="apple")) {
+            assert_eq!(creds.expect("get credentials").euid(), unsafe { libc::geteuid() } as u32);
+        }
+
+        // receiving before anything is sent must wait instead of failing
+        let receiver = async {
+            let mut buf = [0; 10];
+            let mut fd_buf = [-1; 3];
+            let (len, fds) = b.recv_fds(&mut buf, &mut fd_buf).await.expect("receive fds");
+            for &fd in &fd_buf[..fds] {
+                unsafe { libc::close(fd) };
+            }
+            (buf[..len].to_vec(), fds)
+        };
+        let sender = async {
+            tokio::task::yield_now().await;
+            a.send_fds(b"two", &[a.as_raw_fd(), b.as_raw_fd()]).await.expect("send fds")
+        };
+        let ((received, fds), sent) = tokio::join!(receiver, sender);
+        assert_eq!((&received[..], fds, sent), (&b"two"[..], 2, 3));
+    });
+}
+
+#[cfg(not(target_vendor="apple"))]
+#[test]
+fn tokio_seqpacket_byte_stream() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use uds::nonblocking::UnixSeqpacketConn;
+    use uds::TokioSeqpacketByteStream;
+
+    let runtime = Builder::new_current_thread().enable_io().build().expect("create runtime");
+    runtime.block_on(async {
+        let (a, b) = UnixSeqpacketConn::pair().expect("create seqpacket pair");
+        let (c, d) = UnixSeqpacketConn::pair().expect("create seqpacket pair");
+
+        // each flush sends one packet
+        let mut writer = TokioSeqpacketByteStream::with_max_packet_size(a, 4).unwrap();
+        writer.write_all(b"ab").await.unwrap();
+        writer.write_all(b"c").await.unwrap();
+        writer.flush().await.expect("flush");
+        writer.write_all(b"defghi").await.unwrap();
+        writer.shutdown().await.expect("shut down");
+        let mut buf = [0; 10];
+        assert_eq!(b.recv(&mut buf).unwrap(), (3, false));
+        assert_eq!(b.recv(&mut buf).unwrap(), (4, false));
+        assert_eq!(&buf[..4], b"defg");
+        assert_eq!(b.recv(&mut buf).unwrap(), (2, false));
+        assert_eq!(b.recv(&mut buf).unwrap(), (0, false));
+
+        // reading waits for packets and ignores their boundaries
+        let reader = async {
+            let mut reader = TokioSeqpacketByteStream::new(d).unwrap();
+            let mut small = [0; 2];
+            reader.read_exact(&mut small).await.expect("read part of packet");
+            let mut rest = Vec::new();
+            reader.read_to_end(&mut rest).await.expect("read until end");
+            (small, rest)
+        };
+        let sender = async {
+            tokio::task::yield_now().await;
+            c.send(b"xyz").unwrap();
+            c.send(b"").unwrap();
+            c.send(b"w").unwrap();
+            drop(c);
+        };
+        let ((small, rest), ()) = tokio::join!(reader, sender);
+        assert_eq!((&small, &rest[..]), (b"xy", &b"zw"[..]));
+    });
+}
+
+#[cfg(not(target_vendor="apple"))]
+#[test]
+fn tokio_seqpacket_byte_stream_leaves_fd_passing_alone() {
+    use tokio::io::AsyncReadExt;
+    use uds::nonblocking::UnixSeqpacketConn;
+    use uds::TokioSeqpacketByteStream;
+
+    let runtime = Builder::new_current_thread().enable_io().build().expect("create runtime");
+    runtime.block_on(async {
+        let (a, b) = UnixSeqpacketConn::pair().expect("create seqpacket pair");
+        a.send(b"byte").unwrap();
+        let mut reader = TokioSeqpacketByteStream::new(b).unwrap();
+        reader.read_exact(&mut[0; 4]).await.expect("read packet");
+        a.send_fds(b"fd", &[a.as_raw_fd()]).unwrap();
+        let (len, truncated, fds) = reader.get_ref().recv_owned_fds(&mut[0; 10], 1).unwrap();
+        assert_eq!((len, truncated, fds.len()), (2, false, 1));
+    });
+}
+
+#[test]
+fn tokio_datagram_fd_passing() {
+    use tokio::net::UnixDatagram;
+    use uds::{TokioUnixDatagramExt, UnixSocketAddr};
+
+    let runtime = Builder::new_current_thread().enable_io().build().expect("create runtime");
+    runtime.block_on(async {
+        let server_addr = UnixSocketAddr::new_unique().unwrap();
+        let server = UnixDatagram::bind_unix_addr(server_addr).expect("bind server");
+        assert_eq!(server.local_unix_addr().unwrap(), server_addr);
+        let client_addr = UnixSocketAddr::new_unique().unwrap();
+        let client = UnixDatagram::bind_unix_addr(client_addr).expect("bind client");
+
+        let receiver = async {
+            let mut buf = [0; 10];
+            let mut fd_buf = [-1; 2];
+            let (len, fds, from) = server.recv_fds_from(&mut buf, &mut fd_buf).await
+                .expect("receive fds");
+            for &fd in &fd_buf[..fds] {
+                unsafe { libc::close(fd) };
+            }
+            let (plain_len, plain_from) = server.recv_from_unix_addr(&mut buf[len..]).await
+                .expect("receive datagram");
+            (buf[..len+plain_len].to_vec(), fds, from, plain_from)
+        };
+        let sender = async {
+            tokio::task::yield_now().await;
+            let sent = client.send_fds_to(b"fd", &[client.as_raw_fd()], server_addr).await
+                .expect("send fds");
+            client.send_to_unix_addr(b"s", server_addr).await.expect("send datagram");
+            sent
+        };
+        let ((received, fds, from, plain_from), sent) = tokio::join!(receiver, sender);
+        assert_eq!((&received[..], fds, sent), (&b"fds"[..], 1, 2));
+        assert_eq!((from, plain_from), (client_addr, client_addr));
+
+        client.connect_to_unix_addr(server_addr).expect("connect");
+        assert_eq!(client.peer_unix_addr().unwrap(), server_addr);
+        for addr in &[server_addr, client_addr] {
+            if let Some(path) = addr.as_pathname() {
+                let _ = std::fs::remove_file(path);
+            }
+        }
+    });
+}
+
+#[cfg(all(feature="tokio-util", not(target_vendor="apple")))]
+#[test]
+fn tokio_codec_over_seqpacket() {
+    use futures_util::{SinkExt, StreamExt};
+    use tokio_util::codec::{BytesCodec, LinesCodec};
+    use uds::nonblocking::UnixSeqpacketConn;
+    use uds::TokioSeqpacketFramed;
+
+    let runtime = Builder::new_current_thread().enable_io().build().expect("create runtime");
+    runtime.block_on(async {
+        let (a, b) = UnixSeqpacketConn::pair().expect("create seqpacket pair");
+        let mut sender = TokioSeqpacketFramed::new(a, LinesCodec::new()).unwrap();
+        let mut receiver = TokioSeqpacketFramed::new(b, LinesCodec::new()).unwrap();
+        sender.send("first").await.expect("send frame");
+        sender.send("").await.expect("send empty line");
+        sender.get_ref().send(b"two\nlines\n").unwrap();
+        sender.get_ref().send(b"unterminated").unwrap();
+        sender.get_ref().send(b"").unwrap();
+        SinkExt::<&str>::close(&mut sender).await.expect("close");
+
+        assert_eq!(receiver.next().await.unwrap().expect("receive frame"), "first");
+        assert_eq!(receiver.next().await.unwrap().expect("receive empty line"), "");
+        assert!(receiver.next().await.unwrap().is_err());
+        // decode_eof() accepts a missing newline
+        assert_eq!(receiver.next().await.unwrap().expect("receive last line"), "unterminated");
+        // the empty packet is skipped
+        assert!(receiver.next().await.is_none());
+
+        // each frame is one packet
+        let (a, b) = UnixSeqpacketConn::pair().expect("create seqpacket pair");
+        let mut sender = TokioSeqpacketFramed::new(a, BytesCodec::new()).unwrap();
+        sender.feed(bytes::Bytes::from_static(b"ab")).await.unwrap();
+        sender.feed(bytes::Bytes::from_static(b"cde")).await.unwrap();
+        SinkExt::<bytes::Bytes>::flush(&mut sender).await.unwrap();
+        assert_eq!(b.recv(&mut[0; 10]).unwrap(), (2, false));
+        assert_eq!(b.recv(&mut[0; 10]).unwrap(), (3, false));
+    });
+}
+
+#[cfg(all(feature="tokio-util", not(target_vendor="apple")))]
+#[test]
+fn tokio_codec_leaves_fd_passing_alone() {
+    use futures_util::StreamExt;
+    use tokio_util::codec::BytesCodec;
+    use uds::nonblocking::UnixSeqpacketConn;
+    use uds::TokioSeqpacketFramed;
+
+    let runtime = Builder::new_current_thread().enable_io().build().expect("create runtime");
+    runtime.block_on(async {
+        let (a, b) = UnixSeqpacketConn::pair().expect("create seqpacket pair");
+        let mut receiver = TokioSeqpacketFramed::new(b, BytesCodec::new()).unwrap();
+        a.send(b"frame").unwrap();
+        assert_eq!(&receiver.next().await.unwrap().expect("receive frame")[..], b"frame");
+        a.send_fds(b"fd", &[a.as_raw_fd()]).unwrap();
+        let (len, truncated, fds) = receiver.get_ref().recv_owned_fds(&mut[0; 10], 1).unwrap();
+        assert_eq!((len, truncated, fds.len()), (2, false, 1));
+    });
+}
+
+#[cfg(not(target_vendor="apple"))]
+#[test]
+fn tokio_seqpacket_split() {
+    let runtime = Builder::new_current_thread().enable_io().build().expect("create runtime");
+    runtime.block_on(async {
+        let (a, b) = TokioSeqpacketConn::pair().expect("create tokio seqpacket pair");
+        let (a_recv, a_send) = a.into_split();
+        let (b_recv, b_send) = b.into_split();
+
+        // receive in one task while sending in another
+        let receiver = tokio::spawn(async move {
+            let mut buf = [0; 10];
+            let mut received = Vec::new();
+            loop {
+                match a_recv.recv(&mut buf).await.expect("receive packet") {
+                    (0, false) => break,
+                    (len, _) => received.push(buf[..len].to_vec()),
+                }
+            }
+            (a_recv, received)
+        });
+        b_send.send(b"one").await.expect("send first packet");
+        b_send.send(b"two").await.expect("send second packet");
+        b_send.shutdown().expect("shut down sending half");
+        let (a_recv, received) = receiver.await.expect("join receiver task");
+        assert_eq!(received, [b"one".to_vec(), b"two".to_vec()]);
+
+        a_send.send(b"back").await.expect("send packet back");
+        let mut buf = [0; 10];
+        assert_eq!(b_recv.recv(&mut buf).await.expect("receive packet back"), (4, false));
+
+        let (a_recv, b_send) = a_recv.reunite(b_send).expect_err("halves of different connections");
+        let a = a_recv.reunite(a_send).expect("reunite halves");
+        let b = b_recv.reunite(b_send).expect("reunite halves");
+        a.send(b"whole").await.expect("send from reunited connection");
+        assert_eq!(b.recv(&mut buf).await.expect("receive on reunited connection"), (5, false));
+    });
+}
+
+#[cfg(not(target_vendor="apple"))]
+#[test]
+fn tokio_seqpacket_poll_methods() {
+    let runtime = Builder::new_current_thread().enable_io().build().expect("create runtime");
+    runtime.block_on(async {
+        let (a, b) = TokioSeqpacketConn::pair().expect("create tokio seqpacket pair");
+        let mut buf = [0; 10];
+        let mut fd_buf = [-1; 2];
+        let pending = poll_fn(|cx| Poll::Ready(b.poll_recv(cx, &mut buf).is_pending()) ).await;
+        assert!(pending, "nothing has been sent yet");
+
+        let fds = [a.get_ref().as_raw_fd()];
+        let sent = poll_fn(|cx| a.poll_send_fds(cx, b"fd", &fds) ).await.expect("send fd");
+        assert_eq!(sent, 2);
+        let received = poll_fn(|cx| b.poll_recv_fds(cx, &mut buf, &mut fd_buf) ).await
+            .expect("receive fd");
+        assert_eq!(received, (2, false, 1));
+        unsafe { libc::close(fd_buf[0]) };
+
+        let (b_recv, _b_send) = b.into_split();
+        poll_fn(|cx| a.poll_send(cx, b"split") ).await.expect("send packet");
+        let received = poll_fn(|cx| b_recv.poll_recv(cx, &mut buf) ).await.expect("receive packet");
+        assert_eq!(received, (5, false));
+    });
+}
+
+#[cfg(not(target_vendor="apple"))]
+#[test]
+fn tokio_seqpacket_std_conversions() {
+    let (a, b) = UnixSeqpacketConn::pair().expect("create seqpacket pair");
+    let runtime = Builder::new_current_thread().enable_io().build().expect("create runtime");
+    let a = runtime.block_on(async {
+        let a = TokioSeqpacketConn::from_std(a).expect("register connection");
+        assert!(a.get_ref().is_nonblocking().unwrap());
+        a.send(b"async").await.expect("send");
+        a.into_std().expect("deregister connection")
+    });
+    assert!(!a.is_nonblocking().unwrap());
+    assert_eq!(b.recv(&mut[0; 10]).expect("receive"), (5, false));
+}
@@ -0,0 +1,97 @@
+#![cfg(all(feature="io-uring", target_os="linux"))]
+
+extern crate uds;
+
+use std::os::unix::io::{FromRawFd, IntoRawFd};
+use std::os::unix::net::UnixDatagram;
+use std::thread;
+use std::time::Duration;
+
+use uds::nonblocking::UnixSeqpacketConn as NonblockingUnixSeqpacketConn;
+use uds::{UnixSeqpacketConn, UnixSeqpacketListener, UnixSocketAddr, UnixDatagramExt, UringBatch};
+
+#[test]
+fn uring_batches_in_order() {
+    let mut uring = UringBatch::new(4).expect("create io_uring");
+    let (a, b) = NonblockingUnixSeqpacketConn::pair().expect("create seqpacket pair");
+    // more than fits in the submission queue
+    let sender = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        let packets = (0..10u8).map(|n| vec![n; n as usize] ).collect::<Vec<_>>();
+        let packets = packets.iter().map(|p| &p[..] ).collect::<Vec<_>>();
+        let mut uring = UringBatch::new(4).expect("create io_uring");
+        let sent = uring.send_all(&a, &packets).expect("send batch");
+        for (n, result) in sent.into_iter().enumerate() {
+            assert_eq!(result.expect("send packet"), n);
+        }
+        a
+    });
+    let mut storage = [[0xffu8; 10]; 10];
+    let mut buffers = storage.iter_mut().map(|b| &mut b[..] ).collect::<Vec<_>>();
+    // waits even though the socket is nonblocking
+    let received = uring.recv_all(&b, &mut buffers).expect("receive batch");
+    for (n, result) in received.into_iter().enumerate() {
+        assert_eq!(result.expect("receive packet"), n);
+        assert_eq!(&buffers[n][..n], &vec![n as u8; n][..]);
+    }
+    drop(sender.join().unwrap());
+    let mut buf = [0; 10];
+    let received = uring.recv_all(&b, &mut[&mut buf]).unwrap();
+    assert_eq!(received[0].as_ref().unwrap(), &0);
+}
+
+#[test]
+fn uring_datagrams_to_address() {
+    let addr = UnixSocketAddr::new_unique().unwrap();
+    let receiver = UnixDatagram::bind_unix_addr(addr).expect("bind");
+    let sender = UnixDatagram::unbound().unwrap();
+    let mut uring = UringBatch::new(8).expect("create io_uring");
+    let sent = match uring.send_all_to(&sender, &[b"a", b"bc"], addr) {
+        Ok(sent) => sent,
+        Err(e) => panic!("send datagrams: {}", e),
+    };
+    if let Err(e) = &sent[0] {
+        // sending to an address requires Linux 6.0
+        assert_eq!(e.raw_os_error(), Some(libc::EINVAL));
+        return;
+    }
+    assert_eq!(sent.into_iter().map(Result::unwrap).collect::<Vec<_>>(), [1, 2]);
+    let mut buf = [0; 4];
+    assert_eq!(receiver.recv(&mut buf).unwrap(), 1);
+    assert_eq!(receiver.recv(&mut buf).unwrap(), 2);
+    if let Some(path) = addr.as_pathname() {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+#[test]
+fn uring_multishot_accept() {
+    let addr = UnixSocketAddr::new_unique().unwrap();
+    let listener = UnixSeqpacketListener::bind_unix_addr(addr).expect("bind");
+    let clients = (0..3).map(|_| UnixSeqpacketConn::connect_unix_addr(addr).expect("connect") )
+        .collect::<Vec<_>>();
+    let mut uring = UringBatch::new(4).expect("create io_uring");
+    let accepted = uring.accept_multiple(&listener, 2).expect("accept");
+    if let Some(Err(e)) = accepted.first() {
+        // multishot accept requires Linux 5.19
+        assert_eq!(e.raw_os_error(), Some(libc::EINVAL));
+        return;
+    }
+    // the third one might have been accepted before canceling
+    assert!(accepted.len() == 2 || accepted.len() == 3);
+    for conn in accepted {
+        let fd = conn.expect("accept connection").into_raw_fd();
+        let conn = unsafe { UnixSeqpacketConn::from_raw_fd(fd) };
+        conn.send(b"hi").expect("send on accepted connection");
+    }
+    drop(clients);
+    if let Some(path) = addr.as_pathname() {
+        let _ = std::fs::remove_file(path);
+    }
+
+    // the ring can be used for batches afterwards
+    let (a, b) = UnixSeqpacketConn::pair().expect("create seqpacket pair");
+    let sent = uring.send_all(&a, &[b"after"]).expect("send after accepting");
+    assert_eq!(*sent[0].as_ref().unwrap(), 5);
+    assert_eq!(b.recv(&mut[0; 10]).unwrap(), (5, false));
+}